@@ -0,0 +1,126 @@
+use crate::double::{answerable, asynchronous, Double};
+use crate::fake::FakeOptions;
+use crate::method::Method;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+// A fake answering with values drawn from proptest's `Arbitrary` impls of what its methods return, with a seeded
+// runner. It only exists in tests, or wherever a `test_only` fake does, where the crate has to have proptest.
+pub(crate) fn arbitrary(double: &Double, options: &FakeOptions) -> TokenStream {
+    let Double {
+        item,
+        methods,
+        instance,
+        associated,
+        attrs,
+        vis,
+        ..
+    } = double;
+    let arbitrary = double.name("Arbitrary");
+    let cfg = options.test_cfg().unwrap_or_else(|| quote!(#[cfg(test)]));
+    let name = arbitrary.to_string();
+    let fns = methods
+        .iter()
+        .map(|method| arbitrary_method(double, method));
+    let (trait_path, lifetimes) = (&instance.trait_path, &instance.lifetimes);
+    let doc = format!(
+        "A fake [`{}`] answering with arbitrary values, which are the same for the same seed.",
+        item.ident
+    );
+    quote! {
+        #cfg
+        #attrs
+        #[doc = #doc]
+        #vis struct #arbitrary {
+            seed: u64,
+            runner: ::std::sync::Mutex<::proptest::test_runner::TestRunner>,
+        }
+
+        #cfg
+        #attrs
+        impl #arbitrary {
+            /// A fake whose answers are drawn with a generator seeded with `seed`.
+            pub fn from_seed(seed: u64) -> Self {
+                let mut bytes = [0; 32];
+                bytes[..8].copy_from_slice(&seed.to_le_bytes());
+                let rng = ::proptest::test_runner::TestRng::from_seed(
+                    ::proptest::test_runner::RngAlgorithm::ChaCha,
+                    &bytes,
+                );
+                #arbitrary {
+                    seed,
+                    runner: ::std::sync::Mutex::new(::proptest::test_runner::TestRunner::new_with_rng(
+                        ::std::default::Default::default(),
+                        rng,
+                    )),
+                }
+            }
+
+            // The next arbitrary value of a `T`
+            fn draw<T: ::proptest::arbitrary::Arbitrary>(&self) -> T {
+                let mut runner = self
+                    .runner
+                    .lock()
+                    .unwrap_or_else(::std::sync::PoisonError::into_inner);
+                let tree = ::proptest::strategy::Strategy::new_tree(&::proptest::arbitrary::any::<T>(), &mut runner);
+                ::proptest::strategy::ValueTree::current(&tree.unwrap())
+            }
+        }
+
+        #cfg
+        #attrs
+        impl ::core::fmt::Debug for #arbitrary {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.debug_struct(#name).field("seed", &self.seed).finish()
+            }
+        }
+
+        // Each case gets a fake of its own, whose seed proptest can replay
+        #cfg
+        #attrs
+        impl ::proptest::arbitrary::Arbitrary for #arbitrary {
+            type Parameters = ();
+            type Strategy = ::proptest::strategy::BoxedStrategy<Self>;
+
+            fn arbitrary_with((): ()) -> Self::Strategy {
+                let seeds = ::proptest::arbitrary::any::<u64>();
+                ::proptest::strategy::Strategy::boxed(::proptest::strategy::Strategy::prop_map(seeds, Self::from_seed))
+            }
+        }
+
+        #cfg
+        #attrs
+        impl<#(#lifetimes),*> #trait_path for #arbitrary {
+            #(#associated)*
+            #(#fns)*
+        }
+    }
+}
+
+fn arbitrary_method(double: &Double, method: &Method) -> TokenStream {
+    let mut sig = double.sig(method);
+    let output = double.output(method);
+    let (eager, value) = match &output {
+        None => (TokenStream::new(), TokenStream::new()),
+        Some(output) if method.receiver && answerable(method, Some(output)) => {
+            (quote!(let value = self.draw();), quote!(value))
+        }
+        Some(_) => {
+            let message = format!(
+                "`{}` can't return anything from `{}`",
+                double.name("Arbitrary"),
+                method.sig.ident
+            );
+            (TokenStream::new(), quote!(::core::panic!(#message)))
+        }
+    };
+    let cfgs = &method.cfgs;
+    let body = asynchronous(method, &mut sig, output.as_ref(), eager, value);
+    quote! {
+        #(#cfgs)*
+        #[allow(unused_variables)]
+        #sig {
+            #body
+        }
+    }
+}
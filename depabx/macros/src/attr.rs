@@ -0,0 +1,146 @@
+use proc_macro2::TokenStream;
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::token::Paren;
+use syn::{parenthesized, Attribute, Expr, Ident, Lit, LitStr, Token, Type};
+
+// One comma-separated entry of `#[wrap(...)]` or `#[abx(...)]`: a bare flag like `fake`, a `key = value` pair, a list
+// like `cfg(not(test))`, or the binding of an associated item.
+pub(crate) enum Entry {
+    Flag(Ident),
+    Value(Ident, Expr),
+    List(Ident, TokenStream),
+    // `type Output = Hits`
+    Type(Ident, Type),
+    // `const MAX_BATCH = Uploader::MAX_BATCH`
+    Const(Ident, Expr),
+}
+
+impl Entry {
+    pub(crate) fn name(&self) -> &Ident {
+        match self {
+            Entry::Flag(name)
+            | Entry::Value(name, _)
+            | Entry::List(name, _)
+            | Entry::Type(name, _)
+            | Entry::Const(name, _) => name,
+        }
+    }
+
+    // Whether the next tokens are an entry rather than, in `#[wrap]`, a target type. Options are lowercase, while
+    // target types are either capitalized or paths, which continue with `::` after their first segment.
+    pub(crate) fn peek(input: ParseStream) -> bool {
+        let fork = input.fork();
+        let Ok(name) = Ident::parse_any(&fork) else {
+            return false;
+        };
+        let lowercase = name
+            .to_string()
+            .starts_with(|c: char| c.is_ascii_lowercase());
+        lowercase
+            && (fork.is_empty() || fork.peek(Token![,]) || fork.peek(Token![=]) || fork.peek(Paren))
+            && !(name == "crate" || name == "self" || name == "super" || name == "fn")
+    }
+}
+
+impl Parse for Entry {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(Token![type]) {
+            input.parse::<Token![type]>()?;
+            let name = input.parse()?;
+            input.parse::<Token![=]>()?;
+            return Ok(Entry::Type(name, input.parse()?));
+        }
+        if input.peek(Token![const]) {
+            input.parse::<Token![const]>()?;
+            let name = input.parse()?;
+            input.parse::<Token![=]>()?;
+            return Ok(Entry::Const(name, input.parse()?));
+        }
+        let name = Ident::parse_any(input)?;
+        if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            Ok(Entry::Value(name, input.parse()?))
+        } else if input.peek(Paren) {
+            let content;
+            parenthesized!(content in input);
+            Ok(Entry::List(name, content.parse()?))
+        } else {
+            Ok(Entry::Flag(name))
+        }
+    }
+}
+
+// All the entries of the `#[abx(...)]` attributes in `attrs`, which are removed since they only mean something to the
+// macro.
+pub(crate) fn take_abx_entries(attrs: &mut Vec<Attribute>) -> syn::Result<Vec<Entry>> {
+    let mut entries = Vec::new();
+    let mut result = Ok(());
+    attrs.retain(|attr| {
+        if !attr.path().is_ident("abx") {
+            return true;
+        }
+        match attr.parse_args_with(Punctuated::<Entry, Token![,]>::parse_terminated) {
+            Ok(parsed) => entries.extend(parsed),
+            Err(e) => result = Err(e),
+        }
+        false
+    });
+    result.map(|()| entries)
+}
+
+pub(crate) fn unknown(entry: &Entry, what: &str) -> syn::Error {
+    let message = match entry {
+        Entry::Type(name, _) => {
+            format!("`type {name} = ...` binds an associated type, so it goes on the trait")
+        }
+        _ => format!("unknown {what} option `{}`", entry.name()),
+    };
+    syn::Error::new(entry.name().span(), message)
+}
+
+pub(crate) fn string(entry: &Entry) -> syn::Result<LitStr> {
+    match entry {
+        Entry::Value(_, Expr::Lit(lit)) => match &lit.lit {
+            Lit::Str(s) => Ok(s.clone()),
+            other => Err(syn::Error::new(other.span(), "expected a string")),
+        },
+        _ => Err(syn::Error::new(
+            entry.name().span(),
+            format!("expected `{} = \"...\"`", entry.name()),
+        )),
+    }
+}
+
+pub(crate) fn ident(entry: &Entry) -> syn::Result<Ident> {
+    match entry {
+        Entry::Value(_, Expr::Path(path)) if path.path.get_ident().is_some() => {
+            Ok(path.path.segments[0].ident.clone())
+        }
+        _ => Err(syn::Error::new(
+            entry.name().span(),
+            format!("expected `{} = name`", entry.name()),
+        )),
+    }
+}
+
+pub(crate) fn list(entry: &Entry) -> syn::Result<TokenStream> {
+    match entry {
+        Entry::List(_, tokens) => Ok(tokens.clone()),
+        _ => Err(syn::Error::new(
+            entry.name().span(),
+            format!("expected `{}(...)`", entry.name()),
+        )),
+    }
+}
+
+pub(crate) fn flag(entry: &Entry) -> syn::Result<()> {
+    match entry {
+        Entry::Flag(_) => Ok(()),
+        _ => Err(syn::Error::new(
+            entry.name().span(),
+            format!("`{}` doesn't take a value", entry.name()),
+        )),
+    }
+}
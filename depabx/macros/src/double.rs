@@ -0,0 +1,374 @@
+use crate::method::{Arg, Asyncness, Method};
+use crate::target::Instance;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::visit::{self, Visit};
+use syn::visit_mut::{self, VisitMut};
+use syn::{
+    parse_quote, FnArg, GenericParam, Ident, ItemTrait, Lifetime, ReturnType, Signature, Type,
+    TypeParamBound, TypeReference, Visibility, WherePredicate,
+};
+
+// What every generated test double is built from: the trait as the macro rewrote it, and the instance of it the
+// first target implements.
+#[derive(Clone)]
+pub(crate) struct Double<'a> {
+    pub item: &'a ItemTrait,
+    pub methods: &'a [Method],
+    pub instance: Instance,
+    // The type the trait is implemented on, if a double can hold it, and the `#[cfg]` that impl is under
+    pub real: Option<Type>,
+    pub impl_cfg: Option<TokenStream>,
+    // The impl's associated types and consts, and the types bound to them
+    pub associated: &'a [TokenStream],
+    pub bindings: &'a [(Ident, Type)],
+    // `#[doc(hidden)]`, when generated items are hidden
+    pub attrs: TokenStream,
+    pub vis: &'a Visibility,
+    pub prefix: &'a str,
+}
+
+impl Double<'_> {
+    // The name of a generated double: `AbxLogger` becomes `FakeLogger` for a fake, or `StubLogger` for a stub
+    pub(crate) fn name(&self, kind: &str) -> Ident {
+        let name = self.item.ident.to_string();
+        let base = name.strip_prefix("Abx").unwrap_or(&name);
+        format_ident!("{kind}{base}", span = self.item.ident.span())
+    }
+
+    // What the double's own methods for a trait method are named after: `abx_page_view` gives `page_view_calls()`
+    pub(crate) fn short(&self, method: &Method) -> Ident {
+        let name = method.sig.ident.to_string();
+        let short = name.strip_prefix(self.prefix).unwrap_or(&name);
+        format_ident!("{short}", span = method.sig.ident.span())
+    }
+
+    // Whether the double has to be `Send + Sync`, because the trait is or one of its futures is `Send`
+    pub(crate) fn shared(&self) -> bool {
+        let supertrait = self.item.supertraits.iter().any(|bound| match bound {
+            TypeParamBound::Trait(bound) => bound
+                .path
+                .segments
+                .last()
+                .is_some_and(|segment| segment.ident == "Send" || segment.ident == "Sync"),
+            _ => false,
+        });
+        supertrait || self.methods.iter().any(|method| method.options.send)
+    }
+
+    // How a fake keeps the arguments of a call: the type they're captured as, `C`, and the expression capturing them.
+    // That's the argument itself for a method with one, and a tuple of them otherwise.
+    pub(crate) fn capture(&self, method: &Method, self_ty: &Type) -> (Type, TokenStream) {
+        let (types, values): (Vec<_>, Vec<_>) = typed_args(method)
+            .map(|(ty, arg)| self.capture_arg(method, ty, arg, self_ty))
+            .unzip();
+        match (types.as_slice(), values.as_slice()) {
+            ([ty], [value]) => (ty.clone(), value.clone()),
+            _ => (parse_quote!((#(#types,)*)), quote!((#(#values,)*))),
+        }
+    }
+
+    // Borrowed arguments are captured as what `ToOwned` makes of them, and others are cloned. Arguments whose type
+    // can't be named outside the call, or borrows from it, are kept as their `Debug` string, and so are those marked
+    // `#[abx(capture = "debug")]`.
+    fn capture_arg(
+        &self,
+        method: &Method,
+        ty: &Type,
+        arg: &Arg,
+        self_ty: &Type,
+    ) -> (Type, TokenStream) {
+        let name = &arg.name;
+        if arg.capture_debug {
+            return (
+                parse_quote!(::std::string::String),
+                quote!(::std::format!("{:?}", #name)),
+            );
+        }
+        let debug = (
+            parse_quote!(::std::string::String),
+            recorded(method, ty, name),
+        );
+        if let Type::Reference(reference) = ty {
+            if Opaque::find(&reference.elem, method).any() {
+                return debug;
+            }
+            let elem = self.outside(&reference.elem, self_ty);
+            return (
+                parse_quote!(<#elem as ::std::borrow::ToOwned>::Owned),
+                quote!(::std::borrow::ToOwned::to_owned(&*#name)),
+            );
+        }
+        if Opaque::find(ty, method).any() {
+            return debug;
+        }
+        (
+            self.outside(ty, self_ty),
+            quote!(::core::clone::Clone::clone(&#name)),
+        )
+    }
+
+    // The trait method's signature as the double implements it
+    pub(crate) fn sig(&self, method: &Method) -> Signature {
+        let mut sig = method.sig.clone();
+        self.instance.fill(&mut sig);
+        sig
+    }
+
+    // A type from the trait's signatures as the double's own items can write it, with `Self` being `double`
+    pub(crate) fn outside(&self, ty: &Type, double: &Type) -> Type {
+        let mut ty = ty.clone();
+        self.instance.fill_type(&mut ty);
+        let mut outside = Outside {
+            double,
+            trait_path: &self.instance.trait_path,
+            bindings: self.bindings,
+        };
+        outside.visit_type_mut(&mut ty);
+        ty
+    }
+
+    // The type a call of the method produces, unless it's `()`: its return type, or the output of its future
+    pub(crate) fn output(&self, method: &Method) -> Option<Type> {
+        let ReturnType::Type(_, ty) = &method.output else {
+            return None;
+        };
+        if matches!(&**ty, Type::Tuple(tuple) if tuple.elems.is_empty()) {
+            return None;
+        }
+        let mut ty = (**ty).clone();
+        self.instance.fill_type(&mut ty);
+        Some(ty)
+    }
+}
+
+// Replaces `Self` with the double, and its associated types with what they're bound to
+struct Outside<'a> {
+    double: &'a Type,
+    trait_path: &'a TokenStream,
+    bindings: &'a [(Ident, Type)],
+}
+
+impl VisitMut for Outside<'_> {
+    fn visit_type_mut(&mut self, ty: &mut Type) {
+        if let Type::Path(path) = ty {
+            let segments = &path.path.segments;
+            if path.qself.is_none() && segments[0].ident == "Self" {
+                let double = self.double;
+                *ty = match segments.iter().nth(1) {
+                    None => double.clone(),
+                    Some(assoc) => {
+                        match self.bindings.iter().find(|(name, _)| *name == assoc.ident) {
+                            Some((_, bound)) if segments.len() == 2 => bound.clone(),
+                            _ => {
+                                let trait_path = self.trait_path;
+                                let rest = segments.iter().skip(1);
+                                parse_quote!(<#double as #trait_path>::#(#rest)::*)
+                            }
+                        }
+                    }
+                };
+                return;
+            }
+        }
+        visit_mut::visit_type_mut(self, ty);
+    }
+}
+
+// The typed arguments of the method, with their types
+pub(crate) fn typed_args(method: &Method) -> impl Iterator<Item = (&Type, &Arg)> {
+    let types = method.sig.inputs.iter().filter_map(|input| match input {
+        FnArg::Typed(typed) => Some(&*typed.ty),
+        FnArg::Receiver(_) => None,
+    });
+    types.zip(&method.args)
+}
+
+// How an argument is recorded: formatted with `Debug`, or for an argument of a generic type, whatever its bounds
+// allow. That's `to_string()` for a `Display` one, `{:?}` for a `Debug` one, and its type name otherwise.
+pub(crate) fn recorded(method: &Method, ty: &Type, name: &Ident) -> TokenStream {
+    match form(method, ty) {
+        Form::Debug => quote!(::std::format!("{:?}", #name)),
+        Form::Display => quote!(::std::string::ToString::to_string(&#name)),
+        Form::TypeName => {
+            quote!(::std::string::ToString::to_string(::core::any::type_name_of_val(&#name)))
+        }
+    }
+}
+
+// Whether an argument is recorded with `to_string()`, which `verify_sequence!` has to know to format the argument it
+// expects the same way
+pub(crate) fn displayed(method: &Method, ty: &Type) -> bool {
+    matches!(form(method, ty), Form::Display)
+}
+
+enum Form {
+    Debug,
+    Display,
+    TypeName,
+}
+
+fn form(method: &Method, ty: &Type) -> Form {
+    let mut ty = ty;
+    while let Type::Reference(reference) = ty {
+        ty = &reference.elem;
+    }
+    let bounds: Vec<&TypeParamBound> = match ty {
+        Type::ImplTrait(ty) => ty.bounds.iter().collect(),
+        Type::Path(path) => match path.path.get_ident() {
+            Some(param) => param_bounds(method, param),
+            None => Vec::new(),
+        },
+        _ => Vec::new(),
+    };
+    let bounded = |name: &str| {
+        bounds.iter().any(|bound| match bound {
+            TypeParamBound::Trait(bound) => bound
+                .path
+                .segments
+                .last()
+                .is_some_and(|segment| segment.ident == name),
+            _ => false,
+        })
+    };
+    let generic =
+        matches!(ty, Type::ImplTrait(_)) || !bounds.is_empty() || Opaque::find(ty, method).generic;
+    if !generic || bounded("Debug") && !bounded("Display") {
+        Form::Debug
+    } else if bounded("Display") {
+        Form::Display
+    } else {
+        Form::TypeName
+    }
+}
+
+// The bounds on one of the method's type parameters, declared with it or in the `where` clause
+fn param_bounds<'a>(method: &'a Method, param: &Ident) -> Vec<&'a TypeParamBound> {
+    let generics = &method.sig.generics;
+    let declared = generics
+        .type_params()
+        .filter(|declared| declared.ident == *param)
+        .flat_map(|declared| &declared.bounds);
+    let predicates = generics
+        .where_clause
+        .iter()
+        .flat_map(|clause| &clause.predicates);
+    let constrained = predicates
+        .filter_map(|predicate| match predicate {
+            WherePredicate::Type(predicate) => Some(predicate),
+            _ => None,
+        })
+        .filter(
+            |predicate| matches!(&predicate.bounded_ty, Type::Path(ty) if ty.path.is_ident(param)),
+        )
+        .flat_map(|predicate| &predicate.bounds);
+    declared.chain(constrained).collect()
+}
+
+// Whether a closure or a stored value can stand for the method's results, which rules out results borrowing from
+// the call, `impl Trait`, and the method's own type parameters
+pub(crate) fn answerable(method: &Method, output: Option<&Type>) -> bool {
+    let generic = method
+        .sig
+        .generics
+        .params
+        .iter()
+        .any(|param| !matches!(param, GenericParam::Lifetime(_)));
+    let impl_args = method.sig.inputs.iter().any(|input| match input {
+        FnArg::Typed(typed) => Opaque::find(&typed.ty, method).impl_trait,
+        FnArg::Receiver(_) => false,
+    });
+    let output = output.map_or(Opaque::default(), |ty| Opaque::find(ty, method));
+    !generic && !impl_args && !output.impl_trait && !output.borrows
+}
+
+// Whether the type is or holds an `impl Trait`, which the body of a method returning it can't only panic in
+pub(crate) fn opaque(method: &Method, ty: &Type) -> bool {
+    Opaque::find(ty, method).impl_trait
+}
+
+// What a type holds that a stored value can't
+#[derive(Default)]
+struct Opaque {
+    impl_trait: bool,
+    // A lifetime other than `'static`
+    borrows: bool,
+    // One of the method's type parameters
+    generic: bool,
+    params: Vec<Ident>,
+}
+
+impl Opaque {
+    fn find(ty: &Type, method: &Method) -> Self {
+        let params = method.sig.generics.type_params();
+        let mut opaque = Opaque {
+            params: params.map(|param| param.ident.clone()).collect(),
+            ..Opaque::default()
+        };
+        opaque.visit_type(ty);
+        opaque
+    }
+
+    fn any(&self) -> bool {
+        self.impl_trait || self.borrows || self.generic
+    }
+}
+
+impl<'ast> Visit<'ast> for Opaque {
+    fn visit_type(&mut self, ty: &'ast Type) {
+        match ty {
+            Type::ImplTrait(_) => self.impl_trait = true,
+            Type::Path(path) => {
+                let param = path.path.get_ident();
+                self.generic |= param.is_some_and(|param| self.params.contains(param));
+            }
+            _ => {}
+        }
+        visit::visit_type(self, ty);
+    }
+
+    fn visit_type_reference(&mut self, reference: &'ast TypeReference) {
+        if reference.lifetime.is_none() {
+            self.borrows = true;
+        }
+        visit::visit_type_reference(self, reference);
+    }
+
+    fn visit_lifetime(&mut self, lifetime: &'ast Lifetime) {
+        if lifetime.ident != "static" {
+            self.borrows = true;
+        }
+    }
+}
+
+// The body of a double's method, which runs `eager` when it's called and then produces `value`. An async method
+// returns a future of the value instead, so its `async fn` becomes a plain `fn` returning `impl Future`, and `eager`
+// still runs at the call rather than when the future is first polled.
+pub(crate) fn asynchronous(
+    method: &Method,
+    sig: &mut Signature,
+    output: Option<&Type>,
+    eager: TokenStream,
+    value: TokenStream,
+) -> TokenStream {
+    match method.asyncness {
+        Asyncness::Sync => quote! {
+            #eager
+            #value
+        },
+        Asyncness::Native => {
+            let output = output.map_or_else(|| quote!(()), |ty| quote!(#ty));
+            let send = method.options.send.then(|| quote!(+ ::core::marker::Send));
+            sig.asyncness = None;
+            sig.output = parse_quote!(-> impl ::core::future::Future<Output = #output> #send);
+            quote! {
+                #eager
+                async move { #value }
+            }
+        }
+        Asyncness::Boxed => quote! {
+            #eager
+            ::std::boxed::Box::pin(async move { #value })
+        },
+    }
+}
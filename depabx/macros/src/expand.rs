@@ -0,0 +1,675 @@
+use crate::arbitrary;
+use crate::attr::{self, Entry};
+use crate::double::Double;
+use crate::fake;
+use crate::method::{Method, Route};
+use crate::noop;
+use crate::options::{Futures, TraitOptions, WrapOptions};
+use crate::spy;
+use crate::store;
+use crate::stub;
+use crate::target::{self, Instance};
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use std::fs;
+use std::path::PathBuf;
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{
+    parse_quote, FnArg, GenericParam, Ident, ItemTrait, ReturnType, Signature, TraitItem, Type,
+    TypeParamBound, Visibility, WherePredicate,
+};
+
+// `#[wrap]` on a trait: the trait itself, cleaned of `#[abx]` attributes, followed by its impl for each target.
+pub(crate) fn wrap(options: WrapOptions, mut item: ItemTrait) -> syn::Result<TokenStream> {
+    if options.targets.is_empty() {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "expected the concrete type to wrap, as in `#[wrap(Logger)]`",
+        ));
+    }
+    let trait_options = TraitOptions::parse(attr::take_abx_entries(&mut item.attrs)?)?;
+    // Listed methods are declared as if they'd been written out, each calling the method it was listed as
+    for hint in &options.methods {
+        let mut sig = hint.clone();
+        let target = sig.ident.to_string();
+        sig.ident = format_ident!(
+            "{}{}",
+            options.naming.prefix,
+            sig.ident,
+            span = sig.ident.span()
+        );
+        item.items.push(parse_quote! {
+            #[abx(target = #target)]
+            #sig;
+        });
+    }
+    let ext = match options.ext {
+        true => Some(extension_trait(&mut item)?),
+        false => None,
+    };
+    let associated = associated_items(&item, &trait_options)?;
+
+    // A newtype is delegated through like any other field
+    let via = match options.newtype {
+        Some(_) => Some(parse_quote!(0)),
+        None => options.via.clone(),
+    };
+    let mut methods = Vec::new();
+    for trait_item in &mut item.items {
+        let TraitItem::Fn(f) = trait_item else {
+            continue;
+        };
+        let Some(mut method) = Method::parse(f, &options.naming)? else {
+            continue;
+        };
+        if options.deref
+            && matches!(method.sig.receiver(), Some(receiver) if receiver.reference.is_none())
+        {
+            return Err(syn::Error::new(
+                method.sig.ident.span(),
+                format!(
+                    "`deref` can't forward `{}`, since it takes `self` out of the pointer",
+                    method.sig.ident
+                ),
+            ));
+        }
+        if let (Some(via), false) = (&via, method.receiver) {
+            return Err(syn::Error::new(
+                method.sig.ident.span(),
+                format!(
+                    "`{}` has no `self` to reach the `{}` field through",
+                    method.sig.ident,
+                    via.to_token_stream()
+                ),
+            ));
+        }
+        if options.strict {
+            method.reject_adapters()?;
+        }
+        method.options.send |= trait_options.send;
+        if options.futures == Futures::Boxed {
+            method.box_future(f);
+        } else {
+            method.send_future(f);
+        }
+        methods.push(method);
+    }
+
+    if options.object_safe {
+        object_safety(&item)?;
+    }
+
+    if options.send_sync {
+        // Added as supertraits, they're checked against each target like any other
+        let missing: Vec<TypeParamBound> = [
+            (!bounds_by(&item, "Send")).then(|| parse_quote!(::core::marker::Send)),
+            (!bounds_by(&item, "Sync")).then(|| parse_quote!(::core::marker::Sync)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        item.supertraits.extend(missing);
+    }
+
+    let name = &item.ident;
+    if !item
+        .attrs
+        .iter()
+        .any(|attr| attr.path().segments[0].ident == "diagnostic")
+    {
+        // Most often the trait is missing because the type was never wrapped, for instance a vendor type returned
+        // as `impl AbxXxx` from another wrapped method
+        let message = format!("`{{Self}}` doesn't implement `{name}`");
+        let note = format!("`#[wrap({{Self}})]` on `{name}` would implement it by delegating to `{{Self}}`'s methods");
+        item.attrs
+            .push(parse_quote!(#[diagnostic::on_unimplemented(message = #message, note = #note)]));
+    }
+    let glue_attrs = options.glue_attrs();
+    // Inside a module, items as private as the trait still have to be reachable from where the trait is
+    let vis = match (&options.vis, &options.module, &item.vis) {
+        (Some(vis), _, _) => vis.clone(),
+        (None, Some(_), Visibility::Inherited) => parse_quote!(pub(super)),
+        (None, _, vis) => vis.clone(),
+    };
+    let mut impls = Vec::new();
+    for target in &options.targets {
+        let instance = target::instance(&item, target)?;
+        let (trait_path, impl_generics) = (&instance.trait_path, &instance.impl_generics);
+        let self_ty = match &options.newtype {
+            Some(newtype) => {
+                impls.push(quote! {
+                    #glue_attrs
+                    #vis struct #newtype #impl_generics (#vis #target);
+
+                    #glue_attrs
+                    impl #impl_generics ::core::convert::From<#target> for #newtype #impl_generics {
+                        fn from(inner: #target) -> Self {
+                            #newtype(inner)
+                        }
+                    }
+                });
+                parse_quote!(#newtype #impl_generics)
+            }
+            None => target.clone(),
+        };
+        let route = via.as_ref().map_or(Route::Target, Route::Field);
+        let fns = methods
+            .iter()
+            .map(|method| method.delegate(target, &instance, route, options.strict));
+        let checks = supertrait_checks(&item, &self_ty, &instance, &glue_attrs);
+        if options.role {
+            impls.push(role_markers(&item, target, &methods, &glue_attrs));
+        }
+        impls.push(quote! {
+            #checks
+            #glue_attrs
+            impl #impl_generics #trait_path for #self_ty {
+                #(#associated)*
+                #(#fns)*
+            }
+        });
+        if options.deref {
+            impls.push(deref_impl(
+                target,
+                &instance,
+                &methods,
+                &associated,
+                &glue_attrs,
+            ));
+        }
+    }
+    if let Some(target) = options.targets.first() {
+        let instance = target::instance(&item, target)?;
+        // The type the trait is implemented on, which partial fakes and spies hold. A target with lifetimes can't be
+        // held by a fake, which has none.
+        let real: Type = match &options.newtype {
+            Some(newtype) => {
+                let generics = &instance.impl_generics;
+                parse_quote!(#newtype #generics)
+            }
+            None => target.clone(),
+        };
+        let double = Double {
+            item: &item,
+            methods: &methods,
+            real: instance.lifetimes.is_empty().then(|| real.clone()),
+            impl_cfg: options.impl_cfg(),
+            instance,
+            associated: &associated,
+            bindings: &trait_options.types,
+            attrs: options.double_attrs(),
+            vis: &vis,
+            prefix: &options.naming.prefix,
+        };
+        if let Some(fake) = &options.fake {
+            // `fake(type Output = String)` binds the fake's associated types apart from the impls'
+            let mut bound = trait_options.clone();
+            bound
+                .types
+                .retain(|(name, _)| fake.types.iter().all(|(overridden, _)| name != overridden));
+            bound.types.extend(fake.types.iter().cloned());
+            let associated = associated_items(&item, &bound)?;
+            let double = Double {
+                associated: &associated,
+                bindings: &bound.types,
+                // The real impl's methods don't return the fake's types, so there's nothing to fall through to
+                real: double
+                    .real
+                    .clone()
+                    .filter(|_| fake.types.is_empty() && !fake.clone),
+                ..double.clone()
+            };
+            impls.push(fake::fake(&double, fake));
+            if fake.arbitrary {
+                impls.push(arbitrary::arbitrary(&double, fake));
+            }
+        }
+        if let Some(mode) = options.stub {
+            impls.push(stub::stub(&double, mode));
+        }
+        if options.store {
+            impls.push(store::store(&double)?);
+        }
+        if options.noop {
+            impls.push(noop::noop(&double)?);
+        }
+        if options.spy {
+            // The spy calls the real impl, so it exists only when that does
+            let double = Double {
+                attrs: glue_attrs,
+                ..double
+            };
+            impls.push(spy::spy(&double, &real));
+        }
+    }
+    let generated = match &options.module {
+        Some(module) => {
+            let vis = &item.vis;
+            quote! {
+                #vis mod #module {
+                    #[allow(unused_imports)]
+                    use super::*;
+                    #(#impls)*
+                }
+            }
+        }
+        None => quote!(#(#impls)*),
+    };
+    let expansion = quote! {
+        #item
+        #ext
+        #generated
+    };
+    dump(&options, &item.ident, &expansion)?;
+    Ok(expansion)
+}
+
+// `dump = "path"`, or `DEPABX_DUMP_DIR` for every trait: writes the expansion to a file as formatted Rust. Relative
+// paths are taken from the root of the crate being compiled.
+fn dump(options: &WrapOptions, name: &Ident, expansion: &TokenStream) -> syn::Result<()> {
+    let (path, span) = match (&options.dump, std::env::var_os("DEPABX_DUMP_DIR")) {
+        (Some(path), _) => (PathBuf::from(path.value()), path.span()),
+        (None, Some(dir)) => (PathBuf::from(dir).join(format!("{name}.rs")), name.span()),
+        (None, None) => return Ok(()),
+    };
+    let path = match std::env::var_os("CARGO_MANIFEST_DIR") {
+        Some(root) => PathBuf::from(root).join(path),
+        None => path,
+    };
+    let code = prettyplease::unparse(&syn::parse2(expansion.clone())?);
+    let written = match path.parent() {
+        Some(parent) => fs::create_dir_all(parent).and_then(|()| fs::write(&path, code)),
+        None => fs::write(&path, code),
+    };
+    written.map_err(|e| {
+        syn::Error::new(
+            span,
+            format!("couldn't dump the expansion to `{}`: {e}", path.display()),
+        )
+    })
+}
+
+// `ext`: moves the `#[abx(ext)]` methods out of the trait into `<Trait>Ext`, which has the trait as its supertrait
+// and is implemented for everything that implements the trait
+fn extension_trait(item: &mut ItemTrait) -> syn::Result<TokenStream> {
+    let mut moved = Vec::new();
+    let mut kept = Vec::new();
+    for trait_item in std::mem::take(&mut item.items) {
+        let TraitItem::Fn(mut f) = trait_item else {
+            kept.push(trait_item);
+            continue;
+        };
+        let mut attrs = f.attrs.clone();
+        let entries = attr::take_abx_entries(&mut attrs)?;
+        if !entries
+            .iter()
+            .any(|entry| matches!(entry, Entry::Flag(name) if name == "ext"))
+        {
+            kept.push(TraitItem::Fn(f));
+            continue;
+        }
+        if let Some(entry) = entries.iter().find(|entry| entry.name() != "ext") {
+            return Err(syn::Error::new(
+                entry.name().span(),
+                format!("`{}` has no effect on an extension method", entry.name()),
+            ));
+        }
+        if f.default.is_none() {
+            return Err(syn::Error::new(
+                f.sig.ident.span(),
+                "an extension method needs a body, since nothing implements it but the blanket impl",
+            ));
+        }
+        f.attrs = attrs;
+        moved.push(f);
+    }
+    item.items = kept;
+
+    let (vis, name) = (&item.vis, &item.ident);
+    let ext = format_ident!("{}Ext", name);
+    let params: Vec<_> = item.generics.params.iter().collect();
+    let (_, ty_generics, where_clause) = item.generics.split_for_impl();
+    let doc = format!("Helpers for every implementer of [`{name}`].");
+    Ok(quote! {
+        #[doc = #doc]
+        #vis trait #ext <#(#params),*>: #name #ty_generics #where_clause {
+            #(#moved)*
+        }
+
+        impl<#(#params,)* __AbxSelf: #name #ty_generics + ?::core::marker::Sized> #ext #ty_generics for __AbxSelf
+        #where_clause
+        {}
+    })
+}
+
+// `role`: one marker per method of the trait, named after the target and the method. Two roles over the same target
+// that both declare a method define the same marker twice, which fails the build at both traits rather than leaving
+// every call to the method ambiguous. The markers are items of the module the traits are in, so roles in different
+// modules aren't checked against each other.
+fn role_markers(
+    item: &ItemTrait,
+    target: &Type,
+    methods: &[Method],
+    glue_attrs: &TokenStream,
+) -> TokenStream {
+    // The target as written after its path, generic arguments included, so `Cache<u32>` and `Cache<String>` get
+    // markers of their own
+    let written = match target {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .map(ToTokens::to_token_stream)
+            .unwrap_or_default(),
+        _ => target.to_token_stream(),
+    };
+    let mut target = String::new();
+    for c in written.to_string().chars() {
+        match c.is_alphanumeric() {
+            true => target.push(c),
+            false if !target.ends_with('_') => target.push('_'),
+            false => {}
+        }
+    }
+    let target = target.trim_end_matches('_');
+    let markers = methods.iter().map(|method| {
+        let marker = format_ident!(
+            "__AbxRole_{}_{}",
+            target,
+            method.sig.ident,
+            span = item.ident.span()
+        );
+        quote_spanned! {item.ident.span()=>
+            #glue_attrs
+            #[allow(non_camel_case_types, dead_code)]
+            struct #marker;
+        }
+    });
+    quote!(#(#markers)*)
+}
+
+// `deref`: the impls for references and the standard smart pointers to the target, calling the concrete methods on
+// `&**self`. They're written out one by one: a blanket impl over `Deref<Target = T>` would overlap the direct impl
+// whenever `T` is another crate's type, since the compiler can't rule out that it derefs to itself.
+fn deref_impl(
+    target: &Type,
+    instance: &Instance,
+    methods: &[Method],
+    associated: &[TokenStream],
+    glue_attrs: &TokenStream,
+) -> TokenStream {
+    let mutable = methods.iter().any(
+        |method| matches!(method.sig.receiver(), Some(receiver) if receiver.mutability.is_some()),
+    );
+    // Shared pointers can't lend out `&mut`, so a `&mut self` method leaves only the exclusive ones
+    let pointers: Vec<TokenStream> = match mutable {
+        true => vec![
+            quote!(&'__abx mut #target),
+            quote!(::std::boxed::Box<#target>),
+        ],
+        false => vec![
+            quote!(&'__abx #target),
+            quote!(&'__abx mut #target),
+            quote!(::std::boxed::Box<#target>),
+            quote!(::std::rc::Rc<#target>),
+            quote!(::std::sync::Arc<#target>),
+        ],
+    };
+    let lifetimes = &instance.lifetimes;
+    let trait_path = &instance.trait_path;
+    let impls = pointers.iter().map(|pointer| {
+        let fns = methods
+            .iter()
+            .map(|method| method.delegate(target, instance, Route::Deref, false));
+        quote! {
+            #glue_attrs
+            impl<'__abx, #(#lifetimes),*> #trait_path for #pointer {
+                #(#associated)*
+                #(#fns)*
+            }
+        }
+    });
+    quote!(#(#impls)*)
+}
+
+// The associated types and consts of the impl, as bound by the trait's `#[abx(type ...)]` and `#[abx(const ...)]`
+fn associated_items(item: &ItemTrait, options: &TraitOptions) -> syn::Result<Vec<TokenStream>> {
+    let mut associated = Vec::new();
+    for trait_item in &item.items {
+        match trait_item {
+            TraitItem::Type(ty) => {
+                let name = &ty.ident;
+                match options.types.iter().find(|(bound, _)| bound == name) {
+                    Some((_, bound)) => associated.push(quote!(type #name = #bound;)),
+                    None if ty.default.is_none() => {
+                        return Err(syn::Error::new(
+                            name.span(),
+                            format!("bind `{name}` to the concrete type's own type with `#[abx(type {name} = ...)]`"),
+                        ))
+                    }
+                    None => {}
+                }
+            }
+            // Unlike a type, a const repeats its type from the trait
+            TraitItem::Const(c) => {
+                let (name, ty) = (&c.ident, &c.ty);
+                match options.consts.iter().find(|(bound, _)| bound == name) {
+                    Some((_, value)) => associated.push(quote!(const #name: #ty = #value;)),
+                    None if c.default.is_none() => {
+                        return Err(syn::Error::new(
+                            name.span(),
+                            format!("give `{name}` a value with `#[abx(const {name} = ...)]`"),
+                        ))
+                    }
+                    None => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let declared = |name: &Ident| {
+        item.items.iter().any(|trait_item| match trait_item {
+            TraitItem::Type(ty) => ty.ident == *name,
+            TraitItem::Const(c) => c.ident == *name,
+            _ => false,
+        })
+    };
+    let bindings = options.types.iter().map(|(name, _)| name);
+    if let Some(name) = bindings
+        .chain(options.consts.iter().map(|(name, _)| name))
+        .find(|name| !declared(name))
+    {
+        return Err(syn::Error::new(
+            name.span(),
+            format!(
+                "`{item}` doesn't declare an associated `{name}`",
+                item = item.ident
+            ),
+        ));
+    }
+    Ok(associated)
+}
+
+// Asserts that the target implements each of the trait's supertraits, so a missing one is reported at the supertrait
+// with the trait it's required by, rather than only as an unsatisfied bound on the generated impl
+fn supertrait_checks(
+    item: &ItemTrait,
+    target: &Type,
+    instance: &Instance,
+    attrs: &TokenStream,
+) -> TokenStream {
+    let name = &item.ident;
+    let impl_generics = &instance.impl_generics;
+    let checks = item.supertraits.iter().filter_map(|bound| {
+        // The compiler's own message for an auto trait names the field that's missing it, and takes precedence over
+        // `on_unimplemented` anyway
+        if !matches!(bound, TypeParamBound::Trait(_))
+            || names_trait(bound, "Send")
+            || names_trait(bound, "Sync")
+        {
+            return None;
+        }
+        let mut bound = bound.clone();
+        instance.fill_bound(&mut bound);
+        let shown = bound
+            .to_token_stream()
+            .to_string()
+            .replace(":: ", "::")
+            .replace(" ::", "::")
+            .replace(" < ", "<")
+            .replace(" >", ">")
+            .replace(" ,", ",");
+        let message = format!("`{{Self}}` must implement `{shown}` to be wrapped by `{name}`");
+        Some(quote_spanned! {bound.span()=>
+            // Never called, only type checked
+            #attrs
+            #[allow(dead_code)]
+            const _: () = {
+                #[diagnostic::on_unimplemented(message = #message)]
+                trait Supertrait {}
+                impl<T: #bound> Supertrait for T {}
+                fn implements<T: Supertrait>() {}
+                fn check #impl_generics () {
+                    implements::<#target>();
+                }
+            };
+        })
+    });
+    quote!(#(#checks)*)
+}
+
+// `dyn`: reports the first thing that keeps `dyn Trait` from being a type. This looks at the trait as the macro
+// rewrote it, so async methods are fine once their futures are boxed.
+fn object_safety(item: &ItemTrait) -> syn::Result<()> {
+    let name = &item.ident;
+    let unusable = |span: Span, what: &dyn std::fmt::Display, why: &str| {
+        syn::Error::new(
+            span,
+            format!("`{what}` makes `{name}` unusable as `dyn {name}` because {why}"),
+        )
+    };
+    if let Some(bound) = item.supertraits.iter().find(|bound| is_sized(bound)) {
+        return Err(unusable(
+            bound.span(),
+            &"Sized",
+            "it requires `Self: Sized`",
+        ));
+    }
+    for trait_item in &item.items {
+        match trait_item {
+            TraitItem::Fn(f) if !sized_only(&f.sig) => {
+                let sig = &f.sig;
+                let why = if sig.receiver().is_none() {
+                    Some("it has no `self` receiver")
+                } else if sig
+                    .generics
+                    .params
+                    .iter()
+                    .any(|param| !matches!(param, GenericParam::Lifetime(_)))
+                {
+                    Some("it has generic parameters")
+                } else if sig.asyncness.is_some() {
+                    Some("it's an `async fn`, whose future is an opaque type; `futures = \"boxed\"` boxes it")
+                } else if output(sig)
+                    .is_some_and(|ty| mentions(ty, |ty| matches!(ty, Type::ImplTrait(_))))
+                {
+                    Some("it returns `impl Trait`")
+                } else if mentions_self(sig) {
+                    Some("it uses `Self` outside of its receiver")
+                } else {
+                    None
+                };
+                if let Some(why) = why {
+                    return Err(unusable(sig.ident.span(), &sig.ident, why));
+                }
+            }
+            TraitItem::Const(c) => {
+                return Err(unusable(
+                    c.ident.span(),
+                    &c.ident,
+                    "it has an associated const",
+                ));
+            }
+            TraitItem::Type(ty) if !ty.generics.params.is_empty() => {
+                return Err(unusable(
+                    ty.ident.span(),
+                    &ty.ident,
+                    "it has generic parameters",
+                ));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+// Whether the bound is the trait named `name`, however its path is written
+fn names_trait(bound: &TypeParamBound, name: &str) -> bool {
+    let TypeParamBound::Trait(bound) = bound else {
+        return false;
+    };
+    bound
+        .path
+        .segments
+        .last()
+        .is_some_and(|segment| segment.ident == name)
+}
+
+fn bounds_by(item: &ItemTrait, name: &str) -> bool {
+    item.supertraits
+        .iter()
+        .any(|bound| names_trait(bound, name))
+}
+
+fn is_sized(bound: &TypeParamBound) -> bool {
+    names_trait(bound, "Sized")
+}
+
+fn is_self(ty: &Type) -> bool {
+    matches!(ty, Type::Path(ty) if ty.qself.is_none() && ty.path.is_ident("Self"))
+}
+
+fn output(sig: &Signature) -> Option<&Type> {
+    match &sig.output {
+        ReturnType::Type(_, ty) => Some(ty),
+        ReturnType::Default => None,
+    }
+}
+
+// A method bound by `where Self: Sized`, which isn't part of the trait object
+fn sized_only(sig: &Signature) -> bool {
+    sig.generics
+        .where_clause
+        .iter()
+        .flat_map(|clause| &clause.predicates)
+        .any(|predicate| {
+            matches!(predicate, WherePredicate::Type(predicate)
+            if is_self(&predicate.bounded_ty) && predicate.bounds.iter().any(is_sized))
+        })
+}
+
+// Whether a typed argument or the return type names `Self` itself, rather than one of its associated types
+pub(crate) fn mentions_self(sig: &Signature) -> bool {
+    let inputs = sig.inputs.iter().filter_map(|input| match input {
+        FnArg::Typed(typed) => Some(&*typed.ty),
+        FnArg::Receiver(_) => None,
+    });
+    inputs.chain(output(sig)).any(|ty| mentions(ty, is_self))
+}
+
+fn mentions(ty: &Type, found: impl Fn(&Type) -> bool) -> bool {
+    struct Finder<F>(F, bool);
+    impl<'ast, F: Fn(&Type) -> bool> Visit<'ast> for Finder<F> {
+        fn visit_type(&mut self, ty: &'ast Type) {
+            if (self.0)(ty) {
+                self.1 = true;
+            }
+            visit::visit_type(self, ty);
+        }
+    }
+    let mut finder = Finder(found, false);
+    finder.visit_type(ty);
+    finder.1
+}
@@ -0,0 +1,633 @@
+use crate::attr::{self, Entry};
+use crate::double::{answerable, asynchronous, displayed, recorded, typed_args, Double};
+use crate::expand::mentions_self;
+use crate::method::{Asyncness, Method};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::{
+    parse_quote, FnArg, GenericArgument, Ident, LitStr, PathArguments, Receiver, Token, Type,
+};
+
+// `fake`, or `fake(...)` with options: a recording fake is generated next to the impls.
+#[derive(Default)]
+pub(crate) struct FakeOptions {
+    // `sync`: the fake's state is behind a `Mutex` rather than a `RefCell`, so it's `Send + Sync`. A trait with `Send`
+    // or `Sync` supertraits, or with `Send` futures, gets that anyway, since a `RefCell` couldn't implement it.
+    pub sync: bool,
+    // `clone`: the fake is `Clone`, its clones sharing the calls and configuration. A real instance can't be shared
+    // with them, so there's no `with_real`.
+    pub clone: bool,
+    // `test_only`: the fake is only compiled for tests, this crate's own or, through the `test-doubles` feature, its
+    // dependents'. The crate has to declare that feature, as `[features] test-doubles = []`, or rustc warns about an
+    // unexpected cfg; `test_only = "fakes"` names the crate's own feature instead.
+    pub test_only: Option<LitStr>,
+    // `arbitrary`: an `ArbitraryXxx` fake answering with proptest's arbitrary values is generated for tests too. It's
+    // gated like the fake under `test_only`, and on `cfg(test)` otherwise, and names `::proptest`, so the crate needs
+    // proptest wherever it's compiled: as a dev-dependency, and an optional one behind the feature for `test_only`.
+    pub arbitrary: bool,
+    // `type Output = String`: the fake's own binding of an associated type, instead of the impls'
+    pub types: Vec<(Ident, Type)>,
+}
+
+impl FakeOptions {
+    pub(crate) fn parse(entry: &Entry) -> syn::Result<Self> {
+        let mut options = FakeOptions::default();
+        let entries = match entry {
+            Entry::Flag(_) => Punctuated::new(),
+            Entry::List(_, tokens) => {
+                Punctuated::<Entry, Token![,]>::parse_terminated.parse2(tokens.clone())?
+            }
+            _ => {
+                return Err(syn::Error::new(
+                    entry.name().span(),
+                    "expected `fake` or `fake(...)`",
+                ))
+            }
+        };
+        for entry in entries {
+            if let Entry::Type(name, ty) = entry {
+                options.types.push((name, ty));
+                continue;
+            }
+            match entry.name().to_string().as_str() {
+                "sync" => {
+                    attr::flag(&entry)?;
+                    options.sync = true;
+                }
+                "clone" => {
+                    attr::flag(&entry)?;
+                    options.clone = true;
+                }
+                "test_only" => {
+                    options.test_only = Some(match entry {
+                        Entry::Flag(name) => LitStr::new("test-doubles", name.span()),
+                        _ => attr::string(&entry)?,
+                    });
+                }
+                "arbitrary" => {
+                    attr::flag(&entry)?;
+                    options.arbitrary = true;
+                }
+                _ => return Err(attr::unknown(&entry, "fake")),
+            }
+        }
+        Ok(options)
+    }
+
+    // The cfg of a fake that's only compiled for tests, which holds for the crate's own tests and its feature
+    pub(crate) fn test_cfg(&self) -> Option<TokenStream> {
+        let feature = self.test_only.as_ref()?;
+        Some(quote!(#[cfg(any(test, feature = #feature))]))
+    }
+}
+
+// The fake, its state, and its impl of the trait
+pub(crate) fn fake(double: &Double, options: &FakeOptions) -> TokenStream {
+    let Double {
+        item,
+        methods,
+        instance,
+        associated,
+        attrs,
+        vis,
+        ..
+    } = double;
+    let fake = double.name("Fake");
+    let test_only = options.test_cfg();
+    let attrs = quote!(#test_only #attrs);
+    let (kind, send) = match options.sync || double.shared() {
+        true => (quote!(sync), quote!(+ ::core::marker::Send)),
+        false => (quote!(local), TokenStream::new()),
+    };
+    let state = format_ident!("__{}State", fake);
+    let self_ty: Type = parse_quote!(#fake);
+    let fields: Vec<_> = methods.iter().map(|method| &method.sig.ident).collect();
+    let answers: Vec<_> = methods
+        .iter()
+        .map(|method| answered(double, method, &self_ty))
+        .collect();
+    let captures: Vec<_> = methods
+        .iter()
+        .map(|method| double.capture(method, &self_ty).0)
+        .collect();
+    let types = captures
+        .iter()
+        .zip(&answers)
+        .map(|(capture, answer)| match answer {
+            Some(ty) => quote!(#capture, #ty),
+            None => quote!(#capture, ()),
+        });
+    let inspection = inspection(double, &fake, &self_ty);
+    let queues = methods
+        .iter()
+        .zip(&captures)
+        .zip(&answers)
+        .filter_map(|((method, capture), answer)| {
+            let ty = answer.as_ref()?;
+            let name = &method.sig.ident;
+            let returns_for = format_ident!("returns_for_{}", double.short(method));
+            let when = format_ident!("returns_for_{}_when", double.short(method));
+            let rule = format_ident!("when_{}", double.short(method));
+            let rule_doc = format!(
+                "Answers the calls to `{name}` whose arguments `matcher` matches, once given what to return."
+            );
+            let doc = format!("Queues `values` for the next calls to `{name}` to return, in order.");
+            let when_doc = format!(
+                "Queues `values` for the next calls to `{name}` whose arguments `matcher` matches to return, in order."
+            );
+            Some(quote! {
+                #[doc = #doc]
+                pub fn #returns_for(&self, values: impl ::core::iter::IntoIterator<Item = #ty>) {
+                    self.state.#name.queue(values);
+                }
+
+                #[doc = #when_doc]
+                pub fn #when(
+                    &self,
+                    matcher: impl ::depabx::matchers::Matcher<#capture> #send + 'static,
+                    values: impl ::core::iter::IntoIterator<Item = #ty>,
+                ) {
+                    self.state.#name.queue_when(matcher, values);
+                }
+
+                #[doc = #rule_doc]
+                pub fn #rule<M>(
+                    &self,
+                    matcher: M,
+                ) -> ::depabx::When<'_, ::depabx::__private::#kind::Method<#capture, #ty>, M>
+                where
+                    M: ::depabx::matchers::Matcher<#capture> #send + 'static,
+                {
+                    ::depabx::When::new(&self.state.#name, matcher)
+                }
+            })
+        });
+    let failing: Vec<_> = methods
+        .iter()
+        .zip(&answers)
+        .filter_map(|(method, answer)| Some((method, error_type(answer.as_ref()?)?)))
+        .collect();
+    let fail_all = (!failing.is_empty()).then(|| {
+        let errors = failing.iter().map(|(_, error)| error);
+        let fails = failing.iter().map(|(method, _)| {
+            let (name, cfgs) = (&method.sig.ident, &method.cfgs);
+            quote! {
+                #(#cfgs)*
+                {
+                    let error = ::core::clone::Clone::clone(&error);
+                    self.state.#name.fail(move || {
+                        ::core::result::Result::Err(::core::convert::From::from(
+                            ::core::clone::Clone::clone(&error),
+                        ))
+                    });
+                }
+            }
+        });
+        quote! {
+            /// Makes every method returning a `Result` return `error` from now on, converted to the method's error
+            /// type, ahead of anything queued for it. That's a `Result<T, E>`, or a module's alias of one like
+            /// `io::Result<T>`, which fails with that module's `Error`; methods returning other aliases are left be.
+            pub fn fail_all_with<E>(&self, error: E)
+            where
+                E: ::core::clone::Clone #send + 'static,
+                #(#errors: ::core::convert::From<E>,)*
+            {
+                #(#fails)*
+            }
+        }
+    });
+    let expects = methods
+        .iter()
+        .filter(|method| method.receiver)
+        .map(|method| {
+            let name = &method.sig.ident;
+            let (capture, _) = double.capture(method, &self_ty);
+            let expect = format_ident!("expect_{}", double.short(method));
+            let doc = format!("Expects calls to `{name}`, from now on.");
+            let method = name.to_string();
+            quote! {
+                #[doc = #doc]
+                pub fn #expect(&self) -> ::depabx::Expectation<#capture> {
+                    self.state.#name.expect(#method)
+                }
+            }
+        });
+    let holds = methods.iter().filter(|method| held(method)).map(|method| {
+        let name = &method.sig.ident;
+        let hold = format_ident!("hold_{}", double.short(method));
+        let doc = format!(
+            "Holds back the futures of calls to `{name}`, made before or after, until the gate is released."
+        );
+        quote! {
+            #[doc = #doc]
+            pub fn #hold(&self) -> ::depabx::Gate {
+                self.state.#name.hold()
+            }
+        }
+    });
+    // Only fakes with async methods have a latency to give
+    let (latency_field, latency_none, with_latency) = match methods.iter().any(held) {
+        true => (
+            quote!(latency: ::core::option::Option<::core::time::Duration>,),
+            quote!(latency: ::core::option::Option::None,),
+            quote! {
+                /// The fake, with the futures of its async methods waiting `latency` before they answer. That's
+                /// `tokio::time::sleep` under depabx's `tokio` feature, so paused time skips it.
+                pub fn with_latency(self, latency: ::core::time::Duration) -> Self {
+                    #fake {
+                        latency: ::core::option::Option::Some(latency),
+                        ..self
+                    }
+                }
+            },
+        ),
+        false => Default::default(),
+    };
+    let (real_field, real_none, with_real) = match &double.real {
+        Some(real) => {
+            let cfg = &double.impl_cfg;
+            (
+                quote!(#cfg real: ::core::option::Option<#real>,),
+                quote!(#cfg real: ::core::option::Option::None,),
+                quote! {
+                    /// A fake that passes the calls it has no answer for on to `real`, recording them either way.
+                    #cfg
+                    pub fn with_real(real: #real) -> Self {
+                        #fake {
+                            real: ::core::option::Option::Some(real),
+                            ..Self::new()
+                        }
+                    }
+                },
+            )
+        }
+        None => Default::default(),
+    };
+    let (trait_path, lifetimes) = (&instance.trait_path, &instance.lifetimes);
+    let fns = methods
+        .iter()
+        .map(|method| fake_method(double, method, &self_ty));
+    let doc = format!(
+        "A fake [`{}`] that records every call made to it.",
+        item.ident
+    );
+    let fake_name = fake.to_string();
+    let names = fields.iter().map(|field| field.to_string());
+    let derive = options
+        .clone
+        .then(|| quote!(#[derive(::core::clone::Clone)]));
+    quote! {
+        #attrs
+        #[doc = #doc]
+        #derive
+        #vis struct #fake {
+            #real_field
+            #latency_field
+            state: ::std::sync::Arc<#state>,
+        }
+
+        #attrs
+        #[derive(Default)]
+        struct #state {
+            #(#fields: ::depabx::__private::#kind::Method<#types>,)*
+            registry: ::core::option::Option<::depabx::Registry>,
+        }
+
+        #attrs
+        impl ::core::default::Default for #fake {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        #attrs
+        impl ::core::fmt::Debug for #fake {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                ::depabx::__private::debug(
+                    f,
+                    #fake_name,
+                    self.calls(),
+                    &[#((#names, self.state.#fields.queued())),*],
+                )
+            }
+        }
+
+        // Whatever is still expected is checked once the last handle on the fake is gone
+        #attrs
+        impl ::core::ops::Drop for #state {
+            fn drop(&mut self) {
+                ::depabx::__private::verify([#(self.#fields.unmet()),*]);
+            }
+        }
+
+        #attrs
+        impl #fake {
+            /// A fake with no calls recorded.
+            pub fn new() -> Self {
+                #fake {
+                    #real_none
+                    #latency_none
+                    state: ::std::default::Default::default(),
+                }
+            }
+
+            /// A fake reporting its calls into `registry`, as well as recording them.
+            pub fn with_registry(registry: &::depabx::Registry) -> Self {
+                let mut state = #state::default();
+                state.registry = ::core::option::Option::Some(::core::clone::Clone::clone(registry));
+                #fake {
+                    #real_none
+                    #latency_none
+                    state: ::std::sync::Arc::new(state),
+                }
+            }
+
+            #with_real
+
+            #with_latency
+
+            #inspection
+
+            /// Panics with every expectation that wasn't met, then forgets them all, met or not.
+            #[track_caller]
+            pub fn checkpoint(&self) {
+                ::depabx::__private::verify([#(self.state.#fields.unmet()),*]);
+            }
+
+            #(#expects)*
+
+            /// Forgets every recorded call and everything the fake was configured with, as if it were new.
+            pub fn reset(&self) {
+                #(self.state.#fields.reset();)*
+            }
+
+            #(#queues)*
+
+            #fail_all
+
+            #(#holds)*
+        }
+
+        #attrs
+        impl<#(#lifetimes),*> #trait_path for #fake {
+            #(#associated)*
+            #(#fns)*
+        }
+    }
+}
+
+// The methods of a double recording its calls in `self.state`, which show what was recorded
+pub(crate) fn inspection(double: &Double, name: &Ident, self_ty: &Type) -> TokenStream {
+    let fields: Vec<_> = double
+        .methods
+        .iter()
+        .map(|method| &method.sig.ident)
+        .collect();
+    let names: Vec<_> = fields.iter().map(|field| field.to_string()).collect();
+    let double_name = name.to_string();
+    let counters = double.methods.iter().map(|method| {
+        let name = &method.sig.ident;
+        let (capture, _) = double.capture(method, self_ty);
+        let calls = format_ident!("{}_calls", double.short(method));
+        let captured = format_ident!("captured_{}", double.short(method));
+        let doc = format!("The calls made to `{name}`, with their arguments.");
+        let captured_doc = format!(
+            "The arguments of each call made to `{name}`: the argument itself for a method taking one, and a tuple of \
+            them otherwise."
+        );
+        let method = name.to_string();
+        quote! {
+            #[doc = #doc]
+            pub fn #calls(&self) -> ::depabx::Calls<#capture> {
+                ::depabx::Calls::captured(#method, self.state.#name.captured())
+            }
+
+            #[doc = #captured_doc]
+            pub fn #captured(&self) -> ::std::vec::Vec<#capture> {
+                let calls = self.state.#name.captured();
+                calls.into_iter().map(|(_, captured)| captured).collect()
+            }
+        }
+    });
+    quote! {
+        /// Every call made so far, in order.
+        pub fn calls(&self) -> ::std::vec::Vec<::depabx::Call> {
+            ::depabx::__private::merge([#(self.state.#fields.calls()),*])
+        }
+
+        /// The calls made to the method named `method`, which panics if the trait has none by that name.
+        #[track_caller]
+        pub fn calls_of(&self, method: &str) -> ::depabx::Calls {
+            match method {
+                #(#names => ::depabx::Calls::new(#names, self.state.#fields.calls()),)*
+                _ => ::core::panic!("`{}` has no method named `{}`", #double_name, method),
+            }
+        }
+
+        #(#counters)*
+    }
+}
+
+// The statement recording a call of the method in `self.state`, when it has a `self` to record it in, and reporting it
+// into the registry that has, for a double that can have one
+pub(crate) fn record(
+    double: &Double,
+    method: &Method,
+    self_ty: &Type,
+    registry: bool,
+) -> TokenStream {
+    if !method.receiver {
+        return TokenStream::new();
+    }
+    let field = &method.sig.ident;
+    let (call, captured) = call(double, method, self_ty, registry);
+    quote! {
+        self.state.#field.record(#call, #captured);
+    }
+}
+
+// The call of the method to record, and its arguments as the double captures them
+fn call(
+    double: &Double,
+    method: &Method,
+    self_ty: &Type,
+    registry: bool,
+) -> (TokenStream, TokenStream) {
+    let name = method.sig.ident.to_string();
+    let recorded = typed_args(method).map(|(ty, arg)| recorded(method, ty, &arg.name));
+    let displayed = typed_args(method).map(|(ty, _)| displayed(method, ty));
+    let (_, captured) = double.capture(method, self_ty);
+    let mut call = quote! {
+        ::depabx::Call::new(#name, ::std::vec![#(#recorded),*], ::std::vec![#(#displayed),*])
+    };
+    if registry {
+        let trait_name = double.item.ident.to_string();
+        call = quote!(::depabx::__private::report(&self.state.registry, #trait_name, #call));
+    }
+    (call, captured)
+}
+
+// Passes the call on to the real instance of a partial fake, if it has one, and returns what that does. Methods whose
+// signature names the fake itself can't be passed on, and neither can those taking `self`, which would have to give
+// the real instance up.
+fn fallthrough(double: &Double, method: &Method, returns: bool) -> Option<TokenStream> {
+    let real = double.real.as_ref()?;
+    let mutability = match method.sig.inputs.first() {
+        Some(FnArg::Receiver(Receiver {
+            reference: Some(_),
+            mutability,
+            ..
+        })) => mutability,
+        _ => return None,
+    };
+    if mentions_self(&method.sig) {
+        return None;
+    }
+    let (cfg, trait_path, name) = (
+        &double.impl_cfg,
+        &double.instance.trait_path,
+        &method.sig.ident,
+    );
+    let args = method.args.iter().map(|arg| &arg.name);
+    let mut call = quote!(<#real as #trait_path>::#name(real, #(#args),*));
+    if method.asyncness != Asyncness::Sync {
+        call = quote!(#call.await);
+    }
+    if method.sig.unsafety.is_some() {
+        // The caller took on the method's contract by calling the fake's
+        call = quote!(unsafe { #call });
+    }
+    let call = match returns {
+        true => quote!(return #call;),
+        false => quote!(#call;),
+    };
+    Some(quote! {
+        #cfg
+        if let ::core::option::Option::Some(real) = &#mutability self.real {
+            #call
+        }
+    })
+}
+
+// Whether the method's futures can be held back with a gate
+fn held(method: &Method) -> bool {
+    method.receiver && method.asyncness != Asyncness::Sync
+}
+
+// The type of the values queued for the method to return, if it returns something they could stand for
+fn answered(double: &Double, method: &Method, self_ty: &Type) -> Option<Type> {
+    let output = double.output(method)?;
+    (method.receiver && answerable(method, Some(&output))).then(|| double.outside(&output, self_ty))
+}
+
+// The error type of a `Result<T, E>`, or of a module's alias of it like `io::Result<T>` or `fmt::Result`, taken to be
+// that module's `Error`. Those are the only results whose error type is known.
+fn error_type(ty: &Type) -> Option<Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Result" {
+        return None;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 2 => match args.args.last() {
+            Some(GenericArgument::Type(error)) => Some(error.clone()),
+            _ => None,
+        },
+        PathArguments::AngleBracketed(args) if args.args.len() > 1 => None,
+        PathArguments::Parenthesized(_) => None,
+        _ if path.qself.is_some() || path.path.segments.len() < 2 => None,
+        _ => {
+            let mut error = path.clone();
+            *error.path.segments.last_mut()? = parse_quote!(Error);
+            Some(Type::Path(error))
+        }
+    }
+}
+
+fn fake_method(double: &Double, method: &Method, self_ty: &Type) -> TokenStream {
+    let mut sig = double.sig(method);
+    let field = &sig.ident;
+    let name = field.to_string();
+    let record = record(double, method, self_ty, true);
+    let output = double.output(method);
+    let answered = answered(double, method, self_ty);
+    let cannot = format!(
+        "`{}` can't return anything from `{name}`",
+        double.name("Fake")
+    );
+    let (eager, value) = match (
+        fallthrough(double, method, output.is_some()),
+        &output,
+        &answered,
+    ) {
+        (None, None, _) => (record, TokenStream::new()),
+        (None, Some(_), None) => (record, quote!(::core::panic!(#cannot))),
+        (None, Some(_), Some(_)) => {
+            let (call, captured) = call(double, method, self_ty, true);
+            (
+                quote! {
+                    let value = self.state.#field.record_and_answer(#name, #call, #captured);
+                },
+                quote!(value),
+            )
+        }
+        (Some(fallthrough), None, _) => (record, fallthrough),
+        // Whether there's an answer is settled when the call is made, even if the value is only returned later
+        (Some(fallthrough), Some(_), Some(_)) => {
+            let (call, captured) = call(double, method, self_ty, true);
+            (
+                quote! {
+                    let value = self.state.#field.record_and_try_answer(#call, #captured);
+                },
+                quote! {
+                    if let ::core::option::Option::Some(value) = value {
+                        return value;
+                    }
+                    #fallthrough
+                    ::depabx::__private::unanswered(#name)
+                },
+            )
+        }
+        (Some(fallthrough), Some(_), None) => (
+            record,
+            quote! {
+                #fallthrough
+                ::core::panic!(#cannot)
+            },
+        ),
+    };
+    let (eager, value) = match held(method) {
+        true => (
+            quote! {
+                #eager
+                let latency = self.latency;
+                let gate = self.state.#field.gate();
+            },
+            quote! {
+                if let ::core::option::Option::Some(latency) = latency {
+                    ::depabx::__private::delay(latency).await;
+                }
+                if let ::core::option::Option::Some(gate) = gate {
+                    gate.wait().await;
+                }
+                #value
+            },
+        ),
+        false => (eager, value),
+    };
+    let cfgs = &method.cfgs;
+    // So that a panic over what it returns points at the call in the code under test
+    let track_caller = (method.asyncness == Asyncness::Sync).then(|| quote!(#[track_caller]));
+    let body = asynchronous(method, &mut sig, output.as_ref(), eager, value);
+    quote! {
+        #(#cfgs)*
+        #track_caller
+        #sig {
+            #body
+        }
+    }
+}
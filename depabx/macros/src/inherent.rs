@@ -0,0 +1,90 @@
+use crate::expand;
+use crate::options::WrapOptions;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    parse_quote, FnArg, ImplItem, ItemImpl, ItemTrait, Pat, PatIdent, TraitItem, TraitItemFn, Type,
+    Visibility,
+};
+
+// `#[wrap_impl]` on an inherent impl: the impl as written, followed by an Abx trait declaring its public methods and
+// the trait's delegating impl, as `#[wrap]` would generate it for the impl's type.
+pub(crate) fn wrap_impl(mut options: WrapOptions, item: ItemImpl) -> syn::Result<TokenStream> {
+    if let Some(token) = item.trait_.as_ref().map(|(_, path, _)| path) {
+        return Err(syn::Error::new_spanned(
+            token,
+            "`#[wrap_impl]` goes on an inherent impl, to generate a trait from it",
+        ));
+    }
+    if let Some(target) = options.targets.first() {
+        return Err(syn::Error::new_spanned(
+            target,
+            "`#[wrap_impl]` wraps the impl's own type, so it only takes options",
+        ));
+    }
+    if !item.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &item.generics,
+            "`#[wrap_impl]` doesn't support generic impls; write the trait and use `#[wrap]` instead",
+        ));
+    }
+    let Type::Path(self_ty) = &*item.self_ty else {
+        return Err(syn::Error::new_spanned(
+            &item.self_ty,
+            "expected a named type",
+        ));
+    };
+    let name = format_ident!("Abx{}", self_ty.path.segments.last().unwrap().ident);
+
+    let mut methods = Vec::new();
+    for impl_item in &item.items {
+        let ImplItem::Fn(f) = impl_item else {
+            continue;
+        };
+        if !matches!(f.vis, Visibility::Public(_)) {
+            continue;
+        }
+        let mut sig = f.sig.clone();
+        sig.ident = format_ident!(
+            "{}{}",
+            options.naming.prefix,
+            sig.ident,
+            span = sig.ident.span()
+        );
+        // A method without a body can't bind its arguments with patterns like `mut x`
+        for (i, input) in sig.inputs.iter_mut().enumerate() {
+            if let FnArg::Typed(typed) = input {
+                *typed.pat = match &*typed.pat {
+                    Pat::Ident(PatIdent { ident, .. }) => parse_quote!(#ident),
+                    _ => {
+                        let name = format_ident!("arg{}", i);
+                        parse_quote!(#name)
+                    }
+                };
+            }
+        }
+        let attrs = f
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("doc") || attr.path().is_ident("cfg"));
+        let method: TraitItemFn = parse_quote! {
+            #(#attrs)*
+            #sig;
+        };
+        methods.push(TraitItem::Fn(method));
+    }
+
+    // The trait is as private as any other item unless `vis` says otherwise
+    let vis = options.vis.clone().unwrap_or(Visibility::Inherited);
+    let abx_trait: ItemTrait = parse_quote! {
+        #vis trait #name {
+            #(#methods)*
+        }
+    };
+    options.targets = vec![(*item.self_ty).clone()];
+    let generated = expand::wrap(options, abx_trait)?;
+    Ok(quote! {
+        #item
+        #generated
+    })
+}
@@ -0,0 +1,64 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, quote_spanned};
+use syn::{ExprStruct, Member, Path};
+
+// `fake!(AbxLogger { abx_log_event: |d| ..., .. })`: the trait's stub, with each field's closure given to the method
+// of that name. Without `..`, every method the stub can be configured for has to be there, which is checked by
+// destructuring the stub's list of them. Only the stub has to be in scope, since the list is found through it.
+pub(crate) fn fake(input: ExprStruct) -> syn::Result<TokenStream> {
+    if let Some(qself) = &input.qself {
+        return Err(syn::Error::new(
+            qself.lt_token.span,
+            "expected the name of an Abx trait",
+        ));
+    }
+    if let Some(rest) = &input.rest {
+        return Err(syn::Error::new_spanned(
+            rest,
+            "expected a bare `..`, which leaves the other methods to the stub",
+        ));
+    }
+    let stub = sibling(&input.path, "Stub");
+    let mut members = Vec::new();
+    let mut calls = Vec::new();
+    for field in &input.fields {
+        let Member::Named(name) = &field.member else {
+            return Err(syn::Error::new_spanned(
+                &field.member,
+                "expected the name of one of the trait's methods",
+            ));
+        };
+        let on = format_ident!("__on_{}", name, span = name.span());
+        let f = &field.expr;
+        members.push(name);
+        calls.push(quote!(.#on(#f)));
+    }
+    // Spanned like the call, so the error for a missing method reads like one for a missing field
+    let pattern =
+        quote_spanned!(input.brace_token.span.join()=> let Methods { #(#members: _),* } = methods;);
+    let check = input.dot2_token.is_none().then(|| {
+        quote! {
+            type Methods = <#stub<'static> as ::depabx::__private::Inline>::Methods;
+            let _ = |methods: Methods| {
+                #pattern
+            };
+        }
+    });
+    Ok(quote! {
+        {
+            #check
+            #stub::new() #(#calls)*
+        }
+    })
+}
+
+// The path of an item generated next to the trait at `path`: `tests::AbxLogger` gives `tests::StubLogger` for a stub
+fn sibling(path: &Path, kind: &str) -> Path {
+    let mut path = path.clone();
+    if let Some(last) = path.segments.last_mut() {
+        let name = last.ident.to_string();
+        let base = name.strip_prefix("Abx").unwrap_or(&name);
+        last.ident = format_ident!("{kind}{base}", span = last.ident.span());
+    }
+    path
+}
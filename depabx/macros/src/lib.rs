@@ -0,0 +1,55 @@
+//! The procedural macros behind `depabx`. Use them through the `depabx` crate, which re-exports them alongside the
+//! runtime support their expansions refer to.
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, ExprStruct, ItemImpl, ItemTrait};
+
+mod arbitrary;
+mod attr;
+mod double;
+mod expand;
+mod fake;
+mod inherent;
+mod inline;
+mod method;
+mod noop;
+mod options;
+mod spy;
+mod store;
+mod stub;
+mod target;
+
+/// Implements an Abx trait for the concrete type it abstracts, by delegating every method to the concrete method of
+/// the same name without the `abx_` prefix.
+#[proc_macro_attribute]
+pub fn wrap(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let options = parse_macro_input!(attr as options::WrapOptions);
+    let item = parse_macro_input!(item as ItemTrait);
+    expand::wrap(options, item)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Generates an Abx trait from the public methods of an inherent impl, prefixed with `abx_`, and implements it for the
+/// impl's type like `#[wrap]` would. Takes the same options as `#[wrap]`, without the target type.
+#[proc_macro_attribute]
+pub fn wrap_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let options = parse_macro_input!(attr as options::WrapOptions);
+    let item = parse_macro_input!(item as ItemImpl);
+    inherent::wrap_impl(options, item)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Builds a one-off double of an Abx trait in place, as in
+/// `fake!(AbxLogger { abx_log_event: |d| println!("{d}"), .. })`. Each field gives the closure answering the trait
+/// method of that name, and the whole expands to the trait's stub, which `#[wrap]` generates with `stub` and which has
+/// to be in scope. Ending with `..` leaves the other methods to the stub's fallbacks; without it, leaving one out is a
+/// compile error.
+#[proc_macro]
+pub fn fake(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as ExprStruct);
+    inline::fake(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,611 @@
+use crate::attr::{self, Entry};
+use crate::options::Naming;
+use crate::target::Instance;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::visit_mut::{self, VisitMut};
+use syn::{
+    parse_quote, Attribute, Expr, FnArg, GenericParam, Ident, Lifetime, LitStr, Pat, PatIdent,
+    PatType, Receiver, ReturnType, Signature, Token, TraitItemFn, Type, TypeReference,
+    WherePredicate,
+};
+
+// A trait method the macro generates a delegating impl for.
+pub(crate) struct Method {
+    // The signature implementations write, after any rewriting by the macro, with every argument named
+    pub sig: Signature,
+    // The trait method's `#[cfg]` attributes, so the impl method exists exactly when the trait method does. Lint
+    // attributes like `#[must_use]` apply to calls, which resolve to the trait method, so they aren't copied.
+    pub cfgs: Vec<Attribute>,
+    // The trait method's doc comments, which the impl method repeats before saying what it delegates to
+    pub docs: Vec<Attribute>,
+    // The concrete method it delegates to
+    pub target: Ident,
+    pub args: Vec<Arg>,
+    pub receiver: bool,
+    pub asyncness: Asyncness,
+    // What the method returns as declared, before any rewriting of async methods
+    pub output: ReturnType,
+    // Whether the method returns a `Result`, whose error is converted with `Into`
+    pub result: bool,
+    pub options: MethodOptions,
+}
+
+// The `#[abx(...)]` options of a single method.
+#[derive(Default)]
+pub(crate) struct MethodOptions {
+    // `boxed`: the trait returns a `Box<dyn Trait>` and the concrete value is boxed to fit it
+    pub boxed: bool,
+    // `iter`: there's no inherent method, and the trait returns the concrete type's own iterator, boxed
+    pub iter: bool,
+    // `send`: the future of an async method is `Send`
+    pub send: bool,
+    // `target = "upload_object"`: the concrete method to call, instead of the one named after the trait method
+    pub target: Option<Ident>,
+    // `skip`: not part of the dependency, so the method keeps its default body and nothing is generated for it
+    pub skip: bool,
+    // `map_return = "to_user"`: a function the concrete method's return value is passed through
+    pub map_return: Option<syn::Path>,
+    // `map_arg(query = "SearchRequest::new(query)")`: expressions passed on in place of the named arguments
+    pub map_args: Vec<(Ident, Expr)>,
+    // `no_default`: the return type has no `Default`, so a `stub(nice)` can't fall back on it
+    pub no_default: bool,
+    // `store = "delete"`: what the method does to the entries of an in-memory `store`, if its name doesn't say
+    pub store: Option<LitStr>,
+}
+
+impl MethodOptions {
+    fn parse(entries: Vec<Entry>) -> syn::Result<Self> {
+        let mut options = MethodOptions::default();
+        for entry in entries {
+            match entry.name().to_string().as_str() {
+                "boxed" => {
+                    attr::flag(&entry)?;
+                    options.boxed = true;
+                }
+                "iter" => {
+                    attr::flag(&entry)?;
+                    options.iter = true;
+                }
+                "send" => {
+                    attr::flag(&entry)?;
+                    options.send = true;
+                }
+                "skip" => {
+                    attr::flag(&entry)?;
+                    options.skip = true;
+                }
+                "no_default" => {
+                    attr::flag(&entry)?;
+                    options.no_default = true;
+                }
+                "store" => options.store = Some(attr::string(&entry)?),
+                "target" => {
+                    let target = attr::string(&entry)?;
+                    options.target = Some(target.parse()?);
+                }
+                "map_return" => options.map_return = Some(attr::string(&entry)?.parse()?),
+                "map_arg" => {
+                    let maps = Punctuated::<Entry, Token![,]>::parse_terminated
+                        .parse2(attr::list(&entry)?)?;
+                    for map in maps {
+                        let expr = attr::string(&map)?.parse()?;
+                        options.map_args.push((map.name().clone(), expr));
+                    }
+                }
+                "ext" => {
+                    return Err(syn::Error::new(
+                        entry.name().span(),
+                        "`#[abx(ext)]` moves the method to the extension trait, which needs `ext` in `#[wrap(...)]`",
+                    ))
+                }
+                _ => return Err(attr::unknown(&entry, "method")),
+            }
+        }
+        Ok(options)
+    }
+}
+
+// What the generated impl calls the concrete method on.
+#[derive(Clone, Copy)]
+pub(crate) enum Route<'a> {
+    // `self`, which is the target
+    Target,
+    // `via = client`: a field of `self`, whose methods are called with method call syntax
+    Field(&'a Expr),
+    // `deref`: what `self` derefs to, in the blanket impl for smart pointers to the target
+    Deref,
+}
+
+impl Route<'_> {
+    // The receiver expression the concrete method is given in a path call. Through `Deref`, `self` is reborrowed
+    // from what it points to, which gives the `&Target` or `&mut Target` a `&self` or `&mut self` method expects.
+    fn this(self, mutable: bool) -> TokenStream {
+        match (self, mutable) {
+            (Route::Deref, false) => quote!(&**self),
+            (Route::Deref, true) => quote!(&mut **self),
+            _ => quote!(self),
+        }
+    }
+}
+
+// An argument of a trait method, and how it's passed on to the concrete method.
+pub(crate) struct Arg {
+    pub name: Ident,
+    pub convert: Option<Conversion>,
+    // The `map_arg` expression passed on instead of the argument itself
+    pub map: Option<Expr>,
+    // `capture = "debug"`: fakes capture the argument as its `Debug` string rather than cloning it
+    pub capture_debug: bool,
+}
+
+// `#[abx(into)]` or `#[abx(as_ref)]` on an argument
+#[derive(Clone, Copy)]
+pub(crate) enum Conversion {
+    Into,
+    AsRef,
+}
+
+impl Arg {
+    fn parse(name: Ident, entries: Vec<Entry>) -> syn::Result<Self> {
+        let mut arg = Arg {
+            name,
+            convert: None,
+            map: None,
+            capture_debug: false,
+        };
+        for entry in entries {
+            match entry.name().to_string().as_str() {
+                "into" => {
+                    attr::flag(&entry)?;
+                    arg.convert = Some(Conversion::Into);
+                }
+                "as_ref" => {
+                    attr::flag(&entry)?;
+                    arg.convert = Some(Conversion::AsRef);
+                }
+                "capture" => {
+                    let capture = attr::string(&entry)?;
+                    if capture.value() != "debug" {
+                        return Err(syn::Error::new(capture.span(), "expected \"debug\""));
+                    }
+                    arg.capture_debug = true;
+                }
+                _ => return Err(attr::unknown(&entry, "argument")),
+            }
+        }
+        Ok(arg)
+    }
+
+    // The expression the concrete method is given
+    fn forward(&self) -> TokenStream {
+        let name = &self.name;
+        if let Some(map) = &self.map {
+            return quote!(#map);
+        }
+        match self.convert {
+            None => quote!(#name),
+            Some(Conversion::Into) => quote!(::core::convert::Into::into(#name)),
+            Some(Conversion::AsRef) => quote!(#name.as_ref()),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Asyncness {
+    Sync,
+    // An `async fn` in the generated impl
+    Native,
+    // A plain `fn` whose body boxes an async block
+    Boxed,
+}
+
+impl Method {
+    // Reads the method, removing its `#[abx]` attributes from the trait as it goes. Methods with a default body, and
+    // skipped ones, are the trait's own, so there's nothing to delegate.
+    pub(crate) fn parse(item: &mut TraitItemFn, naming: &Naming) -> syn::Result<Option<Self>> {
+        let options = MethodOptions::parse(attr::take_abx_entries(&mut item.attrs)?)?;
+        match (&item.default, options.skip) {
+            (Some(_), _) => return Ok(None),
+            (None, true) => return Err(syn::Error::new(
+                item.sig.ident.span(),
+                "a skipped method needs a default body, since the generated impl won't provide one",
+            )),
+            (None, false) => {}
+        }
+
+        let name = item.sig.ident.to_string();
+        let prefix = &naming.prefix;
+        let target = match (&options.target, name.strip_prefix(prefix.as_str())) {
+            (Some(target), _) => target.clone(),
+            (None, Some(rest)) => Ident::new(&naming.target(rest), item.sig.ident.span()),
+            (None, None) => {
+                let message = format!(
+                    "`{name}` should start with `{prefix}`, then the name of the concrete method it calls"
+                );
+                return Err(syn::Error::new(item.sig.ident.span(), message));
+            }
+        };
+
+        let mut args = Vec::new();
+        let mut receiver = false;
+        for (i, input) in item.sig.inputs.iter_mut().enumerate() {
+            match input {
+                FnArg::Receiver(_) => receiver = true,
+                FnArg::Typed(typed) => {
+                    let entries = attr::take_abx_entries(&mut typed.attrs)?;
+                    let name = match &*typed.pat {
+                        Pat::Ident(PatIdent { ident, .. }) => ident.clone(),
+                        _ => format_ident!("arg{}", i),
+                    };
+                    args.push(Arg::parse(name, entries)?);
+                }
+            }
+        }
+        for (name, map) in &options.map_args {
+            match args.iter_mut().find(|arg| arg.name == *name) {
+                Some(arg) => arg.map = Some(map.clone()),
+                None => {
+                    return Err(syn::Error::new(
+                        name.span(),
+                        format!("`{}` has no argument named `{name}`", item.sig.ident),
+                    ))
+                }
+            }
+        }
+        // The impl names every argument, since it has to pass them on
+        let mut sig = item.sig.clone();
+        for (typed, arg) in sig.inputs.iter_mut().filter_map(typed).zip(&args) {
+            let name = &arg.name;
+            *typed.pat = parse_quote!(#name);
+        }
+
+        let asyncness = if sig.asyncness.is_some() {
+            Asyncness::Native
+        } else {
+            Asyncness::Sync
+        };
+        let result = returns_result(&sig.output);
+        let attrs = |name: &str| {
+            item.attrs
+                .iter()
+                .filter(|attr| attr.path().is_ident(name))
+                .cloned()
+                .collect()
+        };
+        let (cfgs, docs) = (attrs("cfg"), attrs("doc"));
+        Ok(Some(Method {
+            sig,
+            cfgs,
+            docs,
+            target,
+            args,
+            receiver,
+            asyncness,
+            output: item.sig.output.clone(),
+            result,
+            options,
+        }))
+    }
+
+    // In `strict` mode the trait mirrors the concrete method, so nothing may adapt one to the other
+    pub(crate) fn reject_adapters(&self) -> syn::Result<()> {
+        let options = &self.options;
+        let adapter = if options.boxed {
+            Some("boxed")
+        } else if options.iter {
+            Some("iter")
+        } else if options.map_return.is_some() {
+            Some("map_return")
+        } else if !options.map_args.is_empty() {
+            Some("map_arg")
+        } else {
+            self.args.iter().find_map(|arg| match arg.convert {
+                Some(Conversion::Into) => Some("into"),
+                Some(Conversion::AsRef) => Some("as_ref"),
+                None => None,
+            })
+        };
+        match adapter {
+            Some(adapter) => Err(syn::Error::new(
+                self.sig.ident.span(),
+                format!(
+                    "`#[abx({adapter})]` adapts the concrete method, which `strict` doesn't allow"
+                ),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    // Turns `async fn f(..) -> T` into `fn f(..) -> Pin<Box<dyn Future<Output = T> + '_>>`, in both the trait and the
+    // impl
+    pub(crate) fn box_future(&mut self, item: &mut TraitItemFn) {
+        if self.asyncness != Asyncness::Native {
+            return;
+        }
+        self.asyncness = Asyncness::Boxed;
+        box_future(&mut self.sig, self.options.send);
+        box_future(&mut item.sig, self.options.send);
+    }
+
+    // Turns `async fn f(..) -> T` into `fn f(..) -> impl Future<Output = T> + Send` in the trait. The impl keeps its
+    // `async fn`, which fulfils that signature as long as its future is `Send`.
+    pub(crate) fn send_future(&self, item: &mut TraitItemFn) {
+        if self.asyncness != Asyncness::Native || !self.options.send {
+            return;
+        }
+        let sig = &mut item.sig;
+        let output = match &sig.output {
+            ReturnType::Default => parse_quote!(()),
+            ReturnType::Type(_, ty) => (**ty).clone(),
+        };
+        sig.asyncness = None;
+        sig.output = parse_quote! {
+            -> impl ::core::future::Future<Output = #output> + ::core::marker::Send
+        };
+    }
+
+    // The method of the generated impl, calling `target`'s concrete method with the same arguments. With `via`, it
+    // calls the method of that field of `self` instead.
+    pub(crate) fn delegate(
+        &self,
+        target: &Type,
+        instance: &Instance,
+        route: Route,
+        strict: bool,
+    ) -> TokenStream {
+        let mut sig = self.sig.clone();
+        instance.fill(&mut sig);
+        let name = &self.target;
+        let args = self.args.iter().map(Arg::forward);
+        let turbofish = self.turbofish();
+        // How `self` is passed on: as it is, or as what it derefs to
+        let (reference, this) = match &self.sig.receiver() {
+            Some(Receiver {
+                reference: Some(_),
+                mutability,
+                ..
+            }) => (quote!(&#mutability), route.this(mutability.is_some())),
+            _ => (TokenStream::new(), route.this(false)),
+        };
+        let mut call = match (route, self.options.iter) {
+            (Route::Field(via), false) => quote!(self.#via.#name #turbofish(#(#args),*)),
+            (Route::Field(via), true) => quote!(::std::boxed::Box::new(
+                ::core::iter::IntoIterator::into_iter(#reference self.#via)
+            )),
+            (_, true) => quote!(::std::boxed::Box::new(
+                ::core::iter::IntoIterator::into_iter(#this)
+            )),
+            (_, false) => {
+                let receiver = self.receiver.then_some(this);
+                let args = receiver.into_iter().chain(args);
+                quote!(<#target>::#name #turbofish(#(#args),*))
+            }
+        };
+        if self.asyncness != Asyncness::Sync {
+            call = quote!(#call.await);
+        }
+        // A mapped value is converted to the trait's return type by the mapping alone
+        if let Some(map) = &self.options.map_return {
+            call = quote!(#map(#call));
+        } else {
+            if self.result {
+                call = quote!(#call.map_err(::core::convert::Into::into));
+            }
+            if self.options.boxed {
+                call = quote!(::std::boxed::Box::new(#call));
+            }
+        }
+        let mut body = match self.asyncness {
+            Asyncness::Boxed => quote!(::std::boxed::Box::pin(async move { #call })),
+            _ => call,
+        };
+        if self.sig.unsafety.is_some() {
+            // The caller took on the concrete method's contract by calling the `unsafe` trait method
+            body = quote!(unsafe { #body });
+        }
+        let check = (strict && matches!(route, Route::Target))
+            .then(|| self.strict_check(&sig, target))
+            .flatten();
+        let cfgs = &self.cfgs;
+        let docs = self.docs(target, route);
+        quote! {
+            #(#cfgs)*
+            #docs
+            #sig {
+                #check
+                #body
+            }
+        }
+    }
+
+    // In `strict` mode, the concrete method has to coerce to a function pointer of exactly the trait method's
+    // signature. Generic and async methods have no such pointer type, so they're left to the call itself.
+    fn strict_check(&self, sig: &Signature, target: &Type) -> Option<TokenStream> {
+        if !sig.generics.params.is_empty() || self.asyncness != Asyncness::Sync {
+            return None;
+        }
+        let inputs = sig.inputs.iter().map(|input| match input {
+            FnArg::Receiver(receiver) => &*receiver.ty,
+            FnArg::Typed(typed) => &*typed.ty,
+        });
+        let unsafety = &sig.unsafety;
+        let output = &sig.output;
+        let name = &self.target;
+        Some(quote_spanned! {sig.ident.span()=>
+            let _: #unsafety fn(#(#inputs),*) #output = <#target>::#name;
+        })
+    }
+}
+
+impl Method {
+    // The trait method's docs, then a line naming the concrete method, linked when it's a path rustdoc can resolve
+    fn docs(&self, target: &Type, route: Route) -> TokenStream {
+        let name = &self.target;
+        let delegate = match (route, target) {
+            (Route::Field(via), _) => {
+                format!(" Delegates to `self.{}.{name}`.", via.to_token_stream())
+            }
+            (_, Type::Path(path)) if path.qself.is_none() => {
+                let segments = path
+                    .path
+                    .segments
+                    .iter()
+                    .map(|segment| segment.ident.to_string());
+                let leading = path.path.leading_colon.map(|_| "::");
+                format!(
+                    " Delegates to [`{}{}::{name}`].",
+                    leading.unwrap_or_default(),
+                    segments.collect::<Vec<_>>().join("::")
+                )
+            }
+            _ => format!(" Delegates to `<{}>::{name}`.", target.to_token_stream()),
+        };
+        let docs = &self.docs;
+        let separator = (!docs.is_empty()).then(|| quote!(#[doc = ""]));
+        quote! {
+            #(#docs)*
+            #separator
+            #[doc = #delegate]
+        }
+    }
+}
+
+fn typed(input: &mut FnArg) -> Option<&mut PatType> {
+    match input {
+        FnArg::Typed(typed) => Some(typed),
+        FnArg::Receiver(_) => None,
+    }
+}
+
+// `Result<T, E>`, or an alias like `io::Result<T>`, going by the last segment of the path
+fn returns_result(output: &ReturnType) -> bool {
+    match output {
+        ReturnType::Type(_, ty) => match &**ty {
+            Type::Path(path) => path
+                .path
+                .segments
+                .last()
+                .is_some_and(|segment| segment.ident == "Result"),
+            _ => false,
+        },
+        ReturnType::Default => false,
+    }
+}
+
+impl Method {
+    // Type parameters are inferred from the arguments, but a const parameter often appears only in the return type,
+    // so when there is one, every type and const parameter is passed explicitly
+    fn turbofish(&self) -> Option<TokenStream> {
+        let params = &self.sig.generics.params;
+        if !params
+            .iter()
+            .any(|param| matches!(param, GenericParam::Const(_)))
+        {
+            return None;
+        }
+        let args = params.iter().filter_map(|param| match param {
+            GenericParam::Type(t) => Some(&t.ident),
+            GenericParam::Const(c) => Some(&c.ident),
+            GenericParam::Lifetime(_) => None,
+        });
+        Some(quote!(::<#(#args),*>))
+    }
+}
+
+fn box_future(sig: &mut Signature, send: bool) {
+    let output = match &sig.output {
+        ReturnType::Default => parse_quote!(()),
+        ReturnType::Type(_, ty) => (**ty).clone(),
+    };
+    sig.asyncness = None;
+    let lifetime = future_lifetime(sig);
+    let send = send.then(|| quote!(+ ::core::marker::Send));
+    sig.output = parse_quote! {
+        -> ::core::pin::Pin<::std::boxed::Box<dyn ::core::future::Future<Output = #output> #send + #lifetime>>
+    };
+}
+
+// The future borrows everything the method was given. When that's just `&self` the elided `'_` covers it. Otherwise
+// every borrow and type parameter is tied to a new `'abx` lifetime, which the future is then bound by.
+fn future_lifetime(sig: &mut Signature) -> Lifetime {
+    let mut elided = ElidedLifetimes {
+        found: 0,
+        replace: None,
+    };
+    for input in &mut sig.inputs {
+        if let FnArg::Typed(typed) = input {
+            elided.visit_type_mut(&mut typed.ty);
+        }
+    }
+    let borrowed_self = matches!(sig.receiver(), Some(receiver) if receiver.reference.is_some());
+    if borrowed_self && elided.found == 0 && sig.generics.params.is_empty() {
+        return parse_quote!('_);
+    }
+
+    let lifetime: Lifetime = parse_quote!('abx);
+    let mut bounds: Vec<WherePredicate> = Vec::new();
+    for param in &mut sig.generics.params {
+        match param {
+            GenericParam::Lifetime(l) => l.bounds.push(lifetime.clone()),
+            GenericParam::Type(t) => t.bounds.push(parse_quote!(#lifetime)),
+            GenericParam::Const(_) => {}
+        }
+    }
+    if let Some(FnArg::Receiver(receiver)) = sig.inputs.first_mut() {
+        match &mut receiver.reference {
+            Some((_, l @ None)) => {
+                *l = Some(lifetime.clone());
+                *receiver.ty = match receiver.mutability {
+                    Some(_) => parse_quote!(&#lifetime mut Self),
+                    None => parse_quote!(&#lifetime Self),
+                };
+            }
+            _ => bounds.push(parse_quote!(Self: #lifetime)),
+        }
+    }
+    let mut replace = ElidedLifetimes {
+        found: 0,
+        replace: Some(lifetime.clone()),
+    };
+    for input in &mut sig.inputs {
+        if let FnArg::Typed(typed) = input {
+            replace.visit_type_mut(&mut typed.ty);
+        }
+    }
+    if let ReturnType::Type(_, ty) = &mut sig.output {
+        replace.visit_type_mut(ty);
+    }
+    sig.generics.params.insert(0, parse_quote!(#lifetime));
+    if !bounds.is_empty() {
+        sig.generics.make_where_clause().predicates.extend(bounds);
+    }
+    lifetime
+}
+
+// Counts, and optionally names, the lifetimes left out of a type: `&T` and `'_`
+struct ElidedLifetimes {
+    found: usize,
+    replace: Option<Lifetime>,
+}
+
+impl VisitMut for ElidedLifetimes {
+    fn visit_type_reference_mut(&mut self, reference: &mut TypeReference) {
+        if reference.lifetime.is_none() {
+            self.found += 1;
+            reference.lifetime = self.replace.clone();
+        }
+        visit_mut::visit_type_reference_mut(self, reference);
+    }
+
+    fn visit_lifetime_mut(&mut self, lifetime: &mut Lifetime) {
+        if lifetime.ident == "_" {
+            self.found += 1;
+            if let Some(replace) = &self.replace {
+                *lifetime = replace.clone();
+            }
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use crate::double::{answerable, asynchronous, opaque, Double};
+use crate::method::Method;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+// A null object whose methods do nothing and return `Default::default()`, for wherever the dependency isn't wanted.
+// Like a `stub(nice)`, it panics instead in methods marked `#[abx(no_default)]` and those `answerable` rules out,
+// except for methods returning `impl Trait`, which can't be implemented at all.
+pub(crate) fn noop(double: &Double) -> syn::Result<TokenStream> {
+    let Double {
+        item,
+        methods,
+        instance,
+        associated,
+        attrs,
+        vis,
+        ..
+    } = double;
+    let noop = double.name("Noop");
+    let fns = methods
+        .iter()
+        .map(|method| noop_method(double, method))
+        .collect::<syn::Result<Vec<_>>>()?;
+    let (trait_path, lifetimes) = (&instance.trait_path, &instance.lifetimes);
+    let doc = format!(
+        "An [`{}`] whose methods do nothing and return `Default::default()`.",
+        item.ident
+    );
+    Ok(quote! {
+        #attrs
+        #[doc = #doc]
+        #[derive(Clone, Copy, Debug, Default)]
+        #vis struct #noop;
+
+        #attrs
+        impl<#(#lifetimes),*> #trait_path for #noop {
+            #(#associated)*
+            #(#fns)*
+        }
+    })
+}
+
+fn noop_method(double: &Double, method: &Method) -> syn::Result<TokenStream> {
+    let mut sig = double.sig(method);
+    let output = double.output(method);
+    if output.as_ref().is_some_and(|output| opaque(method, output)) {
+        return Err(syn::Error::new(
+            method.sig.ident.span(),
+            "`noop` can't implement a method returning `impl Trait`, which has no default to return; give it a \
+            default body and mark it `#[abx(skip)]`",
+        ));
+    }
+    let value = match &output {
+        Some(output) if !method.options.no_default && answerable(method, Some(output)) => {
+            quote!(::core::default::Default::default())
+        }
+        Some(_) => {
+            let message = format!(
+                "`{}` can't return anything from `{}`",
+                double.name("Noop"),
+                method.sig.ident
+            );
+            quote!(::core::panic!(#message))
+        }
+        None => TokenStream::new(),
+    };
+    let cfgs = &method.cfgs;
+    let body = asynchronous(method, &mut sig, output.as_ref(), TokenStream::new(), value);
+    Ok(quote! {
+        #(#cfgs)*
+        #[allow(unused_variables)]
+        #sig {
+            #body
+        }
+    })
+}
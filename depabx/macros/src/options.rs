@@ -0,0 +1,374 @@
+use crate::attr::{self, Entry};
+use crate::fake::FakeOptions;
+use crate::stub::StubMode;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::parse::{Parse, ParseStream, Parser};
+use syn::{
+    parenthesized, Expr, FnArg, Generics, Ident, LitStr, Signature, Token, Type, Visibility,
+};
+
+// Everything that can be written inside `#[wrap(...)]`: the target types first, then the options.
+pub(crate) struct WrapOptions {
+    pub targets: Vec<Type>,
+    pub futures: Futures,
+    // How trait method names map to concrete ones
+    pub naming: Naming,
+    // `cfg(not(test))`: the configuration predicate the generated impls are compiled under
+    pub cfg: Option<TokenStream>,
+    // `feature = "s3"`: the cargo feature the generated impls are compiled under, along with any `cfg`
+    pub feature: Option<LitStr>,
+    // `vis = "pub(crate)"`: the visibility of the named items generated next to the impls, the trait's unless given
+    pub vis: Option<Visibility>,
+    // `doc(hidden)`: generated items are left out of rustdoc
+    pub doc_hidden: bool,
+    // `via = client`: the field of the target whose methods are called, instead of the target's own
+    pub via: Option<Expr>,
+    // `newtype = ProdLogger`: a newtype around the target that the trait is implemented on instead
+    pub newtype: Option<Ident>,
+    // `module = abstractions`: the inline module everything generated for the trait is put in
+    pub module: Option<Ident>,
+    // `strict`: trait signatures have to match the concrete ones exactly, with no adapters
+    pub strict: bool,
+    // `deref`: the trait is also implemented for `&T`, `&mut T`, `Box<T>`, `Rc<T>` and `Arc<T>` of the target `T`, or
+    // only `&mut T` and `Box<T>` when a method takes `&mut self`. Other smart pointers aren't covered: a blanket impl
+    // over `Deref` would conflict with the impl for the target itself whenever it's another crate's type.
+    pub deref: bool,
+    // `dyn`: the trait has to stay object safe
+    pub object_safe: bool,
+    // `send_sync`: `Send + Sync` are added to the trait's supertraits, which the target is then checked against
+    pub send_sync: bool,
+    // `role`: the trait is one of several over the same target, which mustn't declare the same method. Only roles in
+    // the same module are checked against each other.
+    pub role: bool,
+    // `dump = "target/depabx/slack.rs"`: the file the expansion is written to, relative to the crate root
+    pub dump: Option<LitStr>,
+    // `methods(warn(&self, message: &str))`: the concrete methods to declare in the trait, with their signatures
+    pub methods: Vec<Signature>,
+    // `fake`: a recording fake of the trait is generated next to the impls
+    pub fake: Option<FakeOptions>,
+    // `stub`: a stub of the trait, configured per method with closures, is generated next to the impls
+    pub stub: Option<StubMode>,
+    // `store`: an in-memory store of the trait's gets, puts and deletes, backed by a `HashMap`, is generated next to
+    // the impls
+    pub store: bool,
+    // `noop`: a null object whose methods do nothing and return `Default::default()` is generated next to the impls.
+    // Methods it has nothing to return from, as for a `stub(nice)`, panic.
+    pub noop: bool,
+    // `spy`: a spy owning the target, passing calls on to it and recording them, is generated next to the impls
+    pub spy: bool,
+    // `ext`: methods marked `#[abx(ext)]` are moved to an `AbxXxxExt` trait implemented for every implementer
+    pub ext: bool,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Futures {
+    // `async fn` stays `async fn` in the trait
+    Native,
+    // `futures = "boxed"`: async methods return `Pin<Box<dyn Future>>`, which keeps the trait object safe
+    Boxed,
+}
+
+impl Parse for WrapOptions {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut targets = Vec::new();
+        let mut entries = Vec::new();
+        while !input.is_empty() {
+            if Entry::peek(input) {
+                entries.push(input.parse::<Entry>()?);
+            } else if entries.is_empty() {
+                targets.push(input.parse::<Type>()?);
+            } else {
+                return Err(input.error("target types go before the options"));
+            }
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+        let mut options = WrapOptions {
+            targets,
+            futures: Futures::Native,
+            naming: Naming {
+                prefix: "abx_".to_string(),
+                rename_all: None,
+                target_prefix: String::new(),
+            },
+            cfg: None,
+            feature: None,
+            vis: None,
+            doc_hidden: false,
+            via: None,
+            newtype: None,
+            module: None,
+            strict: false,
+            deref: false,
+            object_safe: false,
+            ext: false,
+            dump: None,
+            methods: Vec::new(),
+            fake: None,
+            stub: None,
+            store: false,
+            noop: false,
+            spy: false,
+            role: false,
+            send_sync: false,
+        };
+        for entry in &entries {
+            match entry.name().to_string().as_str() {
+                "futures" => {
+                    let value = attr::string(entry)?;
+                    options.futures = match value.value().as_str() {
+                        "native" => Futures::Native,
+                        "boxed" => Futures::Boxed,
+                        _ => {
+                            return Err(syn::Error::new(
+                                value.span(),
+                                "expected \"native\" or \"boxed\"",
+                            ))
+                        }
+                    };
+                }
+                "prefix" => options.naming.prefix = attr::string(entry)?.value(),
+                "rename_all" => {
+                    let case = attr::string(entry)?;
+                    options.naming.rename_all = Some(match case.value().as_str() {
+                        "PascalCase" => Case::Pascal,
+                        "camelCase" => Case::Camel,
+                        "SCREAMING_SNAKE_CASE" => Case::ScreamingSnake,
+                        _ => return Err(syn::Error::new(
+                            case.span(),
+                            "expected \"PascalCase\", \"camelCase\" or \"SCREAMING_SNAKE_CASE\"",
+                        )),
+                    });
+                }
+                "target_prefix" => options.naming.target_prefix = attr::string(entry)?.value(),
+                "cfg" => options.cfg = Some(attr::list(entry)?),
+                "feature" => options.feature = Some(attr::string(entry)?),
+                "dump" => options.dump = Some(attr::string(entry)?),
+                "methods" => options.methods = method_hints.parse2(attr::list(entry)?)?,
+                "via" => match entry {
+                    Entry::Value(_, via) => options.via = Some(via.clone()),
+                    _ => {
+                        return Err(syn::Error::new(
+                            entry.name().span(),
+                            "expected `via = field`",
+                        ))
+                    }
+                },
+                "newtype" => options.newtype = Some(attr::ident(entry)?),
+                "module" => options.module = Some(attr::ident(entry)?),
+                "deref" => {
+                    attr::flag(entry)?;
+                    options.deref = true;
+                }
+                "send_sync" => {
+                    attr::flag(entry)?;
+                    options.send_sync = true;
+                }
+                "role" => {
+                    attr::flag(entry)?;
+                    options.role = true;
+                }
+                "fake" => options.fake = Some(FakeOptions::parse(entry)?),
+                "stub" => options.stub = Some(StubMode::parse(entry)?),
+                "store" => {
+                    attr::flag(entry)?;
+                    options.store = true;
+                }
+                "noop" => {
+                    attr::flag(entry)?;
+                    options.noop = true;
+                }
+                "spy" => {
+                    attr::flag(entry)?;
+                    options.spy = true;
+                }
+                "ext" => {
+                    attr::flag(entry)?;
+                    options.ext = true;
+                }
+                "dyn" => {
+                    attr::flag(entry)?;
+                    options.object_safe = true;
+                }
+                "strict" => {
+                    attr::flag(entry)?;
+                    options.strict = true;
+                }
+                "vis" => options.vis = Some(attr::string(entry)?.parse()?),
+                "doc" => {
+                    let doc = attr::list(entry)?;
+                    if doc.to_string() != "hidden" {
+                        return Err(syn::Error::new_spanned(doc, "expected `doc(hidden)`"));
+                    }
+                    options.doc_hidden = true;
+                }
+                _ => return Err(attr::unknown(entry, "wrap")),
+            }
+        }
+        if let (true, Some(entry)) = (
+            options.strict,
+            entries
+                .iter()
+                .find(|entry| matches!(entry.name().to_string().as_str(), "via" | "newtype")),
+        ) {
+            return Err(syn::Error::new(
+                entry.name().span(),
+                "`strict` checks the target's own methods, so it can't be combined with delegating through a field",
+            ));
+        }
+        if let (true, Some(entry)) = (
+            options.deref,
+            entries
+                .iter()
+                .find(|entry| matches!(entry.name().to_string().as_str(), "via" | "newtype")),
+        ) {
+            return Err(syn::Error::new(
+                entry.name().span(),
+                "`deref` forwards to the target's own methods, so it can't be combined with delegating through a field",
+            ));
+        }
+        if options.deref && options.targets.len() > 1 {
+            return Err(input.error(
+                "`deref` needs a single target, since each pointer derefs to only one type",
+            ));
+        }
+        if let Some(newtype) = &options.newtype {
+            if options.targets.len() > 1 || options.via.is_some() {
+                return Err(syn::Error::new(
+                    newtype.span(),
+                    "a newtype wraps a single target, and is delegated through its only field",
+                ));
+            }
+        }
+        Ok(options)
+    }
+}
+
+// The entries of `methods(...)`: a concrete method's name, then its signature as it would follow `fn`
+fn method_hints(input: ParseStream) -> syn::Result<Vec<Signature>> {
+    let mut hints = Vec::new();
+    while !input.is_empty() {
+        let asyncness = input.parse()?;
+        let ident = input.parse()?;
+        let mut generics: Generics = input.parse()?;
+        let content;
+        let paren_token = parenthesized!(content in input);
+        let inputs = content.parse_terminated(FnArg::parse, Token![,])?;
+        let output = input.parse()?;
+        generics.where_clause = input.parse()?;
+        hints.push(Signature {
+            constness: None,
+            asyncness,
+            unsafety: None,
+            abi: None,
+            fn_token: Default::default(),
+            ident,
+            generics,
+            paren_token,
+            inputs,
+            variadic: None,
+            output,
+        });
+        if !input.is_empty() {
+            input.parse::<Token![,]>()?;
+        }
+    }
+    Ok(hints)
+}
+
+impl WrapOptions {
+    // The `#[cfg]` the impls are compiled under, combining `cfg` and `feature`
+    pub(crate) fn impl_cfg(&self) -> Option<TokenStream> {
+        let feature = self
+            .feature
+            .as_ref()
+            .map(|feature| quote!(feature = #feature));
+        match (&self.cfg, feature) {
+            (Some(cfg), Some(feature)) => Some(quote!(#[cfg(all(#feature, #cfg))])),
+            (Some(cfg), None) => Some(quote!(#[cfg(#cfg)])),
+            (None, Some(feature)) => Some(quote!(#[cfg(#feature)])),
+            (None, None) => None,
+        }
+    }
+
+    // The attributes of the test doubles, which unlike the impls aren't subject to `cfg`
+    pub(crate) fn double_attrs(&self) -> TokenStream {
+        let hidden = self.doc_hidden.then(|| quote!(#[doc(hidden)]));
+        quote!(#hidden)
+    }
+
+    // The attributes of every item generated next to the trait: the impls and everything that supports them
+    pub(crate) fn glue_attrs(&self) -> TokenStream {
+        let cfg = self.impl_cfg();
+        let hidden = self.doc_hidden.then(|| quote!(#[doc(hidden)]));
+        quote!(#cfg #hidden)
+    }
+}
+
+// How the name of a trait method is turned into the name of the concrete method it calls
+pub(crate) struct Naming {
+    // `prefix = "dep_"`: stripped from trait method names to find the concrete methods, `abx_` unless given
+    pub prefix: String,
+    // `rename_all = "PascalCase"`: the case the rest of the name is converted to
+    pub rename_all: Option<Case>,
+    // `target_prefix = "Logger"`: prepended to the converted name
+    pub target_prefix: String,
+}
+
+#[derive(Clone, Copy)]
+pub(crate) enum Case {
+    Pascal,
+    Camel,
+    ScreamingSnake,
+}
+
+impl Naming {
+    // The name of the concrete method for a trait method named the prefix followed by `rest`
+    pub(crate) fn target(&self, rest: &str) -> String {
+        let words = rest.split('_').filter(|word| !word.is_empty());
+        let converted = match self.rename_all {
+            None => rest.to_string(),
+            Some(Case::ScreamingSnake) => rest.to_uppercase(),
+            Some(case) => words
+                .enumerate()
+                .map(|(i, word)| match (case, i) {
+                    (Case::Camel, 0) => word.to_string(),
+                    _ => {
+                        let mut chars = word.chars();
+                        let first = chars.next().map(char::to_uppercase);
+                        first.into_iter().flatten().chain(chars).collect()
+                    }
+                })
+                .collect(),
+        };
+        format!("{}{converted}", self.target_prefix)
+    }
+}
+
+// The `#[abx(...)]` options on the trait itself.
+#[derive(Clone, Default)]
+pub(crate) struct TraitOptions {
+    // `type Output = Hits`: the associated types of the impl
+    pub types: Vec<(Ident, Type)>,
+    // `const MAX_BATCH = Uploader::MAX_BATCH`: the values of its associated consts
+    pub consts: Vec<(Ident, Expr)>,
+    // `send`: every async method's future is `Send`, as with `#[abx(send)]` on each of them
+    pub send: bool,
+}
+
+impl TraitOptions {
+    pub(crate) fn parse(entries: Vec<Entry>) -> syn::Result<Self> {
+        let mut options = TraitOptions::default();
+        for entry in entries {
+            match entry {
+                Entry::Type(name, ty) => options.types.push((name, ty)),
+                Entry::Const(name, value) => options.consts.push((name, value)),
+                Entry::Flag(name) if name == "send" => options.send = true,
+                _ => return Err(attr::unknown(&entry, "trait")),
+            }
+        }
+        Ok(options)
+    }
+}
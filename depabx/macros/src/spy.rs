@@ -0,0 +1,145 @@
+use crate::double::{asynchronous, Double};
+use crate::expand::mentions_self;
+use crate::fake::{inspection, record};
+use crate::method::{Asyncness, Method};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_quote, FnArg, Receiver, Type};
+
+// A spy owning the real dependency, whose impl of the trait records each call before making it on the real one
+pub(crate) fn spy(double: &Double, real: &Type) -> TokenStream {
+    let Double {
+        item,
+        methods,
+        instance,
+        associated,
+        attrs,
+        vis,
+        ..
+    } = double;
+    let spy = double.name("Spy");
+    let state = format_ident!("__{}State", spy);
+    let generics = &instance.impl_generics;
+    let self_ty: Type = parse_quote!(#spy #generics);
+    let fields = methods.iter().map(|method| &method.sig.ident);
+    let captures = methods
+        .iter()
+        .map(|method| double.capture(method, &self_ty).0);
+    // Shared across threads, when the trait needs its implementers to be
+    let kind = match double.shared() {
+        true => quote!(sync),
+        false => quote!(local),
+    };
+    let inspection = inspection(double, &spy, &self_ty);
+    let trait_path = &instance.trait_path;
+    let fns = methods
+        .iter()
+        .map(|method| spy_method(double, method, real, &self_ty));
+    let doc = format!(
+        "A spy on a real [`{}`], which passes every call on to it and records them.",
+        item.ident
+    );
+    quote! {
+        #attrs
+        #[doc = #doc]
+        #vis struct #spy #generics {
+            real: #real,
+            state: ::std::sync::Arc<#state>,
+        }
+
+        #attrs
+        #[derive(Default)]
+        struct #state {
+            #(#fields: ::depabx::__private::#kind::Method<#captures, ()>,)*
+        }
+
+        #attrs
+        impl #generics #spy #generics {
+            /// A spy on `real`, with no calls recorded.
+            pub fn new(real: #real) -> Self {
+                #spy {
+                    real,
+                    state: ::std::default::Default::default(),
+                }
+            }
+
+            /// The real dependency the calls are passed on to.
+            pub fn real(&self) -> &#real {
+                &self.real
+            }
+
+            /// The real dependency, giving up the spy.
+            pub fn into_inner(self) -> #real {
+                self.real
+            }
+
+            #inspection
+        }
+
+        #attrs
+        impl #generics #trait_path for #spy #generics {
+            #(#associated)*
+            #(#fns)*
+        }
+    }
+}
+
+// Passes the call on to the real dependency after recording it. A method whose signature names `Self` can't be passed
+// on, since the real one takes or returns the real type where the spy's has the spy, so it panics instead.
+fn spy_method(double: &Double, method: &Method, real: &Type, self_ty: &Type) -> TokenStream {
+    let mut sig = double.sig(method);
+    let name = &sig.ident;
+    let output = double.output(method);
+    let cfgs = &method.cfgs;
+    if mentions_self(&method.sig) {
+        let message = format!(
+            "`{}` can't pass `{name}` on to the real `{}`, since its signature names `Self`",
+            double.name("Spy"),
+            quote!(#real).to_string().replace(' ', ""),
+        );
+        let body = asynchronous(
+            method,
+            &mut sig,
+            output.as_ref(),
+            TokenStream::new(),
+            quote!(::core::panic!(#message)),
+        );
+        return quote! {
+            #(#cfgs)*
+            #[allow(unused_variables)]
+            #sig {
+                #body
+            }
+        };
+    }
+    let trait_path = &double.instance.trait_path;
+    let this = match method.sig.inputs.first() {
+        Some(FnArg::Receiver(Receiver {
+            reference: Some(_),
+            mutability,
+            ..
+        })) => Some(quote!(&#mutability self.real)),
+        Some(FnArg::Receiver(_)) => Some(quote!(self.real)),
+        _ => None,
+    };
+    let args = this.into_iter().chain(method.args.iter().map(|arg| {
+        let name = &arg.name;
+        quote!(#name)
+    }));
+    let mut call = quote!(<#real as #trait_path>::#name(#(#args),*));
+    if method.asyncness != Asyncness::Sync {
+        call = quote!(#call.await);
+    }
+    if sig.unsafety.is_some() {
+        // The caller took on the method's contract by calling the spy's
+        call = quote!(unsafe { #call });
+    }
+    let record = record(double, method, self_ty, false);
+    let body = asynchronous(method, &mut sig, output.as_ref(), record, call);
+    quote! {
+        #(#cfgs)*
+        #sig {
+            #body
+        }
+    }
+}
@@ -0,0 +1,259 @@
+use crate::double::{answerable, asynchronous, typed_args, Double};
+use crate::method::Method;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{parse_quote, GenericArgument, Ident, PathArguments, Type};
+
+// What a method of a CRUD-shaped trait does to the store's entries
+#[derive(Clone, Copy, PartialEq)]
+enum Role {
+    Get,
+    Put,
+    Delete,
+}
+
+impl Role {
+    // The role named by `#[abx(store = "...")]`, or else by the first word of the method's name
+    fn of(double: &Double, method: &Method) -> syn::Result<Self> {
+        if let Some(role) = &method.options.store {
+            return match role.value().as_str() {
+                "get" => Ok(Role::Get),
+                "put" => Ok(Role::Put),
+                "delete" => Ok(Role::Delete),
+                _ => Err(syn::Error::new(
+                    role.span(),
+                    "expected \"get\", \"put\" or \"delete\"",
+                )),
+            };
+        }
+        let short = double.short(method).to_string();
+        match short.split('_').next().unwrap_or_default() {
+            "get" | "fetch" | "find" | "load" | "read" | "lookup" => Ok(Role::Get),
+            "put" | "insert" | "set" | "save" | "store" | "write" | "upsert" => Ok(Role::Put),
+            "delete" | "remove" | "del" | "evict" => Ok(Role::Delete),
+            _ => Err(syn::Error::new(
+                method.sig.ident.span(),
+                "`store` can't tell whether this method gets, puts or deletes; mark it with \
+                `#[abx(store = \"get\")]`, \"put\" or \"delete\"",
+            )),
+        }
+    }
+}
+
+// An in-memory store implementing a trait of gets, puts and deletes with a `HashMap`, so reads see earlier writes
+pub(crate) fn store(double: &Double) -> syn::Result<TokenStream> {
+    let Double {
+        item,
+        methods,
+        instance,
+        associated,
+        attrs,
+        vis,
+        ..
+    } = double;
+    let store = double.name("InMemory");
+    let self_ty: Type = parse_quote!(#store);
+    let mut roles = Vec::new();
+    for method in methods.iter() {
+        if !method.receiver || !answerable(method, double.output(method).as_ref()) {
+            return Err(syn::Error::new(
+                method.sig.ident.span(),
+                "`store` can only implement methods taking `self` and returning types that can be named outside them",
+            ));
+        }
+        let role = Role::of(double, method)?;
+        let needed = match role {
+            Role::Put => 2,
+            Role::Get | Role::Delete => 1,
+        };
+        if typed_args(method).count() < needed {
+            return Err(syn::Error::new(
+                method.sig.ident.span(),
+                match role {
+                    Role::Put => "a store's put takes the key and then the value",
+                    Role::Get | Role::Delete => "a store's get and delete take the key",
+                },
+            ));
+        }
+        roles.push((method, role));
+    }
+    let key = roles
+        .first()
+        .map(|(method, _)| owned(double, typed_args(method).next().unwrap().0, &self_ty).0);
+    let value = roles
+        .iter()
+        .find(|(_, role)| *role == Role::Put)
+        .map(|(method, _)| owned(double, typed_args(method).nth(1).unwrap().0, &self_ty).0)
+        .or_else(|| {
+            roles
+                .iter()
+                .find(|(_, role)| *role == Role::Get)
+                .and_then(|(method, _)| {
+                    let output = double.outside(&double.output(method)?, &self_ty);
+                    option(ok(&output)).cloned()
+                })
+        });
+    let (Some(key), Some(value)) = (key, value) else {
+        return Err(syn::Error::new(
+            item.ident.span(),
+            "`store` needs a put method taking the value, or a get method returning it in an `Option`",
+        ));
+    };
+    let fns = roles
+        .iter()
+        .map(|(method, role)| store_method(double, method, *role, &self_ty))
+        .collect::<syn::Result<Vec<_>>>()?;
+    let (trait_path, lifetimes) = (&instance.trait_path, &instance.lifetimes);
+    let doc = format!(
+        "An in-memory [`{}`] keeping what's put in it in a `HashMap`, so that later gets see it.",
+        item.ident
+    );
+    Ok(quote! {
+        #attrs
+        #[doc = #doc]
+        #vis struct #store {
+            entries: ::std::sync::Mutex<::std::collections::HashMap<#key, #value>>,
+        }
+
+        #attrs
+        impl #store {
+            /// An empty store.
+            pub fn new() -> Self {
+                Self::with_entries([])
+            }
+
+            /// A store holding `entries` to begin with.
+            pub fn with_entries(entries: impl ::core::iter::IntoIterator<Item = (#key, #value)>) -> Self {
+                #store {
+                    entries: ::std::sync::Mutex::new(entries.into_iter().collect()),
+                }
+            }
+
+            /// Whether the store has an entry for `key`.
+            pub fn contains<Q>(&self, key: &Q) -> bool
+            where
+                #key: ::std::borrow::Borrow<Q>,
+                Q: ::std::hash::Hash + ::core::cmp::Eq + ?::core::marker::Sized,
+            {
+                self.entries().contains_key(key)
+            }
+
+            fn entries(&self) -> ::std::sync::MutexGuard<'_, ::std::collections::HashMap<#key, #value>> {
+                self.entries
+                    .lock()
+                    .unwrap_or_else(::std::sync::PoisonError::into_inner)
+            }
+        }
+
+        #attrs
+        impl ::core::default::Default for #store {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        #attrs
+        impl<#(#lifetimes),*> #trait_path for #store {
+            #(#associated)*
+            #(#fns)*
+        }
+    })
+}
+
+// The owned type an argument is kept as, and how it's made from the argument: a borrowed argument with `ToOwned`,
+// others as they are
+fn owned(double: &Double, ty: &Type, self_ty: &Type) -> (Type, fn(&Ident) -> TokenStream) {
+    match ty {
+        Type::Reference(reference) => {
+            let elem = double.outside(&reference.elem, self_ty);
+            (
+                parse_quote!(<#elem as ::std::borrow::ToOwned>::Owned),
+                |name| quote!(::std::borrow::ToOwned::to_owned(#name)),
+            )
+        }
+        _ => (double.outside(ty, self_ty), |name| quote!(#name)),
+    }
+}
+
+fn store_method(
+    double: &Double,
+    method: &Method,
+    role: Role,
+    self_ty: &Type,
+) -> syn::Result<TokenStream> {
+    let mut sig = double.sig(method);
+    let mut args = typed_args(method).map(|(ty, arg)| owned(double, ty, self_ty).1(&arg.name));
+    let key = args.next();
+    let entry = match role {
+        Role::Get => quote!(entries.get(&#key).cloned()),
+        Role::Put => {
+            let value = args.next();
+            quote!(entries.insert(#key, #value))
+        }
+        Role::Delete => quote!(entries.remove(&#key)),
+    };
+    let output = double.output(method);
+    let entry = quote!({
+        let mut entries = self.entries();
+        #entry
+    });
+    let (eager, value) = match &output {
+        None => (quote!(#entry;), TokenStream::new()),
+        Some(output) => {
+            let value = returned(entry, output)?;
+            (quote!(let value = #value;), quote!(value))
+        }
+    };
+    let cfgs = &method.cfgs;
+    let body = asynchronous(method, &mut sig, output.as_ref(), eager, value);
+    Ok(quote! {
+        #(#cfgs)*
+        #[allow(unused_variables)]
+        #sig {
+            #body
+        }
+    })
+}
+
+// What the method returns, made from the `Option` of the entry it got, replaced or removed: the `Option` itself,
+// whether there was one, nothing, or any of those as the `Ok` of a `Result`
+fn returned(entry: TokenStream, output: &Type) -> syn::Result<TokenStream> {
+    match output {
+        Type::Tuple(tuple) if tuple.elems.is_empty() => Ok(quote!({ #entry; })),
+        Type::Path(path) if path.path.is_ident("bool") => Ok(quote!(#entry.is_some())),
+        _ if option(output).is_some() => Ok(entry),
+        _ if argument(output, "Result").is_some() => {
+            let ok = returned(entry, ok(output))?;
+            Ok(quote!(::core::result::Result::Ok(#ok)))
+        }
+        _ => Err(syn::Error::new(
+            output.span(),
+            "a store's methods can return `()`, `bool`, an `Option` of the value, or a `Result` of one of those",
+        )),
+    }
+}
+
+// The single type argument of the last segment of a path named `name`, as `T` in `Option<T>`
+fn argument<'a>(ty: &'a Type, name: &str) -> Option<&'a Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first() {
+        Some(GenericArgument::Type(arg)) if segment.ident == name => Some(arg),
+        _ => None,
+    }
+}
+
+fn option(ty: &Type) -> Option<&Type> {
+    argument(ty, "Option")
+}
+
+// The `Ok` type of a `Result`, or the type itself
+fn ok(ty: &Type) -> &Type {
+    argument(ty, "Result").unwrap_or(ty)
+}
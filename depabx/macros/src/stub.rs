@@ -0,0 +1,292 @@
+use crate::attr::{self, Entry};
+use crate::double::{answerable, asynchronous, recorded, typed_args, Double};
+use crate::method::{Asyncness, Method};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_quote, FnArg, GenericParam, Type};
+
+// What a stub's unconfigured methods do
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum StubMode {
+    // `stub`: nothing, for methods returning `()`, and panic for the rest
+    Lenient,
+    // `stub(strict)`: panic with the call, whatever the method returns
+    Strict,
+    // `stub(nice)`: return `Default::default()`, unless the method is marked `#[abx(no_default)]`
+    Nice,
+}
+
+impl StubMode {
+    pub(crate) fn parse(entry: &Entry) -> syn::Result<Self> {
+        let tokens = match entry {
+            Entry::Flag(_) => return Ok(StubMode::Lenient),
+            Entry::List(_, tokens) => tokens,
+            _ => {
+                return Err(syn::Error::new(
+                    entry.name().span(),
+                    "expected `stub`, `stub(strict)` or `stub(nice)`",
+                ))
+            }
+        };
+        let mode: Entry = syn::parse2(tokens.clone())?;
+        match mode.name().to_string().as_str() {
+            "strict" => {
+                attr::flag(&mode)?;
+                Ok(StubMode::Strict)
+            }
+            "nice" => {
+                attr::flag(&mode)?;
+                Ok(StubMode::Nice)
+            }
+            _ => Err(attr::unknown(&mode, "stub")),
+        }
+    }
+}
+
+// A stub the test configures per method with closures, and the impl of the trait that calls them
+pub(crate) fn stub(double: &Double, mode: StubMode) -> TokenStream {
+    let Double {
+        item,
+        methods,
+        instance,
+        associated,
+        attrs,
+        vis,
+        ..
+    } = double;
+    let stub = double.name("Stub");
+    let self_ty: Type = parse_quote!(#stub<'a>);
+    let shared = double
+        .shared()
+        .then(|| quote!(+ ::core::marker::Send + ::core::marker::Sync));
+    let configurable: Vec<_> = methods
+        .iter()
+        .filter_map(|method| {
+            let closure = closure(double, method, &self_ty)?;
+            let name = &method.sig.ident;
+            let doc = format!("Answers calls to `{name}` with `f`.");
+            let on = format_ident!("on_{}", double.short(method));
+            let alias = format_ident!("__on_{}", name);
+            let cfgs = &method.cfgs;
+            let field = quote! {
+                #(#cfgs)*
+                #name: ::core::option::Option<::std::boxed::Box<dyn #closure #shared + 'a>>,
+            };
+            let setter = quote! {
+                #(#cfgs)*
+                #[doc = #doc]
+                pub fn #on(mut self, f: impl #closure #shared + 'a) -> Self {
+                    self.#name = ::core::option::Option::Some(::std::boxed::Box::new(f));
+                    self
+                }
+
+                // What `depabx::fake!` calls, since it only knows the trait's method names
+                #(#cfgs)*
+                #[doc(hidden)]
+                pub fn #alias(self, f: impl #closure #shared + 'a) -> Self {
+                    self.#on(f)
+                }
+            };
+            let member = quote! {
+                #(#cfgs)*
+                pub #name: (),
+            };
+            let none = quote! {
+                #(#cfgs)*
+                #name: ::core::option::Option::None,
+            };
+            Some((field, none, setter, member))
+        })
+        .collect();
+    let fields = configurable.iter().map(|(field, ..)| field);
+    let nones = configurable.iter().map(|(_, none, ..)| none);
+    let setters = configurable.iter().map(|(_, _, setter, _)| setter);
+    let members = configurable.iter().map(|(.., member)| member);
+    let methods_struct = format_ident!("__{}Methods", stub);
+    let fns = methods
+        .iter()
+        .map(|method| stub_method(double, method, &self_ty, mode));
+    let lifetimes = &instance.lifetimes;
+    let trait_path = &instance.trait_path;
+    let doc = format!(
+        "A stub [`{}`] whose methods are answered by closures, configured with its `on_` methods.",
+        item.ident
+    );
+    quote! {
+        #attrs
+        #[doc = #doc]
+        #vis struct #stub<'a> {
+            #(#fields)*
+            marker: ::core::marker::PhantomData<&'a ()>,
+        }
+
+        // The methods `depabx::fake!` has to be given when it doesn't end with `..`, which it destructures
+        #attrs
+        #[doc(hidden)]
+        #[allow(dead_code)]
+        #vis struct #methods_struct {
+            #(#members)*
+        }
+
+        #attrs
+        impl ::depabx::__private::Inline for #stub<'_> {
+            type Methods = #methods_struct;
+        }
+
+        #attrs
+        impl<'a> #stub<'a> {
+            /// A stub with no method configured.
+            pub fn new() -> Self {
+                #stub {
+                    #(#nones)*
+                    marker: ::core::marker::PhantomData,
+                }
+            }
+
+            #(#setters)*
+        }
+
+        #attrs
+        impl ::core::default::Default for #stub<'_> {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        #attrs
+        impl<'a, #(#lifetimes),*> #trait_path for #stub<'a> {
+            #(#associated)*
+            #(#fns)*
+        }
+    }
+}
+
+// The closure type that answers the method, `Fn(&str) -> f64`, unless there's none that could: a method without a
+// receiver has no stub to look it up in, and see `answerable` for the rest
+fn closure(double: &Double, method: &Method, self_ty: &Type) -> Option<TokenStream> {
+    let output = double.output(method);
+    if !method.receiver || !answerable(method, output.as_ref()) {
+        return None;
+    }
+    let inputs = method.sig.inputs.iter().filter_map(|input| match input {
+        FnArg::Typed(typed) => Some(double.outside(&typed.ty, self_ty)),
+        FnArg::Receiver(_) => None,
+    });
+    let output = output.map(|ty| {
+        let ty = double.outside(&ty, self_ty);
+        quote!(-> #ty)
+    });
+    let lifetimes: Vec<_> = method
+        .sig
+        .generics
+        .params
+        .iter()
+        .filter_map(|param| match param {
+            GenericParam::Lifetime(lifetime) => Some(&lifetime.lifetime),
+            _ => None,
+        })
+        .collect();
+    let binder = (!lifetimes.is_empty()).then(|| quote!(for<#(#lifetimes),*>));
+    Some(quote!(#binder ::core::ops::Fn(#(#inputs),*) #output))
+}
+
+fn stub_method(double: &Double, method: &Method, self_ty: &Type, mode: StubMode) -> TokenStream {
+    let mut sig = double.sig(method);
+    let name = &sig.ident;
+    let stub = double.name("Stub");
+    let output = double.output(method);
+    let args = method.args.iter().map(|arg| &arg.name);
+    let unconfigured = match (&output, mode) {
+        (_, StubMode::Strict) => unexpected(double, method, self_ty),
+        (None, _) => quote!({}),
+        (Some(output), StubMode::Nice)
+            if !method.options.no_default && answerable(method, Some(output)) =>
+        {
+            quote!(::core::default::Default::default())
+        }
+        (Some(_), _) => {
+            let message = match closure(double, method, self_ty) {
+                Some(_) => format!(
+                    "`{stub}` wasn't given anything to return from `{name}`; configure it with `on_{}`",
+                    double.short(method)
+                ),
+                None => format!("`{stub}` can't return anything from `{name}`"),
+            };
+            quote!(::core::panic!(#message))
+        }
+    };
+    let (eager, value) = match (closure(double, method, self_ty), &output) {
+        (None, _) => (TokenStream::new(), unconfigured),
+        (Some(_), None) => (
+            quote! {
+                match &self.#name {
+                    ::core::option::Option::Some(f) => f(#(#args),*),
+                    ::core::option::Option::None => #unconfigured,
+                }
+            },
+            TokenStream::new(),
+        ),
+        (Some(_), Some(_)) => (
+            quote! {
+                let value = match &self.#name {
+                    ::core::option::Option::Some(f) => f(#(#args),*),
+                    ::core::option::Option::None => #unconfigured,
+                };
+            },
+            quote!(value),
+        ),
+    };
+    let cfgs = &method.cfgs;
+    let track_caller = (method.asyncness == Asyncness::Sync).then(|| quote!(#[track_caller]));
+    let body = asynchronous(method, &mut sig, output.as_ref(), eager, value);
+    quote! {
+        #(#cfgs)*
+        #[allow(unused_variables)]
+        #track_caller
+        #sig {
+            #body
+        }
+    }
+}
+
+// The panic of a strict stub's unconfigured method: the call as it was written, where it was made from when that's
+// known, and the methods the stub was configured for
+fn unexpected(double: &Double, method: &Method, self_ty: &Type) -> TokenStream {
+    let (trait_name, name) = (&double.item.ident, &method.sig.ident);
+    let args = typed_args(method).map(|(ty, arg)| recorded(method, ty, &arg.name));
+    let configured = double.methods.iter().filter_map(|method| {
+        closure(double, method, self_ty)?;
+        let (name, cfgs) = (&method.sig.ident, &method.cfgs);
+        let text = name.to_string();
+        Some(quote! {
+            #(#cfgs)*
+            if self.#name.is_some() {
+                configured.push(#text);
+            }
+        })
+    });
+    // The caller is only known to a synchronous method, which is `#[track_caller]`
+    let (message, location) = match method.asyncness {
+        Asyncness::Sync => (
+            format!("unexpected call to {trait_name}::{name}({{}}) at {{}}\nconfigured: {{}}"),
+            Some(quote!(::core::panic::Location::caller(),)),
+        ),
+        _ => (
+            format!("unexpected call to {trait_name}::{name}({{}})\nconfigured: {{}}"),
+            None,
+        ),
+    };
+    quote!({
+        let mut configured: ::std::vec::Vec<&str> = ::std::vec::Vec::new();
+        #(#configured)*
+        ::core::panic!(
+            #message,
+            <[::std::string::String]>::join(&[#(#args),*], ", "),
+            #location
+            match configured.is_empty() {
+                true => ::std::string::String::from("nothing"),
+                false => configured.join(", "),
+            },
+        )
+    })
+}
@@ -0,0 +1,165 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use std::collections::HashMap;
+use syn::spanned::Spanned;
+use syn::visit::Visit;
+use syn::visit_mut::{self, VisitMut};
+use syn::{
+    Expr, GenericArgument, GenericParam, Ident, ItemTrait, Lifetime, PathArguments, Signature,
+    Type, TypeParamBound,
+};
+
+// How the trait is implemented for one target
+#[derive(Clone)]
+pub(crate) struct Instance {
+    pub trait_path: TokenStream,
+    // The lifetimes named in the target, as in `Transaction<'conn>`, which the impl has to declare
+    pub impl_generics: TokenStream,
+    pub lifetimes: Vec<Lifetime>,
+    // The trait's generic parameters, and the target's arguments they stand for
+    params: HashMap<Ident, GenericArgument>,
+}
+
+impl Instance {
+    // A trait method's signature as the impl writes it, with the trait's parameters replaced by their arguments
+    pub(crate) fn fill(&self, sig: &mut Signature) {
+        if !self.params.is_empty() {
+            let mut visitor = self;
+            visitor.visit_signature_mut(sig);
+        }
+    }
+
+    pub(crate) fn fill_type(&self, ty: &mut Type) {
+        if !self.params.is_empty() {
+            let mut visitor = self;
+            visitor.visit_type_mut(ty);
+        }
+    }
+
+    pub(crate) fn fill_bound(&self, bound: &mut TypeParamBound) {
+        if !self.params.is_empty() {
+            let mut visitor = self;
+            visitor.visit_type_param_bound_mut(bound);
+        }
+    }
+
+    fn param(&self, path: &syn::Path) -> Option<&GenericArgument> {
+        path.get_ident().and_then(|ident| self.params.get(ident))
+    }
+}
+
+impl VisitMut for &Instance {
+    fn visit_type_mut(&mut self, ty: &mut Type) {
+        if let Type::Path(path) = ty {
+            if let (None, Some(GenericArgument::Type(arg))) = (&path.qself, self.param(&path.path))
+            {
+                *ty = arg.clone();
+                return;
+            }
+        }
+        visit_mut::visit_type_mut(self, ty);
+    }
+
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        if let Expr::Path(path) = expr {
+            match (&path.qself, self.param(&path.path)) {
+                (None, Some(GenericArgument::Const(arg))) => {
+                    *expr = arg.clone();
+                    return;
+                }
+                // A const argument that is a plain name, like `N`, parses as a type
+                (None, Some(GenericArgument::Type(Type::Path(arg)))) => {
+                    path.path = arg.path.clone();
+                    return;
+                }
+                _ => {}
+            }
+        }
+        visit_mut::visit_expr_mut(self, expr);
+    }
+
+    fn visit_lifetime_mut(&mut self, lifetime: &mut Lifetime) {
+        if let Some(GenericArgument::Lifetime(arg)) = self.params.get(&lifetime.ident) {
+            *lifetime = arg.clone();
+        }
+    }
+}
+
+// The distinct named lifetimes of a target, leaving out `'static` and `'_`
+struct TargetLifetimes(Vec<Lifetime>);
+
+impl<'ast> Visit<'ast> for TargetLifetimes {
+    fn visit_lifetime(&mut self, lifetime: &'ast Lifetime) {
+        if lifetime.ident != "static" && lifetime.ident != "_" && !self.0.contains(lifetime) {
+            self.0.push(lifetime.clone());
+        }
+    }
+}
+
+// The generic arguments written on a wrap target, as in `Store<String, u64>` or `Pool::<TcpStream, 4>`
+fn generic_args(target: &Type) -> Vec<&GenericArgument> {
+    let Type::Path(path) = target else {
+        return Vec::new();
+    };
+    match path.path.segments.last().map(|segment| &segment.arguments) {
+        Some(PathArguments::AngleBracketed(args)) => args.args.iter().collect(),
+        _ => Vec::new(),
+    }
+}
+
+// The trait as implemented for `target`: a generic trait's parameters are filled in positionally from the target's
+// generic arguments, so `#[wrap(Store<String, u64>)]` on `AbxStore<K, V>` implements `AbxStore<String, u64>`.
+pub(crate) fn instance(item: &ItemTrait, target: &Type) -> syn::Result<Instance> {
+    let name = &item.ident;
+    let mut named = TargetLifetimes(Vec::new());
+    named.visit_type(target);
+    let impl_generics = match named.0.as_slice() {
+        [] => TokenStream::new(),
+        named => quote!(<#(#named),*>),
+    };
+    let mut params = HashMap::new();
+    if item.generics.params.is_empty() {
+        return Ok(Instance {
+            trait_path: quote!(#name),
+            impl_generics,
+            lifetimes: named.0,
+            params,
+        });
+    }
+
+    let args = generic_args(target);
+    let mut lifetimes = args
+        .iter()
+        .filter(|arg| matches!(arg, GenericArgument::Lifetime(_)));
+    let mut values = args
+        .iter()
+        .filter(|arg| matches!(arg, GenericArgument::Type(_) | GenericArgument::Const(_)));
+    let mut filled = Vec::new();
+    for param in &item.generics.params {
+        let (ident, arg) = match param {
+            GenericParam::Lifetime(l) => (&l.lifetime.ident, lifetimes.next()),
+            GenericParam::Type(t) => (&t.ident, values.next()),
+            GenericParam::Const(c) => (&c.ident, values.next()),
+        };
+        match arg {
+            Some(arg) => {
+                params.insert(ident.clone(), (*arg).clone());
+                filled.push(arg);
+            }
+            None => {
+                return Err(syn::Error::new(
+                    target.span(),
+                    format!(
+                        "`{name}` has more generic parameters than this target has generic arguments to fill them in"
+                    ),
+                ))
+            }
+        }
+    }
+    Ok(Instance {
+        trait_path: quote!(#name<#(#filled),*>),
+        impl_generics,
+        lifetimes: named.0,
+        params,
+    })
+}
@@ -0,0 +1,84 @@
+//! Time: what time it is, how long it's been, and waiting.
+
+use crate::wrap;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "test-doubles")]
+use std::sync::{Mutex, PoisonError};
+
+/// The real clock. `now` and `elapsed` come from std, and `sleep` from tokio, so it has to run on a tokio runtime
+/// with time enabled.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl SystemClock {
+    pub fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    pub fn elapsed(&self, since: Instant) -> Duration {
+        since.elapsed()
+    }
+
+    pub async fn sleep(&self, duration: Duration) {
+        tokio::time::sleep(duration).await;
+    }
+}
+
+/// A clock, implemented for [`SystemClock`] and, for tests, `TestClock`. Sleeping gives a `Send` future, so it can
+/// be awaited in spawned tasks.
+#[wrap(SystemClock)]
+pub trait AbxClock {
+    fn abx_now(&self) -> Instant;
+    fn abx_elapsed(&self, since: Instant) -> Duration;
+    #[abx(send)]
+    async fn abx_sleep(&self, duration: Duration);
+}
+
+/// A clock that only moves when told to. It starts at the moment it's created and stays there until
+/// [`advance`](TestClock::advance) is called. Sleeping doesn't wait: it moves the clock forward by the duration and
+/// returns straight away, so code that sleeps runs instantly but still sees the time pass.
+#[cfg(feature = "test-doubles")]
+#[derive(Debug)]
+pub struct TestClock {
+    start: Instant,
+    offset: Mutex<Duration>,
+}
+
+#[cfg(feature = "test-doubles")]
+impl TestClock {
+    /// A clock standing at the moment it's created.
+    pub fn new() -> Self {
+        TestClock {
+            start: Instant::now(),
+            offset: Mutex::new(Duration::ZERO),
+        }
+    }
+
+    /// Moves the clock forward by `duration`.
+    pub fn advance(&self, duration: Duration) {
+        *self.offset.lock().unwrap_or_else(PoisonError::into_inner) += duration;
+    }
+}
+
+#[cfg(feature = "test-doubles")]
+impl Default for TestClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "test-doubles")]
+impl AbxClock for TestClock {
+    fn abx_now(&self) -> Instant {
+        self.start + *self.offset.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn abx_elapsed(&self, since: Instant) -> Duration {
+        self.abx_now().saturating_duration_since(since)
+    }
+
+    async fn abx_sleep(&self, duration: Duration) {
+        self.advance(duration);
+    }
+}
@@ -0,0 +1,105 @@
+//! The terminal: reading lines typed in, and printing to stdout and stderr.
+
+use crate::wrap;
+use std::io::{self, BufRead, Write};
+
+#[cfg(feature = "test-doubles")]
+use std::{
+    collections::VecDeque,
+    sync::{Mutex, PoisonError},
+};
+
+/// The terminal, through stdin, stdout and stderr.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StdConsole;
+
+impl StdConsole {
+    pub fn read_line(&self) -> io::Result<Option<String>> {
+        let mut line = String::new();
+        match io::stdin().lock().read_line(&mut line)? {
+            0 => Ok(None),
+            _ => Ok(Some(line.trim_end_matches(['\r', '\n']).to_string())),
+        }
+    }
+
+    pub fn print(&self, text: &str) {
+        print!("{text}");
+        // Prompts don't end in a newline, so they'd otherwise sit in the buffer while waiting for input
+        let _ = io::stdout().flush();
+    }
+
+    pub fn eprint(&self, text: &str) {
+        eprint!("{text}");
+    }
+}
+
+/// A console, implemented for [`StdConsole`] and, for tests, `ScriptedConsole`. `abx_read_line` returns the line
+/// without its line ending, or `None` once input has run out.
+#[wrap(StdConsole)]
+pub trait AbxConsole {
+    fn abx_read_line(&self) -> io::Result<Option<String>>;
+    fn abx_print(&self, text: &str);
+    fn abx_eprint(&self, text: &str);
+}
+
+/// A console whose input is scripted up front and whose output is captured. `ScriptedConsole::new(["ada", "y"])`
+/// answers the first two `abx_read_line` calls with those lines and the rest with `None`.
+#[cfg(feature = "test-doubles")]
+#[derive(Debug, Default)]
+pub struct ScriptedConsole {
+    input: Mutex<VecDeque<String>>,
+    stdout: Mutex<String>,
+    stderr: Mutex<String>,
+}
+
+#[cfg(feature = "test-doubles")]
+impl ScriptedConsole {
+    /// A console answering reads with the lines of `input`, in order.
+    pub fn new<L: Into<String>>(input: impl IntoIterator<Item = L>) -> Self {
+        ScriptedConsole {
+            input: Mutex::new(input.into_iter().map(Into::into).collect()),
+            ..Self::default()
+        }
+    }
+
+    /// Everything printed to stdout so far.
+    pub fn stdout(&self) -> String {
+        self.stdout
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Everything printed to stderr so far.
+    pub fn stderr(&self) -> String {
+        self.stderr
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+#[cfg(feature = "test-doubles")]
+impl AbxConsole for ScriptedConsole {
+    fn abx_read_line(&self) -> io::Result<Option<String>> {
+        Ok(self
+            .input
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .pop_front())
+    }
+
+    fn abx_print(&self, text: &str) {
+        self.stdout
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push_str(text);
+    }
+
+    fn abx_eprint(&self, text: &str) {
+        self.stderr
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push_str(text);
+    }
+}
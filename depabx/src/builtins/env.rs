@@ -0,0 +1,85 @@
+//! Environment variables: reading, setting and listing them.
+
+use crate::wrap;
+
+#[cfg(feature = "test-doubles")]
+use std::{
+    collections::BTreeMap,
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+/// The process environment, through `std::env`. Setting a variable changes it for the whole process.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ProcessEnv;
+
+impl ProcessEnv {
+    pub fn get(&self, key: &str) -> Option<String> {
+        std::env::var(key).ok()
+    }
+
+    pub fn set(&self, key: &str, value: &str) {
+        std::env::set_var(key, value);
+    }
+
+    pub fn vars(&self) -> Vec<(String, String)> {
+        std::env::vars().collect()
+    }
+}
+
+/// An environment, implemented for [`ProcessEnv`] and, for tests, `MapEnv`.
+#[wrap(ProcessEnv)]
+pub trait AbxEnv {
+    fn abx_get(&self, key: &str) -> Option<String>;
+    fn abx_set(&self, key: &str, value: &str);
+    fn abx_vars(&self) -> Vec<(String, String)>;
+}
+
+/// An environment of its own, held in a map. Each test gets separate variables, so tests can run in parallel without
+/// racing on the process environment. `vars` lists them sorted by name.
+#[cfg(feature = "test-doubles")]
+#[derive(Debug, Default)]
+pub struct MapEnv {
+    vars: Mutex<BTreeMap<String, String>>,
+}
+
+#[cfg(feature = "test-doubles")]
+impl MapEnv {
+    /// An environment with no variables.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// An environment holding `vars`, as names and their values.
+    pub fn with_vars<K: Into<String>, V: Into<String>>(
+        vars: impl IntoIterator<Item = (K, V)>,
+    ) -> Self {
+        let vars = vars
+            .into_iter()
+            .map(|(key, value)| (key.into(), value.into()));
+        MapEnv {
+            vars: Mutex::new(vars.collect()),
+        }
+    }
+
+    fn vars(&self) -> MutexGuard<'_, BTreeMap<String, String>> {
+        self.vars.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(feature = "test-doubles")]
+impl AbxEnv for MapEnv {
+    fn abx_get(&self, key: &str) -> Option<String> {
+        self.vars().get(key).cloned()
+    }
+
+    fn abx_set(&self, key: &str, value: &str) {
+        self.vars().insert(key.to_string(), value.to_string());
+    }
+
+    fn abx_vars(&self) -> Vec<(String, String)> {
+        self.vars()
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    }
+}
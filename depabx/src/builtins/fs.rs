@@ -0,0 +1,122 @@
+//! Files: reading, writing, inspecting and removing them.
+
+use crate::wrap;
+use std::io;
+use std::path::Path;
+
+#[cfg(feature = "test-doubles")]
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+/// What `metadata` reports about a file. It's a plain struct rather than `std::fs::Metadata`, which only the OS can
+/// create.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileMetadata {
+    pub len: u64,
+}
+
+/// The real filesystem, through `std::fs`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OsFs;
+
+impl OsFs {
+    pub fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        std::fs::read(path)
+    }
+
+    pub fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        std::fs::write(path, contents)
+    }
+
+    pub fn metadata(&self, path: &Path) -> io::Result<FileMetadata> {
+        std::fs::metadata(path).map(|metadata| FileMetadata {
+            len: metadata.len(),
+        })
+    }
+
+    pub fn remove(&self, path: &Path) -> io::Result<()> {
+        std::fs::remove_file(path)
+    }
+}
+
+/// A filesystem, implemented for [`OsFs`] and, for tests, `MemoryFs`.
+#[wrap(OsFs)]
+pub trait AbxFs {
+    fn abx_read(&self, path: &Path) -> io::Result<Vec<u8>>;
+    fn abx_write(&self, path: &Path, contents: &[u8]) -> io::Result<()>;
+    fn abx_metadata(&self, path: &Path) -> io::Result<FileMetadata>;
+    fn abx_remove(&self, path: &Path) -> io::Result<()>;
+}
+
+/// A filesystem that lives in a map from paths to file contents, so tests need no temporary directories. There are
+/// no directories: any path can be written to, and paths that were never written (or were removed) fail with
+/// `io::ErrorKind::NotFound`, like they would on disk.
+#[cfg(feature = "test-doubles")]
+#[derive(Debug, Default)]
+pub struct MemoryFs {
+    files: Mutex<HashMap<PathBuf, Vec<u8>>>,
+}
+
+#[cfg(feature = "test-doubles")]
+impl MemoryFs {
+    /// A filesystem with no files.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A filesystem holding `files`, as paths and their contents.
+    pub fn with_files<P: Into<PathBuf>, C: Into<Vec<u8>>>(
+        files: impl IntoIterator<Item = (P, C)>,
+    ) -> Self {
+        let files = files
+            .into_iter()
+            .map(|(path, contents)| (path.into(), contents.into()));
+        MemoryFs {
+            files: Mutex::new(files.collect()),
+        }
+    }
+
+    fn files(&self) -> MutexGuard<'_, HashMap<PathBuf, Vec<u8>>> {
+        self.files.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn not_found(path: &Path) -> io::Error {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("{} not found", path.display()),
+        )
+    }
+}
+
+#[cfg(feature = "test-doubles")]
+impl AbxFs for MemoryFs {
+    fn abx_read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        self.files()
+            .get(path)
+            .cloned()
+            .ok_or_else(|| Self::not_found(path))
+    }
+
+    fn abx_write(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        self.files().insert(path.to_path_buf(), contents.to_vec());
+        Ok(())
+    }
+
+    fn abx_metadata(&self, path: &Path) -> io::Result<FileMetadata> {
+        let files = self.files();
+        let contents = files.get(path).ok_or_else(|| Self::not_found(path))?;
+        Ok(FileMetadata {
+            len: contents.len() as u64,
+        })
+    }
+
+    fn abx_remove(&self, path: &Path) -> io::Result<()> {
+        self.files()
+            .remove(path)
+            .map(|_| ())
+            .ok_or_else(|| Self::not_found(path))
+    }
+}
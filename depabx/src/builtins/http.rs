@@ -0,0 +1,178 @@
+//! HTTP: sending a request and getting its response.
+
+use crate::wrap;
+use std::fmt;
+
+#[cfg(feature = "test-doubles")]
+use std::{
+    collections::HashMap,
+    sync::{Mutex, PoisonError},
+};
+
+/// The method of a [`Request`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Method {
+    Get,
+    Post,
+    Put,
+    Delete,
+}
+
+/// A request, built with [`Request::get`] or [`Request::new`], plus [`header`](Request::header) for each header to
+/// send.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Request {
+    pub method: Method,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    /// A request to `url` sending `body`, without headers.
+    pub fn new(method: Method, url: &str, body: Vec<u8>) -> Self {
+        Request {
+            method,
+            url: url.to_string(),
+            headers: Vec::new(),
+            body,
+        }
+    }
+
+    /// A `GET` request to `url`.
+    pub fn get(url: &str) -> Self {
+        Self::new(Method::Get, url, Vec::new())
+    }
+
+    /// Adds a header to send.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+}
+
+/// Any response the server sent, whatever its status. Only a request that got no response at all is an
+/// [`HttpError`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    /// A response with `status` and a text body.
+    pub fn new(status: u16, body: &str) -> Self {
+        Response {
+            status,
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    /// The body as text, with any invalid UTF-8 replaced.
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+}
+
+/// A request that got no response, with why.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HttpError(pub String);
+
+impl fmt::Display for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "HTTP request failed: {}", self.0)
+    }
+}
+
+impl std::error::Error for HttpError {}
+
+/// The real client, on `reqwest`. It's behind the `reqwest` feature so that crates only using the trait and its fake
+/// don't pull in an HTTP stack; `reqwest` also needs to run on a tokio runtime.
+#[cfg(feature = "reqwest")]
+#[derive(Clone, Debug, Default)]
+pub struct ReqwestHttp {
+    client: reqwest::Client,
+}
+
+#[cfg(feature = "reqwest")]
+impl ReqwestHttp {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn send(&self, request: Request) -> Result<Response, HttpError> {
+        let method = match request.method {
+            Method::Get => reqwest::Method::GET,
+            Method::Post => reqwest::Method::POST,
+            Method::Put => reqwest::Method::PUT,
+            Method::Delete => reqwest::Method::DELETE,
+        };
+        let mut builder = self.client.request(method, &request.url).body(request.body);
+        for (name, value) in &request.headers {
+            builder = builder.header(name, value);
+        }
+        let failed = |err: reqwest::Error| HttpError(err.to_string());
+        let response = builder.send().await.map_err(failed)?;
+        let status = response.status().as_u16();
+        let body = response.bytes().await.map_err(failed)?;
+        Ok(Response {
+            status,
+            body: body.to_vec(),
+        })
+    }
+}
+
+/// An HTTP client, implemented for `ReqwestHttp` under the `reqwest` feature and, for tests, `CannedHttp`.
+/// Sending gives a `Send` future, so it can be awaited in spawned tasks.
+#[wrap(ReqwestHttp, feature = "reqwest")]
+pub trait AbxHttp {
+    #[abx(send)]
+    async fn abx_send(&self, request: Request) -> Result<Response, HttpError>;
+}
+
+/// A client that serves canned responses. `on(Method::Get, url, response)` answers every request with that method
+/// and URL with a copy of `response`; a request with no canned response fails with an [`HttpError`] naming it, as if
+/// the host were unreachable.
+#[cfg(feature = "test-doubles")]
+#[derive(Debug, Default)]
+pub struct CannedHttp {
+    responses: HashMap<(Method, String), Response>,
+    requests: Mutex<Vec<Request>>,
+}
+
+#[cfg(feature = "test-doubles")]
+impl CannedHttp {
+    /// A client with no canned responses.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answers requests with `method` to `url` with `response`.
+    pub fn on(mut self, method: Method, url: &str, response: Response) -> Self {
+        self.responses.insert((method, url.to_string()), response);
+        self
+    }
+
+    /// Everything that was sent, in order, headers included.
+    pub fn requests(&self) -> Vec<Request> {
+        self.requests
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+#[cfg(feature = "test-doubles")]
+impl AbxHttp for CannedHttp {
+    async fn abx_send(&self, request: Request) -> Result<Response, HttpError> {
+        let key = (request.method, request.url.clone());
+        self.requests
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(request);
+        self.responses.get(&key).cloned().ok_or_else(|| {
+            let (method, url) = key;
+            HttpError(format!("no canned response for {method:?} {url}"))
+        })
+    }
+}
@@ -0,0 +1,24 @@
+//! Ready-made abstractions of the dependencies almost every program has, so they don't need wrapping again in every
+//! project. Each is behind the cargo feature of the same name, and comes with a real implementation and a test
+//! double, which is behind the `test-doubles` feature so that the two can be enabled separately for tests.
+
+#[cfg(feature = "clock")]
+pub mod clock;
+#[cfg(feature = "console")]
+pub mod console;
+#[cfg(feature = "env")]
+pub mod env;
+#[cfg(feature = "fs")]
+pub mod fs;
+#[cfg(feature = "http")]
+pub mod http;
+#[cfg(feature = "process")]
+pub mod process;
+#[cfg(feature = "rng")]
+pub mod rng;
+#[cfg(feature = "signals")]
+pub mod signals;
+#[cfg(feature = "tcp")]
+pub mod tcp;
+#[cfg(feature = "timer")]
+pub mod timer;
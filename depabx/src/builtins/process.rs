@@ -0,0 +1,141 @@
+//! Processes: running commands, and spawning them in the background.
+
+use crate::wrap;
+use std::io;
+use std::process::Command;
+
+#[cfg(feature = "test-doubles")]
+use std::{
+    collections::HashMap,
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+/// What a finished command produced. `code` is `None` if it was killed by a signal. It's a plain struct rather than
+/// `std::process::Output`, whose exit status only the OS can create.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommandOutput {
+    pub code: Option<i32>,
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+}
+
+/// Commands run through `std::process::Command`. A spawned command runs in the background, and is waited for on a
+/// thread of its own so it doesn't linger once it exits.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OsProcess;
+
+impl OsProcess {
+    pub fn spawn(&self, program: &str, args: &[&str]) -> io::Result<u32> {
+        let mut child = Command::new(program).args(args).spawn()?;
+        let id = child.id();
+        std::thread::spawn(move || child.wait());
+        Ok(id)
+    }
+
+    pub fn output(&self, program: &str, args: &[&str]) -> io::Result<CommandOutput> {
+        let output = Command::new(program).args(args).output()?;
+        Ok(CommandOutput {
+            code: output.status.code(),
+            stdout: output.stdout,
+            stderr: output.stderr,
+        })
+    }
+
+    pub fn status(&self, program: &str, args: &[&str]) -> io::Result<Option<i32>> {
+        Command::new(program)
+            .args(args)
+            .status()
+            .map(|status| status.code())
+    }
+}
+
+/// Running commands, implemented for [`OsProcess`] and, for tests, `ScriptedProcess`.
+#[wrap(OsProcess)]
+pub trait AbxProcess {
+    fn abx_spawn(&self, program: &str, args: &[&str]) -> io::Result<u32>;
+    fn abx_output(&self, program: &str, args: &[&str]) -> io::Result<CommandOutput>;
+    fn abx_status(&self, program: &str, args: &[&str]) -> io::Result<Option<i32>>;
+}
+
+#[cfg(feature = "test-doubles")]
+impl CommandOutput {
+    /// The output of a command that exited with 0 after printing `stdout`.
+    pub fn success(stdout: &str) -> Self {
+        CommandOutput {
+            code: Some(0),
+            stdout: stdout.as_bytes().to_vec(),
+            stderr: Vec::new(),
+        }
+    }
+
+    /// The output of a command that exited with `code` after printing `stderr`.
+    pub fn failure(code: i32, stderr: &str) -> Self {
+        CommandOutput {
+            code: Some(code),
+            stdout: Vec::new(),
+            stderr: stderr.as_bytes().to_vec(),
+        }
+    }
+}
+
+/// Commands answered from a script instead of being run. `on("git status", output)` scripts a command line, written
+/// as the program and its arguments separated by spaces; every run of it gets a copy of `output`, and spawning it
+/// returns a made-up process ID. Anything unscripted fails with `io::ErrorKind::NotFound`, as a missing program
+/// would. [`commands`](ScriptedProcess::commands) lists every command line that was run or spawned, in order.
+#[cfg(feature = "test-doubles")]
+#[derive(Debug, Default)]
+pub struct ScriptedProcess {
+    script: HashMap<String, CommandOutput>,
+    commands: Mutex<Vec<String>>,
+}
+
+#[cfg(feature = "test-doubles")]
+impl ScriptedProcess {
+    /// A script with no commands in it.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Scripts `command_line` to produce `output`.
+    pub fn on(mut self, command_line: &str, output: CommandOutput) -> Self {
+        self.script.insert(command_line.to_string(), output);
+        self
+    }
+
+    /// The command lines that were run or spawned, in order.
+    pub fn commands(&self) -> Vec<String> {
+        self.log().clone()
+    }
+
+    fn log(&self) -> MutexGuard<'_, Vec<String>> {
+        self.commands.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn record(&self, program: &str, args: &[&str]) -> io::Result<&CommandOutput> {
+        let command_line = [&[program], args].concat().join(" ");
+        self.log().push(command_line.clone());
+        let not_found = || {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("unscripted command: {command_line}"),
+            )
+        };
+        self.script.get(&command_line).ok_or_else(not_found)
+    }
+}
+
+#[cfg(feature = "test-doubles")]
+impl AbxProcess for ScriptedProcess {
+    fn abx_spawn(&self, program: &str, args: &[&str]) -> io::Result<u32> {
+        self.record(program, args)?;
+        Ok(1000 + self.log().len() as u32)
+    }
+
+    fn abx_output(&self, program: &str, args: &[&str]) -> io::Result<CommandOutput> {
+        self.record(program, args).cloned()
+    }
+
+    fn abx_status(&self, program: &str, args: &[&str]) -> io::Result<Option<i32>> {
+        self.record(program, args).map(|output| output.code)
+    }
+}
@@ -0,0 +1,106 @@
+//! Randomness: numbers in a range, and shuffles.
+
+use crate::wrap;
+use rand::{seq::SliceRandom, Rng};
+
+#[cfg(feature = "test-doubles")]
+use rand::{rngs::StdRng, SeedableRng};
+#[cfg(feature = "test-doubles")]
+use std::{
+    collections::VecDeque,
+    sync::{Mutex, PoisonError},
+};
+
+/// Randomness from `rand`'s thread-local generator, seeded by the OS.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemRng;
+
+impl SystemRng {
+    pub fn range(&self, low: u64, high: u64) -> u64 {
+        rand::thread_rng().gen_range(low..high)
+    }
+
+    pub fn shuffle<T>(&self, items: &mut [T]) {
+        items.shuffle(&mut rand::thread_rng());
+    }
+}
+
+/// Randomness, implemented for [`SystemRng`] and, for tests, `SeededRng` and `ScriptedRng`. `abx_range` returns
+/// a number in `low..high`, so `high` has to be greater than `low`.
+#[wrap(SystemRng)]
+pub trait AbxRng {
+    fn abx_range(&self, low: u64, high: u64) -> u64;
+    fn abx_shuffle<T>(&self, items: &mut [T]);
+}
+
+/// Randomness that's the same on every run for the same seed, so tests get reproducible shuffles and tokens. It's
+/// built on `StdRng`, whose sequence may change between `rand` versions: compare results against each other, not
+/// against values written into the test, or use [`ScriptedRng`] for those.
+#[cfg(feature = "test-doubles")]
+#[derive(Debug)]
+pub struct SeededRng {
+    rng: Mutex<StdRng>,
+}
+
+#[cfg(feature = "test-doubles")]
+impl SeededRng {
+    /// A generator whose sequence is decided by `seed`.
+    pub fn new(seed: u64) -> Self {
+        SeededRng {
+            rng: Mutex::new(StdRng::seed_from_u64(seed)),
+        }
+    }
+}
+
+#[cfg(feature = "test-doubles")]
+impl AbxRng for SeededRng {
+    fn abx_range(&self, low: u64, high: u64) -> u64 {
+        let mut rng = self.rng.lock().unwrap_or_else(PoisonError::into_inner);
+        rng.gen_range(low..high)
+    }
+
+    fn abx_shuffle<T>(&self, items: &mut [T]) {
+        items.shuffle(&mut *self.rng.lock().unwrap_or_else(PoisonError::into_inner));
+    }
+}
+
+/// Randomness written by the test: each `abx_range` returns the next of the numbers it was given, and shuffles
+/// leave the items in the order they were in, so a test can say exactly what it expects. A call to `abx_range` panics
+/// when the numbers have run out, or when the next one isn't in the range asked for.
+#[cfg(feature = "test-doubles")]
+#[derive(Debug)]
+pub struct ScriptedRng {
+    numbers: Mutex<VecDeque<u64>>,
+}
+
+#[cfg(feature = "test-doubles")]
+impl ScriptedRng {
+    /// A generator returning `numbers` from `abx_range`, in order.
+    pub fn new(numbers: impl IntoIterator<Item = u64>) -> Self {
+        ScriptedRng {
+            numbers: Mutex::new(numbers.into_iter().collect()),
+        }
+    }
+}
+
+#[cfg(feature = "test-doubles")]
+impl AbxRng for ScriptedRng {
+    fn abx_range(&self, low: u64, high: u64) -> u64 {
+        let next = self
+            .numbers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .pop_front();
+        match next {
+            Some(number) if (low..high).contains(&number) => number,
+            Some(number) => {
+                panic!("`ScriptedRng`'s next number is {number}, which isn't in {low}..{high}")
+            }
+            None => panic!(
+                "`ScriptedRng` has run out of numbers to return from `abx_range({low}, {high})`"
+            ),
+        }
+    }
+
+    fn abx_shuffle<T>(&self, _items: &mut [T]) {}
+}
@@ -0,0 +1,86 @@
+//! Signals: finding out that the process has been asked to stop.
+
+use crate::wrap;
+use std::io;
+
+#[cfg(feature = "test-doubles")]
+use tokio::sync::watch;
+
+/// How the process was asked to stop.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Signal {
+    /// Ctrl-C, or SIGINT.
+    Interrupt,
+    /// SIGTERM, as sent by service managers and container runtimes. Only received on Unix.
+    Terminate,
+}
+
+/// The signals the OS sends the process, through `tokio::signal`. It needs a tokio runtime with the IO driver
+/// enabled.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OsSignals;
+
+impl OsSignals {
+    #[cfg(unix)]
+    pub async fn wait(&self) -> io::Result<Signal> {
+        use tokio::signal::unix::{signal, SignalKind};
+        let mut terminate = signal(SignalKind::terminate())?;
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => result.map(|()| Signal::Interrupt),
+            _ = terminate.recv() => Ok(Signal::Terminate),
+        }
+    }
+
+    #[cfg(not(unix))]
+    pub async fn wait(&self) -> io::Result<Signal> {
+        tokio::signal::ctrl_c().await.map(|()| Signal::Interrupt)
+    }
+}
+
+/// Waiting for the process to be asked to stop, implemented for [`OsSignals`] and, for tests, `TestSignals`.
+/// `abx_wait` returns once it has been, saying how, and gives a `Send` future so it can be awaited in spawned tasks.
+#[wrap(OsSignals)]
+pub trait AbxSignals {
+    #[abx(send)]
+    async fn abx_wait(&self) -> io::Result<Signal>;
+}
+
+/// Signals sent by the test itself. [`trigger`](TestSignals::trigger) wakes everything waiting in `abx_wait`, and
+/// later waits return straight away, just like a process that's been asked to stop stays that way.
+#[cfg(feature = "test-doubles")]
+#[derive(Debug)]
+pub struct TestSignals {
+    sender: watch::Sender<Option<Signal>>,
+}
+
+#[cfg(feature = "test-doubles")]
+impl TestSignals {
+    /// Signals that haven't been sent yet.
+    pub fn new() -> Self {
+        TestSignals {
+            sender: watch::Sender::new(None),
+        }
+    }
+
+    /// Sends `signal`, replacing any sent before it.
+    pub fn trigger(&self, signal: Signal) {
+        self.sender.send_replace(Some(signal));
+    }
+}
+
+#[cfg(feature = "test-doubles")]
+impl Default for TestSignals {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "test-doubles")]
+impl AbxSignals for TestSignals {
+    async fn abx_wait(&self) -> io::Result<Signal> {
+        let mut receiver = self.sender.subscribe();
+        // The sender lives as long as `self`, so the wait can't fail for lack of one
+        let signal = receiver.wait_for(Option::is_some).await.unwrap();
+        Ok(signal.unwrap())
+    }
+}
@@ -0,0 +1,147 @@
+//! Network sockets: connecting over TCP, and reading and writing the connection.
+
+use crate::wrap;
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+
+#[cfg(feature = "test-doubles")]
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+/// Connections over real TCP.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OsTcp;
+
+impl OsTcp {
+    pub fn connect(&self, addr: &str) -> io::Result<TcpStream> {
+        TcpStream::connect(addr)
+    }
+}
+
+/// A single connection, implemented for `TcpStream` and, for tests, `PipeEnd`.
+// `TcpStream` reads and writes through `Read` and `Write`, which are imported above so the generated impl finds them
+#[wrap(TcpStream)]
+pub trait AbxConnection {
+    fn abx_read(&mut self, buf: &mut [u8]) -> io::Result<usize>;
+    fn abx_write(&mut self, buf: &[u8]) -> io::Result<usize>;
+}
+
+/// Opening connections, implemented for [`OsTcp`] and, for tests, `MemoryTcp`.
+#[wrap(OsTcp)]
+#[abx(type Connection = TcpStream)]
+pub trait AbxTcp {
+    type Connection: AbxConnection;
+    fn abx_connect(&self, addr: &str) -> io::Result<Self::Connection>;
+}
+
+// One direction of a pipe: the bytes written but not yet read, and whether the writing end is gone
+#[cfg(feature = "test-doubles")]
+#[derive(Debug, Default)]
+struct Pipe {
+    bytes: VecDeque<u8>,
+    closed: bool,
+}
+
+#[cfg(feature = "test-doubles")]
+fn pipe(pipe: &Mutex<Pipe>) -> MutexGuard<'_, Pipe> {
+    pipe.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// One end of an in-memory connection: it reads what the other end writes, and the other way around. Reading when
+/// nothing is buffered fails with `io::ErrorKind::WouldBlock` while the other end is still around, and returns 0 (the
+/// end of the stream) once it's been dropped. Writing then fails with `io::ErrorKind::BrokenPipe`.
+#[cfg(feature = "test-doubles")]
+#[derive(Debug)]
+pub struct PipeEnd {
+    incoming: Arc<Mutex<Pipe>>,
+    outgoing: Arc<Mutex<Pipe>>,
+}
+
+/// The two ends of a new in-memory connection.
+#[cfg(feature = "test-doubles")]
+pub fn duplex() -> (PipeEnd, PipeEnd) {
+    let (there, back) = (Arc::default(), Arc::default());
+    let near = PipeEnd {
+        incoming: Arc::clone(&back),
+        outgoing: Arc::clone(&there),
+    };
+    let far = PipeEnd {
+        incoming: there,
+        outgoing: back,
+    };
+    (near, far)
+}
+
+#[cfg(feature = "test-doubles")]
+impl AbxConnection for PipeEnd {
+    fn abx_read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut pipe = pipe(&self.incoming);
+        if pipe.bytes.is_empty() && !pipe.closed {
+            return Err(io::ErrorKind::WouldBlock.into());
+        }
+        let read = buf.len().min(pipe.bytes.len());
+        for (slot, byte) in buf.iter_mut().zip(pipe.bytes.drain(..read)) {
+            *slot = byte;
+        }
+        Ok(read)
+    }
+
+    fn abx_write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // The other end closed what it writes when it was dropped, so nothing would read these
+        if pipe(&self.incoming).closed {
+            return Err(io::ErrorKind::BrokenPipe.into());
+        }
+        pipe(&self.outgoing).bytes.extend(buf);
+        Ok(buf.len())
+    }
+}
+
+#[cfg(feature = "test-doubles")]
+impl Drop for PipeEnd {
+    fn drop(&mut self) {
+        pipe(&self.outgoing).closed = true;
+    }
+}
+
+/// A network without ports. [`listen`](MemoryTcp::listen) returns the server's end of a pipe whose other end goes to
+/// the next `abx_connect` to that address, and the test then plays the server through it. Connecting to an address
+/// nobody listens on fails with `io::ErrorKind::ConnectionRefused`.
+#[cfg(feature = "test-doubles")]
+#[derive(Debug, Default)]
+pub struct MemoryTcp {
+    pending: Mutex<HashMap<String, VecDeque<PipeEnd>>>,
+}
+
+#[cfg(feature = "test-doubles")]
+impl MemoryTcp {
+    /// A network nobody listens on.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The server's end of the next connection made to `addr`.
+    pub fn listen(&self, addr: &str) -> PipeEnd {
+        let (client, server) = duplex();
+        self.pending()
+            .entry(addr.to_string())
+            .or_default()
+            .push_back(client);
+        server
+    }
+
+    fn pending(&self) -> MutexGuard<'_, HashMap<String, VecDeque<PipeEnd>>> {
+        self.pending.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(feature = "test-doubles")]
+impl AbxTcp for MemoryTcp {
+    type Connection = PipeEnd;
+
+    fn abx_connect(&self, addr: &str) -> io::Result<PipeEnd> {
+        let client = self.pending().get_mut(addr).and_then(VecDeque::pop_front);
+        client.ok_or_else(|| io::ErrorKind::ConnectionRefused.into())
+    }
+}
@@ -0,0 +1,72 @@
+//! Timers on tokio's clock, for code whose waits should be skipped by paused time in tests.
+
+use crate::wrap;
+use std::time::Duration;
+use tokio::time::Instant;
+
+#[cfg(feature = "test-doubles")]
+use std::sync::{Mutex, PoisonError};
+
+/// Timers from `tokio::time`, so they need a tokio runtime with time enabled.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TokioTimer;
+
+impl TokioTimer {
+    pub fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    pub async fn sleep(&self, duration: Duration) {
+        tokio::time::sleep(duration).await;
+    }
+}
+
+/// A timer, implemented for [`TokioTimer`] and, for tests, `TestTimer`. Sleeping gives a `Send` future, so it can
+/// be awaited in spawned tasks.
+#[wrap(TokioTimer)]
+pub trait AbxTimer {
+    fn abx_now(&self) -> Instant;
+    #[abx(send)]
+    async fn abx_sleep(&self, duration: Duration);
+}
+
+/// A timer for tests that records how long each sleep asked for. It still sleeps on tokio's clock, so in a test with
+/// paused time (`#[tokio::test(start_paused = true)]`) a sleep ends as soon as the runtime has nothing else to do,
+/// and `tokio::time::advance` moves it along like any other timer. Unlike the
+/// clock's `TestClock`, it can't be used without tokio.
+#[cfg(feature = "test-doubles")]
+#[derive(Debug, Default)]
+pub struct TestTimer {
+    sleeps: Mutex<Vec<Duration>>,
+}
+
+#[cfg(feature = "test-doubles")]
+impl TestTimer {
+    /// A timer that hasn't slept yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// How long each sleep asked for, in order.
+    pub fn sleeps(&self) -> Vec<Duration> {
+        self.sleeps
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+#[cfg(feature = "test-doubles")]
+impl AbxTimer for TestTimer {
+    fn abx_now(&self) -> Instant {
+        Instant::now()
+    }
+
+    async fn abx_sleep(&self, duration: Duration) {
+        self.sleeps
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(duration);
+        tokio::time::sleep(duration).await;
+    }
+}
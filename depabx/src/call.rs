@@ -0,0 +1,138 @@
+use crate::matchers::Matcher;
+use std::fmt;
+use std::ops::Index;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+// Every call to every generated double is numbered from this, so calls on different doubles can be ordered
+static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+/// One call recorded by a generated fake: the trait method that was called, and each argument formatted with `Debug`.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Call {
+    pub method: &'static str,
+    pub args: Vec<String>,
+    // Which of the arguments were of a generic `Display` type, and so formatted with `to_string()` instead
+    pub(crate) displayed: Vec<bool>,
+    pub(crate) seq: u64,
+}
+
+impl Call {
+    #[doc(hidden)]
+    pub fn new(method: &'static str, args: Vec<String>, displayed: Vec<bool>) -> Self {
+        Call {
+            method,
+            args,
+            displayed,
+            seq: SEQUENCE.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+}
+
+// Rendered the way the call was written, as in `abx_log_error("disk full", 500)`
+impl fmt::Display for Call {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}({})", self.method, self.args.join(", "))
+    }
+}
+
+impl fmt::Debug for Call {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// The calls a generated fake recorded for one of its methods, in order, with assertions on how many there were.
+///
+/// Got from a typed accessor like `page_view_calls()`, they also hold each call's arguments as the fake captured them,
+/// a `C`, so they can be narrowed down with [`matchers`](crate::matchers).
+#[derive(Clone, Debug)]
+pub struct Calls<C = ()> {
+    method: &'static str,
+    calls: Vec<(Call, C)>,
+}
+
+impl Calls {
+    #[doc(hidden)]
+    pub fn new(method: &'static str, calls: Vec<Call>) -> Self {
+        let calls = calls.into_iter().map(|call| (call, ())).collect();
+        Calls { method, calls }
+    }
+}
+
+impl<C> Calls<C> {
+    #[doc(hidden)]
+    pub fn captured(method: &'static str, calls: Vec<(Call, C)>) -> Self {
+        Calls { method, calls }
+    }
+
+    /// The calls whose arguments `matcher` matches.
+    pub fn matching(self, matcher: impl Matcher<C>) -> Self {
+        let calls = self
+            .calls
+            .into_iter()
+            .filter(|(_, captured)| matcher.matches(captured))
+            .collect();
+        Calls {
+            method: self.method,
+            calls,
+        }
+    }
+
+    /// How many calls there are.
+    pub fn len(&self) -> usize {
+        self.calls.len()
+    }
+
+    /// Whether there are no calls.
+    pub fn is_empty(&self) -> bool {
+        self.calls.is_empty()
+    }
+
+    /// The calls, in the order they were made.
+    pub fn iter(&self) -> impl Iterator<Item = &Call> {
+        self.calls.iter().map(|(call, _)| call)
+    }
+
+    /// Panics unless the method was called exactly once.
+    #[track_caller]
+    pub fn assert_called_once(&self) {
+        self.assert_called_times(1);
+    }
+
+    /// Panics unless the method was called exactly `times` times.
+    #[track_caller]
+    pub fn assert_called_times(&self, times: usize) {
+        if self.calls.len() != times {
+            let calls: Vec<_> = self.calls.iter().map(|(call, _)| call).collect();
+            panic!(
+                "expected `{}` to be called {}, but it was called {}: {calls:?}",
+                self.method,
+                count(times),
+                count(self.calls.len()),
+            );
+        }
+    }
+
+    /// Panics if the method was called at all.
+    #[track_caller]
+    pub fn assert_not_called(&self) {
+        self.assert_called_times(0);
+    }
+}
+
+impl<C> Index<usize> for Calls<C> {
+    type Output = Call;
+
+    fn index(&self, index: usize) -> &Call {
+        &self.calls[index].0
+    }
+}
+
+// `once`, `twice` or `3 times`, for the assertion messages
+pub(crate) fn count(times: usize) -> String {
+    match times {
+        1 => "once".to_string(),
+        2 => "twice".to_string(),
+        _ => format!("{times} times"),
+    }
+}
@@ -0,0 +1,94 @@
+use crate::call::count;
+use crate::matchers::Matcher;
+use crate::Call;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+/// How a method of a generated fake is expected to be called, got from its `expect_` method. It's checked by the
+/// fake's `checkpoint()`, or when the fake is dropped.
+///
+/// Only calls made after the expectation, with arguments its matcher matches, count towards it. Without `times` the
+/// method is expected to be called at least once.
+pub struct Expectation<C> {
+    shared: Arc<Mutex<Expected<C>>>,
+}
+
+#[doc(hidden)]
+pub struct Expected<C> {
+    method: &'static str,
+    matcher: Option<Box<dyn Matcher<C> + Send>>,
+    times: Option<usize>,
+    calls: Vec<Call>,
+}
+
+impl<C> Expectation<C> {
+    #[doc(hidden)]
+    pub fn new(method: &'static str) -> (Self, Arc<Mutex<Expected<C>>>) {
+        let shared = Arc::new(Mutex::new(Expected {
+            method,
+            matcher: None,
+            times: None,
+            calls: Vec::new(),
+        }));
+        let expectation = Expectation {
+            shared: shared.clone(),
+        };
+        (expectation, shared)
+    }
+
+    /// Only counts the calls whose arguments `matcher` matches.
+    pub fn with(self, matcher: impl Matcher<C> + Send + 'static) -> Self {
+        lock(&self.shared).matcher = Some(Box::new(matcher));
+        self
+    }
+
+    /// Expects exactly `times` calls.
+    pub fn times(self, times: usize) -> Self {
+        lock(&self.shared).times = Some(times);
+        self
+    }
+}
+
+impl<C> Expected<C> {
+    // Counts the call if it's one the expectation is about
+    pub(crate) fn record(&mut self, call: &Call, captured: &C) {
+        if self
+            .matcher
+            .as_ref()
+            .is_none_or(|matcher| matcher.matches(captured))
+        {
+            self.calls.push(call.clone());
+        }
+    }
+
+    // What's wrong, if the expectation wasn't met
+    pub(crate) fn unmet(&self) -> Option<String> {
+        let expected = match self.times {
+            Some(times) if times != self.calls.len() => count(times),
+            None if self.calls.is_empty() => "at least once".to_string(),
+            _ => return None,
+        };
+        let arguments = match self.matcher {
+            Some(_) => " with matching arguments",
+            None => "",
+        };
+        Some(format!(
+            "expected `{}` to be called {expected}{arguments}, but it was called {}: {:?}",
+            self.method,
+            count(self.calls.len()),
+            self.calls
+        ))
+    }
+}
+
+pub(crate) fn lock<C>(expected: &Mutex<Expected<C>>) -> MutexGuard<'_, Expected<C>> {
+    expected.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+// Panics with every expectation of a fake that wasn't met, unless it's already panicking
+#[track_caller]
+pub fn verify(unmet: impl IntoIterator<Item = Vec<String>>) {
+    let unmet: Vec<String> = unmet.into_iter().flatten().collect();
+    if !unmet.is_empty() && !std::thread::panicking() {
+        panic!("{}", unmet.join("\n"));
+    }
+}
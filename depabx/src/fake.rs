@@ -0,0 +1,362 @@
+// The state behind each method of a generated fake. The generated code only forwards to these, so what a fake does
+// is written once, here.
+
+use crate::Call;
+use std::fmt;
+
+// The state of a method of a generated fake, written once for each kind of fake. `local` is for the default ones,
+// behind a `RefCell`, and `sync` for `fake(sync)`, behind a `Mutex` and with only `Send` matchers, so a fake made of
+// them is `Send + Sync`.
+macro_rules! method {
+    ($($send:tt)*) => {
+        // A method's calls, each with its arguments captured as a `C`, and the values queued for it to return.
+        // Methods that return `()`, or something no queued value could stand for, have an `R` of `()` that's never
+        // asked for.
+        pub struct Method<C, R> {
+            state: Cell<State<C, R>>,
+        }
+
+        struct State<C, R> {
+            calls: Vec<(Call, C)>,
+            queue: VecDeque<R>,
+            // Queues that only answer calls whose arguments match, tried in the order they were added
+            conditions: Vec<(Box<dyn Matcher<C> $($send)*>, VecDeque<R>)>,
+            // What calls whose arguments match return once nothing else is queued for them, set with `When`
+            rules: Vec<(Box<dyn Matcher<C> $($send)*>, Box<dyn Fn() -> R $($send)*>)>,
+            // What every call returns instead of anything queued, set by `fail_all_with`
+            failure: Option<Box<dyn Fn() -> R $($send)*>>,
+            // Holds back the futures of an async method
+            gate: Option<Gate>,
+            expectations: Vec<Arc<Mutex<Expected<C>>>>,
+        }
+
+        impl<C, R> Default for State<C, R> {
+            fn default() -> Self {
+                State {
+                    calls: Vec::new(),
+                    queue: VecDeque::new(),
+                    conditions: Vec::new(),
+                    rules: Vec::new(),
+                    failure: None,
+                    gate: None,
+                    expectations: Vec::new(),
+                }
+            }
+        }
+
+        impl<C, R> Default for Method<C, R> {
+            fn default() -> Self {
+                Method {
+                    state: Cell::new(State::default()),
+                }
+            }
+        }
+
+        impl<C, R> Method<C, R> {
+            pub fn record(&self, call: Call, captured: C) {
+                lock(&self.state).record(call, captured);
+            }
+
+            // Records a call and picks the value it returns under the one lock, so that a call made on another
+            // thread in between can't take the value or have it picked for its own arguments
+            #[track_caller]
+            pub fn record_and_answer(&self, method: &str, call: Call, captured: C) -> R {
+                match self.record_and_try_answer(call, captured) {
+                    Some(value) => value,
+                    None => unanswered(method),
+                }
+            }
+
+            pub fn record_and_try_answer(&self, call: Call, captured: C) -> Option<R> {
+                let mut state = lock(&self.state);
+                let value = state.answer(&captured);
+                state.record(call, captured);
+                value
+            }
+
+            pub fn expect(&self, method: &'static str) -> Expectation<C> {
+                let (expectation, expected) = Expectation::new(method);
+                lock(&self.state).expectations.push(expected);
+                expectation
+            }
+
+            // What's wrong with each of the expectations that weren't met, which are all forgotten
+            pub fn unmet(&self) -> Vec<String> {
+                let expectations = std::mem::take(&mut lock(&self.state).expectations);
+                let unmet = expectations.iter().map(|expected| expectation::lock(expected).unmet());
+                unmet.flatten().collect()
+            }
+
+            pub fn calls(&self) -> Vec<Call> {
+                let state = lock(&self.state);
+                state.calls.iter().map(|(call, _)| call.clone()).collect()
+            }
+
+            pub fn captured(&self) -> Vec<(Call, C)>
+            where
+                C: Clone,
+            {
+                lock(&self.state).calls.clone()
+            }
+
+            // Forgets the calls and everything configured, leaving the futures of calls already made as they are
+            pub fn reset(&self) {
+                *lock(&self.state) = State::default();
+            }
+
+            pub fn hold(&self) -> Gate {
+                let gate = Gate::default();
+                lock(&self.state).gate = Some(gate.clone());
+                gate
+            }
+
+            // The gate the future of a call being made now has to wait for
+            pub fn gate(&self) -> Option<Gate> {
+                lock(&self.state).gate.clone()
+            }
+
+            // How many values are queued for calls to return, with any arguments or only matching ones, leaving out
+            // the answers that are never used up
+            pub fn queued(&self) -> usize {
+                let state = lock(&self.state);
+                let conditions = state.conditions.iter().map(|(_, values)| values.len());
+                state.queue.len() + conditions.sum::<usize>()
+            }
+
+            pub fn queue(&self, values: impl IntoIterator<Item = R>) {
+                lock(&self.state).queue.extend(values);
+            }
+
+            pub fn queue_when(
+                &self,
+                matcher: impl Matcher<C> $($send)* + 'static,
+                values: impl IntoIterator<Item = R>,
+            ) {
+                let values = values.into_iter().collect();
+                lock(&self.state).conditions.push((Box::new(matcher), values));
+            }
+
+            pub fn fail(&self, failure: impl Fn() -> R $($send)* + 'static) {
+                lock(&self.state).failure = Some(Box::new(failure));
+            }
+        }
+
+        impl<C, R> State<C, R> {
+            fn record(&mut self, call: Call, captured: C) {
+                for expected in &self.expectations {
+                    expectation::lock(expected).record(&call, &captured);
+                }
+                self.calls.push((call, captured));
+            }
+
+            // The failure every call returns, or else the next value queued for any call, or else for calls matching
+            // `captured`, or else what those calls are always answered with
+            fn answer(&mut self, captured: &C) -> Option<R> {
+                if let Some(failure) = &self.failure {
+                    return Some(failure());
+                }
+                self.queue.pop_front().or_else(|| {
+                    let queued = self
+                        .conditions
+                        .iter_mut()
+                        .filter(|(matcher, _)| matcher.matches(captured))
+                        .find_map(|(_, values)| values.pop_front());
+                    queued.or_else(|| {
+                        let (_, value) = self.rules.iter().find(|(matcher, _)| matcher.matches(captured))?;
+                        Some(value())
+                    })
+                })
+            }
+        }
+
+        impl<C, R, T> Rules<T> for Method<C, R>
+        where
+            R: Clone $($send)* + 'static,
+            T: Matcher<C> $($send)* + 'static,
+        {
+            type Value = R;
+
+            fn rule(&self, matcher: T, value: R) {
+                let value = Box::new(move || value.clone());
+                lock(&self.state).rules.push((Box::new(matcher), value));
+            }
+        }
+    };
+}
+
+pub mod local {
+    use super::unanswered;
+    use crate::expectation::{self, Expected};
+    use crate::matchers::Matcher;
+    use crate::when::Rules;
+    use crate::{Call, Expectation, Gate};
+    use std::cell::{RefCell as Cell, RefMut};
+    use std::collections::VecDeque;
+    use std::sync::{Arc, Mutex};
+
+    fn lock<T>(cell: &Cell<T>) -> RefMut<'_, T> {
+        cell.borrow_mut()
+    }
+
+    method!();
+}
+
+pub mod sync {
+    use super::unanswered;
+    use crate::expectation::{self, Expected};
+    use crate::matchers::Matcher;
+    use crate::when::Rules;
+    use crate::{Call, Expectation, Gate};
+    use std::collections::VecDeque;
+    use std::sync::{Arc, Mutex, Mutex as Cell, MutexGuard, PoisonError};
+
+    // A test that panicked while holding the lock has already failed, so what it left behind is still worth showing
+    fn lock<T>(cell: &Cell<T>) -> MutexGuard<'_, T> {
+        cell.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    method!(+ Send);
+}
+
+// The panic of a fake's method called with nothing to return
+#[track_caller]
+pub fn unanswered(method: &str) -> ! {
+    panic!("`{method}` was called with nothing queued for it to return")
+}
+
+// Every call of a fake in the order they were made, from the calls of each of its methods
+pub fn merge(calls: impl IntoIterator<Item = Vec<Call>>) -> Vec<Call> {
+    let mut merged: Vec<Call> = calls.into_iter().flatten().collect();
+    merged.sort_by_key(|call| call.seq);
+    merged
+}
+
+// A fake's `Debug`, as in `FakeCache { calls: [abx_get("greeting")], queued: {abx_get: 1} }`. Queued values are
+// only counted, so they don't have to be `Debug` themselves.
+pub fn debug(
+    f: &mut fmt::Formatter<'_>,
+    name: &str,
+    calls: Vec<Call>,
+    queued: &[(&str, usize)],
+) -> fmt::Result {
+    let queued = queued.iter().filter(|(_, count)| *count > 0).copied();
+    f.debug_struct(name)
+        .field("calls", &calls)
+        .field("queued", &Queued(queued.collect()))
+        .finish()
+}
+
+struct Queued<'a>(Vec<(&'a str, usize)>);
+
+impl fmt::Debug for Queued<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut map = f.debug_map();
+        for (method, count) in &self.0 {
+            // The method's name unquoted, as a field would be
+            map.entry(&format_args!("{method}"), count);
+        }
+        map.finish()
+    }
+}
+
+// One call listed in `verify_sequence!`: the calls of the fake it was made on, and the method and arguments it had, or
+// `None` for any arguments
+pub struct Step {
+    pub calls: Vec<Call>,
+    pub method: &'static str,
+    pub args: Option<Vec<Expected>>,
+}
+
+impl Step {
+    fn matches(&self, call: &Call) -> bool {
+        call.method == self.method
+            && self.args.as_ref().is_none_or(|args| {
+                args.len() == call.args.len()
+                    && args.iter().enumerate().all(|(i, arg)| {
+                        // Compared in the form the fake recorded the argument in
+                        match call.displayed.get(i) {
+                            Some(true) => arg.display.as_ref() == Some(&call.args[i]),
+                            _ => arg.debug == call.args[i],
+                        }
+                    })
+            })
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.args {
+            Some(args) => {
+                let args: Vec<_> = args.iter().map(|arg| arg.debug.as_str()).collect();
+                write!(f, "{}({})", self.method, args.join(", "))
+            }
+            None => write!(f, "{}(..)", self.method),
+        }
+    }
+}
+
+// An argument listed in `verify_sequence!`, formatted with `Debug`, and with `Display` too when it can be, since a
+// fake records an argument of a generic `Display` type with `to_string()`
+pub struct Expected {
+    debug: String,
+    display: Option<String>,
+}
+
+// `(&Shown(&arg)).expected()` picks `ViaDisplay` for an argument that's `Display` as well as `Debug`, and `ViaDebug`
+// for the rest, as method calls try the receiver's own type before borrowing it again
+pub struct Shown<'a, T: ?Sized>(pub &'a T);
+
+pub trait ViaDisplay {
+    fn expected(&self) -> Expected;
+}
+
+impl<T: fmt::Debug + fmt::Display + ?Sized> ViaDisplay for Shown<'_, T> {
+    fn expected(&self) -> Expected {
+        Expected {
+            debug: format!("{:?}", self.0),
+            display: Some(self.0.to_string()),
+        }
+    }
+}
+
+pub trait ViaDebug {
+    fn expected(&self) -> Expected;
+}
+
+impl<T: fmt::Debug + ?Sized> ViaDebug for &Shown<'_, T> {
+    fn expected(&self) -> Expected {
+        Expected {
+            debug: format!("{:?}", self.0),
+            display: None,
+        }
+    }
+}
+
+#[track_caller]
+pub fn verify_sequence(steps: &[Step]) {
+    let mut after = None;
+    for step in steps {
+        let next = step
+            .calls
+            .iter()
+            .find(|call| after.is_none_or(|seq| call.seq > seq) && step.matches(call));
+        match next {
+            Some(call) => after = Some(call.seq),
+            None => {
+                let mut made = merge(steps.iter().map(|step| step.calls.clone()));
+                made.dedup();
+                let expected: Vec<_> = steps.iter().map(Step::to_string).collect();
+                panic!(
+                    "expected the calls [{}] in that order, but `{step}` wasn't made after the ones before it; \
+                    the calls made were {made:?}",
+                    expected.join(", ")
+                );
+            }
+        }
+    }
+}
+
+// Names the methods a generated stub can be configured for, which `fake!` destructures to check none were left out
+pub trait Inline {
+    type Methods;
+}
@@ -0,0 +1,49 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll, Waker};
+
+/// Holds back the futures of an async method of a generated fake, got from its `hold_` method. Calls made while the
+/// gate is closed stay pending until [`release`](Gate::release) is called, and once released it stays open.
+#[derive(Clone, Default)]
+pub struct Gate {
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Default)]
+struct State {
+    released: bool,
+    // The tasks waiting on it, woken when it's released
+    wakers: Vec<Waker>,
+}
+
+impl Gate {
+    /// Lets every call waiting on the gate, and every later one, resolve.
+    pub fn release(&self) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.released = true;
+        for waker in state.wakers.drain(..) {
+            waker.wake();
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn wait(&self) -> impl Future<Output = ()> + Send + 'static {
+        Wait(self.clone())
+    }
+}
+
+struct Wait(Gate);
+
+impl Future for Wait {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.0.state.lock().unwrap_or_else(PoisonError::into_inner);
+        if state.released {
+            return Poll::Ready(());
+        }
+        state.wakers.push(cx.waker().clone());
+        Poll::Pending
+    }
+}
@@ -0,0 +1,21 @@
+// The latency of the async methods of a generated fake, given with `with_latency`
+
+use std::time::Duration;
+
+// Under the `tokio` feature the wait is tokio's own, which paused time skips as soon as the runtime is idle
+#[cfg(feature = "tokio")]
+pub async fn delay(latency: Duration) {
+    tokio::time::sleep(latency).await;
+}
+
+// Without a runtime to ask, a thread sleeps for the latency and then releases the gate the call waits on
+#[cfg(not(feature = "tokio"))]
+pub async fn delay(latency: Duration) {
+    let gate = crate::Gate::default();
+    let release = gate.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(latency);
+        release.release();
+    });
+    gate.wait().await;
+}
@@ -0,0 +1,86 @@
+//! Abstract a concrete dependency behind a trait without writing the impl by hand.
+//!
+//! Declare the methods you use in an `AbxXxx` trait, prefixing each with `abx_`, and put `#[wrap(Xxx)]` on it. The
+//! trait is implemented for `Xxx` by calling the concrete method of the same name, so code written against the trait
+//! takes the real dependency in production and a fake in tests.
+//!
+//! ```
+//! use depabx::wrap;
+//!
+//! pub struct Logger;
+//!
+//! impl Logger {
+//!     pub fn log_event(&self, description: &str) {
+//!         println!("Event: {description}");
+//!     }
+//! }
+//!
+//! #[wrap(Logger)]
+//! trait AbxLogger {
+//!     fn abx_log_event(&self, description: &str);
+//! }
+//!
+//! fn run<L: AbxLogger>(logger: &L) {
+//!     logger.abx_log_event("started");
+//! }
+//!
+//! run(&Logger);
+//! ```
+
+pub mod builtins;
+mod call;
+mod expectation;
+mod fake;
+mod gate;
+mod latency;
+pub mod matchers;
+mod registry;
+mod when;
+
+pub use call::{Call, Calls};
+pub use depabx_macros::{fake, wrap, wrap_impl};
+pub use expectation::Expectation;
+pub use gate::Gate;
+pub use registry::Registry;
+pub use when::When;
+
+/// Checks that the listed calls were made on generated fakes in that order, as in
+/// `verify_sequence![audit.abx_append("transfer 100"), payments.abx_transfer(..)]`. Other calls in between are
+/// ignored, and so are a call's arguments when they're given as `..`. Otherwise each argument is compared with the
+/// recorded one by its `Debug` form, so `"transfer 100"` matches a `&str` or `String` argument with that text, or by
+/// its `Display` form for an argument of a generic `Display` type, which is how the fake recorded it.
+#[macro_export]
+macro_rules! verify_sequence {
+    ($($fake:ident . $method:ident ($($args:tt)*)),+ $(,)?) => {
+        $crate::__private::verify_sequence(&[$($crate::__private::Step {
+            calls: $fake.calls(),
+            method: ::core::stringify!($method),
+            args: $crate::__verify_args!($($args)*),
+        }),+])
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __verify_args {
+    (..) => {
+        ::core::option::Option::None
+    };
+    ($($arg:expr),* $(,)?) => {
+        ::core::option::Option::Some(::std::vec![$({
+            #[allow(unused_imports)]
+            use $crate::__private::{ViaDebug as _, ViaDisplay as _};
+            (&$crate::__private::Shown(&$arg)).expected()
+        }),*])
+    };
+}
+
+// Support for the generated code, which isn't part of the API
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::expectation::verify;
+    pub use crate::fake::*;
+    pub use crate::latency::delay;
+    pub use crate::registry::report;
+    pub use crate::when::Rules;
+}
@@ -0,0 +1,78 @@
+//! Matchers describe the arguments of a call, to pick out the recorded calls of a generated fake that have them, or
+//! the calls it answers with particular values. A method with one argument takes one matcher, and a method with
+//! several takes a tuple of them, one per argument.
+//!
+//! Arguments are matched as the fake captured them, so a `&str` argument is matched as the `String` it was copied
+//! into, which `eq("+1")` compares against and `predicate(|text: &str| ...)` borrows from.
+
+use std::borrow::Borrow;
+use std::marker::PhantomData;
+
+/// Whether a captured argument, or a tuple of them, is one a test is looking for.
+pub trait Matcher<C: ?Sized> {
+    fn matches(&self, captured: &C) -> bool;
+}
+
+/// Matches any argument.
+pub fn any() -> Any {
+    Any
+}
+
+/// Matches an argument equal to `value`.
+pub fn eq<V>(value: V) -> Eq<V> {
+    Eq(value)
+}
+
+/// Matches an argument `f` returns `true` for, given the argument borrowed as a `T`.
+pub fn predicate<T: ?Sized, F: Fn(&T) -> bool>(f: F) -> Predicate<F, T> {
+    Predicate(f, PhantomData)
+}
+
+/// See [`any`].
+#[derive(Clone, Copy, Debug)]
+pub struct Any;
+
+/// See [`eq`].
+#[derive(Clone, Copy, Debug)]
+pub struct Eq<V>(V);
+
+/// See [`predicate`].
+pub struct Predicate<F, T: ?Sized>(F, PhantomData<fn(&T)>);
+
+impl<C: ?Sized> Matcher<C> for Any {
+    fn matches(&self, _: &C) -> bool {
+        true
+    }
+}
+
+impl<C: PartialEq<V> + ?Sized, V> Matcher<C> for Eq<V> {
+    fn matches(&self, captured: &C) -> bool {
+        *captured == self.0
+    }
+}
+
+impl<C: Borrow<T> + ?Sized, T: ?Sized, F: Fn(&T) -> bool> Matcher<C> for Predicate<F, T> {
+    fn matches(&self, captured: &C) -> bool {
+        (self.0)(captured.borrow())
+    }
+}
+
+macro_rules! tuples {
+    ($(($($m:ident $c:ident $i:tt),+))+) => {$(
+        impl<$($c,)+ $($m: Matcher<$c>,)+> Matcher<($($c,)+)> for ($($m,)+) {
+            fn matches(&self, captured: &($($c,)+)) -> bool {
+                $(self.$i.matches(&captured.$i))&&+
+            }
+        }
+    )+};
+}
+
+tuples! {
+    (M0 C0 0, M1 C1 1)
+    (M0 C0 0, M1 C1 1, M2 C2 2)
+    (M0 C0 0, M1 C1 1, M2 C2 2, M3 C3 3)
+    (M0 C0 0, M1 C1 1, M2 C2 2, M3 C3 3, M4 C4 4)
+    (M0 C0 0, M1 C1 1, M2 C2 2, M3 C3 3, M4 C4 4, M5 C5 5)
+    (M0 C0 0, M1 C1 1, M2 C2 2, M3 C3 3, M4 C4 4, M5 C5 5, M6 C6 6)
+    (M0 C0 0, M1 C1 1, M2 C2 2, M3 C3 3, M4 C4 4, M5 C5 5, M6 C6 6, M7 C7 7)
+}
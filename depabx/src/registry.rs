@@ -0,0 +1,59 @@
+use crate::Call;
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// A record that several generated fakes report their calls into, so a test can check the order of calls across
+/// dependencies. Fakes are given one with their `with_registry` constructor, and each call is recorded as it was
+/// written, along with its trait, as in `AbxCache::abx_get("/users")`.
+#[derive(Clone, Debug, Default)]
+pub struct Registry {
+    interactions: Arc<Mutex<Vec<String>>>,
+}
+
+impl Registry {
+    /// An empty registry.
+    pub fn new() -> Self {
+        Registry::default()
+    }
+
+    /// Every call reported so far, in the order they were made.
+    pub fn interactions(&self) -> Vec<String> {
+        self.lock().clone()
+    }
+
+    /// Checks that calls to the listed methods, named with their trait as in `"AbxCache::abx_get"`, were made in that
+    /// order. Calls to other methods in between are ignored.
+    #[track_caller]
+    pub fn assert_order<'a>(&self, methods: impl IntoIterator<Item = &'a str>) {
+        let interactions = self.interactions();
+        let methods: Vec<_> = methods.into_iter().collect();
+        let mut remaining = interactions.iter();
+        for method in &methods {
+            let called = |interaction: &&String| {
+                interaction
+                    .strip_prefix(method)
+                    .is_some_and(|rest| rest.starts_with('('))
+            };
+            if !remaining.any(|interaction| called(&interaction)) {
+                panic!(
+                    "expected calls to [{}] in that order, but `{method}` wasn't called after the ones before it; \
+                    the calls were {interactions:?}",
+                    methods.join(", ")
+                );
+            }
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<String>> {
+        self.interactions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+// Reports a call of a fake into its registry, if it has one, on its way to being recorded
+pub fn report(registry: &Option<Registry>, trait_name: &str, call: Call) -> Call {
+    if let Some(registry) = registry {
+        registry.lock().push(format!("{trait_name}::{call}"));
+    }
+    call
+}
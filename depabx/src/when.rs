@@ -0,0 +1,30 @@
+/// Answers to the calls of a fake's method whose arguments match a condition, got from its `when_` method, as in
+/// `users.when_get(eq("user-1")).returns(Ok(user))`.
+pub struct When<'a, M, T> {
+    method: &'a M,
+    matcher: T,
+}
+
+impl<'a, M, T> When<'a, M, T> {
+    #[doc(hidden)]
+    pub fn new(method: &'a M, matcher: T) -> Self {
+        When { method, matcher }
+    }
+
+    /// Answers every matching call with a clone of `value`, from now on. Values queued for the method are returned
+    /// first, and conditions are tried in the order they were added.
+    pub fn returns(self, value: M::Value)
+    where
+        M: Rules<T>,
+    {
+        self.method.rule(self.matcher, value);
+    }
+}
+
+// The methods of generated fakes, which keep the answers of `When`
+#[doc(hidden)]
+pub trait Rules<T> {
+    type Value;
+
+    fn rule(&self, matcher: T, value: Self::Value);
+}
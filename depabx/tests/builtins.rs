@@ -0,0 +1,453 @@
+// The built-in abstractions in `depabx::builtins`, and their test doubles.
+
+mod clock {
+    use depabx::builtins::clock::{AbxClock, SystemClock, TestClock};
+    use futures::executor::block_on;
+    use std::time::Duration;
+
+    #[test]
+    fn it_only_moves_when_advanced_or_slept_on() {
+        let clock = TestClock::new();
+        let started = clock.abx_now();
+        assert_eq!(clock.abx_now(), started);
+
+        clock.advance(Duration::from_secs(40));
+        block_on(clock.abx_sleep(Duration::from_secs(20)));
+
+        assert_eq!(clock.abx_elapsed(started), Duration::from_secs(60));
+        assert_eq!(clock.abx_now(), started + Duration::from_secs(60));
+    }
+
+    #[test]
+    fn it_never_reports_negative_elapsed_time() {
+        let clock = TestClock::new();
+        let later = clock.abx_now() + Duration::from_secs(5);
+
+        assert_eq!(clock.abx_elapsed(later), Duration::ZERO);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn the_system_clock_sleeps_on_tokio_time() {
+        let clock = SystemClock;
+        let started = tokio::time::Instant::now();
+
+        clock.abx_sleep(Duration::from_secs(3)).await;
+
+        assert_eq!(started.elapsed(), Duration::from_secs(3));
+        assert!(clock.abx_elapsed(clock.abx_now()) < Duration::from_secs(1));
+    }
+}
+
+mod fs {
+    use depabx::builtins::fs::{AbxFs, FileMetadata, MemoryFs, OsFs};
+    use std::io::ErrorKind;
+    use std::path::Path;
+
+    #[test]
+    fn it_keeps_files_in_memory() {
+        let fs = MemoryFs::with_files([("config.toml", "port = 80")]);
+        let (config, notes) = (Path::new("config.toml"), Path::new("notes/today.md"));
+
+        fs.abx_write(notes, b"standup").unwrap();
+
+        assert_eq!(fs.abx_read(config).unwrap(), b"port = 80");
+        assert_eq!(fs.abx_metadata(notes).unwrap(), FileMetadata { len: 7 });
+        fs.abx_remove(config).unwrap();
+        assert_eq!(fs.abx_read(config).unwrap_err().kind(), ErrorKind::NotFound);
+        assert_eq!(
+            fs.abx_remove(config).unwrap_err().kind(),
+            ErrorKind::NotFound
+        );
+        assert_eq!(
+            fs.abx_metadata(config).unwrap_err().kind(),
+            ErrorKind::NotFound
+        );
+    }
+
+    #[test]
+    fn the_os_filesystem_uses_the_disk() {
+        let path = std::env::temp_dir().join(format!("depabx-fs-{}", std::process::id()));
+
+        OsFs.abx_write(&path, b"on disk").unwrap();
+
+        assert_eq!(OsFs.abx_read(&path).unwrap(), b"on disk");
+        assert_eq!(OsFs.abx_metadata(&path).unwrap(), FileMetadata { len: 7 });
+        OsFs.abx_remove(&path).unwrap();
+        assert_eq!(
+            OsFs.abx_read(&path).unwrap_err().kind(),
+            ErrorKind::NotFound
+        );
+    }
+}
+
+mod env {
+    use depabx::builtins::env::{AbxEnv, MapEnv, ProcessEnv};
+
+    #[test]
+    fn it_keeps_variables_of_its_own() {
+        let env = MapEnv::with_vars([("RUST_LOG", "debug")]);
+
+        env.abx_set("PORT", "8080");
+        env.abx_set("RUST_LOG", "info");
+
+        assert_eq!(env.abx_get("PORT"), Some("8080".to_string()));
+        assert_eq!(env.abx_get("HOME"), None);
+        let vars = [("PORT", "8080"), ("RUST_LOG", "info")];
+        let vars: Vec<_> = vars.map(|(k, v)| (k.to_string(), v.to_string())).into();
+        assert_eq!(env.abx_vars(), vars);
+    }
+
+    #[test]
+    fn the_process_environment_is_shared() {
+        let key = format!("DEPABX_ENV_{}", std::process::id());
+
+        ProcessEnv.abx_set(&key, "on");
+
+        assert_eq!(std::env::var(&key).unwrap(), "on");
+        assert_eq!(ProcessEnv.abx_get(&key), Some("on".to_string()));
+        assert!(ProcessEnv.abx_vars().contains(&(key, "on".to_string())));
+    }
+}
+
+mod rng {
+    use depabx::builtins::rng::{AbxRng, ScriptedRng, SeededRng, SystemRng};
+
+    #[test]
+    fn it_repeats_its_sequence_for_a_seed() {
+        let (first, second) = (SeededRng::new(7), SeededRng::new(7));
+        let (mut a, mut b) = ([1, 2, 3, 4, 5], [1, 2, 3, 4, 5]);
+
+        first.abx_shuffle(&mut a);
+        second.abx_shuffle(&mut b);
+
+        assert_eq!(a, b);
+        let draws = |rng: &SeededRng| (0..5).map(|_| rng.abx_range(10, 20)).collect::<Vec<_>>();
+        assert_eq!(draws(&first), draws(&second));
+    }
+
+    #[test]
+    fn it_returns_the_scripted_numbers_in_order() {
+        let rng = ScriptedRng::new([4, 0]);
+        let mut items = ["c", "a", "b"];
+
+        rng.abx_shuffle(&mut items);
+
+        assert_eq!(items, ["c", "a", "b"]);
+        assert_eq!(rng.abx_range(0, 10), 4);
+        assert_eq!(rng.abx_range(0, 2), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "`ScriptedRng`'s next number is 12, which isn't in 0..10")]
+    fn it_panics_when_the_next_number_is_out_of_range() {
+        ScriptedRng::new([12]).abx_range(0, 10);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "`ScriptedRng` has run out of numbers to return from `abx_range(0, 10)`"
+    )]
+    fn it_panics_when_the_numbers_run_out() {
+        ScriptedRng::new([]).abx_range(0, 10);
+    }
+
+    #[test]
+    fn the_system_rng_stays_in_range() {
+        let mut items = [1, 2, 3];
+
+        SystemRng.abx_shuffle(&mut items);
+
+        items.sort();
+        assert_eq!(items, [1, 2, 3]);
+        assert!((0..100).all(|_| (5..8).contains(&SystemRng.abx_range(5, 8))));
+    }
+}
+
+mod process {
+    use depabx::builtins::process::{AbxProcess, CommandOutput, OsProcess, ScriptedProcess};
+    use std::io::ErrorKind;
+
+    #[test]
+    fn it_answers_commands_from_its_script() {
+        let process = ScriptedProcess::new()
+            .on("cargo --version", CommandOutput::success("cargo 1.95.0\n"))
+            .on(
+                "cargo publish",
+                CommandOutput::failure(101, "error: no token\n"),
+            );
+
+        let version = process.abx_output("cargo", &["--version"]).unwrap();
+        let published = process.abx_status("cargo", &["publish"]).unwrap();
+        let spawned = process.abx_spawn("cargo", &["--version"]).unwrap();
+        let missing = process.abx_output("make", &[]).unwrap_err();
+
+        assert_eq!(
+            version,
+            CommandOutput {
+                code: Some(0),
+                stdout: b"cargo 1.95.0\n".to_vec(),
+                stderr: vec![]
+            }
+        );
+        assert_eq!(published, Some(101));
+        assert!(spawned > 0);
+        assert_eq!(missing.kind(), ErrorKind::NotFound);
+        assert_eq!(missing.to_string(), "unscripted command: make");
+        let commands = [
+            "cargo --version",
+            "cargo publish",
+            "cargo --version",
+            "make",
+        ];
+        assert_eq!(process.commands(), commands);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn the_os_process_runs_the_command() {
+        let script = ["-c", "echo out; echo err >&2; exit 3"];
+
+        let output = OsProcess.abx_output("sh", &script).unwrap();
+
+        assert_eq!(output.code, Some(3));
+        assert_eq!(output.stdout, b"out\n");
+        assert_eq!(output.stderr, b"err\n");
+        assert_eq!(
+            OsProcess.abx_status("sh", &["-c", "exit 0"]).unwrap(),
+            Some(0)
+        );
+        assert!(OsProcess.abx_spawn("sh", &["-c", "exit 0"]).unwrap() > 0);
+    }
+}
+
+mod tcp {
+    use depabx::builtins::tcp::{duplex, AbxConnection, AbxTcp, MemoryTcp, OsTcp};
+    use std::io::{ErrorKind, Read, Write};
+    use std::net::TcpListener;
+
+    #[test]
+    fn it_pipes_bytes_between_the_ends() {
+        let (mut near, mut far) = duplex();
+        let mut buf = [0; 8];
+
+        assert_eq!(
+            near.abx_read(&mut buf).unwrap_err().kind(),
+            ErrorKind::WouldBlock
+        );
+        near.abx_write(b"hello").unwrap();
+        assert_eq!(far.abx_read(&mut buf[..3]).unwrap(), 3);
+        assert_eq!(far.abx_read(&mut buf[3..]).unwrap(), 2);
+        assert_eq!(&buf[..5], b"hello");
+        drop(near);
+        assert_eq!(far.abx_read(&mut buf).unwrap(), 0);
+        assert_eq!(
+            far.abx_write(b"bye").unwrap_err().kind(),
+            ErrorKind::BrokenPipe
+        );
+    }
+
+    #[test]
+    fn it_connects_to_listened_addresses_only() {
+        let tcp = MemoryTcp::new();
+        let mut server = tcp.listen("db:5432");
+
+        let mut client = tcp.abx_connect("db:5432").unwrap();
+        client.abx_write(b"SELECT 1").unwrap();
+
+        let mut buf = [0; 8];
+        assert_eq!(server.abx_read(&mut buf).unwrap(), 8);
+        assert_eq!(&buf, b"SELECT 1");
+        let refused = tcp.abx_connect("db:5432").unwrap_err();
+        assert_eq!(refused.kind(), ErrorKind::ConnectionRefused);
+    }
+
+    #[test]
+    fn the_os_tcp_connects_over_the_network() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+
+        let mut client = OsTcp.abx_connect(&addr).unwrap();
+        let (mut server, _) = listener.accept().unwrap();
+        client.abx_write(b"ping").unwrap();
+        let mut buf = [0; 4];
+        server.read_exact(&mut buf).unwrap();
+        server.write_all(b"pong").unwrap();
+
+        assert_eq!(&buf, b"ping");
+        let mut reply = [0; 4];
+        client.read_exact(&mut reply).unwrap();
+        assert_eq!(&reply, b"pong");
+    }
+}
+
+mod console {
+    use depabx::builtins::console::{AbxConsole, ScriptedConsole, StdConsole};
+
+    #[test]
+    fn it_scripts_input_and_captures_output() {
+        let console = ScriptedConsole::new(["y"]);
+
+        console.abx_print("Continue? ");
+        let answer = console.abx_read_line().unwrap();
+        console.abx_eprint("warning: no backup\n");
+
+        assert_eq!(answer, Some("y".to_string()));
+        assert_eq!(console.abx_read_line().unwrap(), None);
+        assert_eq!(console.stdout(), "Continue? ");
+        assert_eq!(console.stderr(), "warning: no backup\n");
+    }
+
+    // The output goes to the test harness, which captures it
+    #[test]
+    fn the_std_console_prints() {
+        StdConsole.abx_print("printed\n");
+        StdConsole.abx_eprint("printed to stderr\n");
+    }
+}
+
+mod timer {
+    use depabx::builtins::timer::{AbxTimer, TestTimer, TokioTimer};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[tokio::test(start_paused = true)]
+    async fn it_records_sleeps_and_follows_paused_time() {
+        let timer = TestTimer::new();
+        let started = timer.abx_now();
+
+        timer.abx_sleep(Duration::from_secs(1)).await;
+        timer.abx_sleep(Duration::from_secs(2)).await;
+
+        let secs = Duration::from_secs;
+        assert_eq!(timer.sleeps(), vec![secs(1), secs(2)]);
+        assert_eq!(started.elapsed(), secs(3));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn it_is_moved_along_by_advance() {
+        let timer = Arc::new(TestTimer::new());
+        let sleeper = Arc::clone(&timer);
+        let sleep = tokio::spawn(async move { sleeper.abx_sleep(Duration::from_secs(60)).await });
+
+        tokio::task::yield_now().await;
+        tokio::time::advance(Duration::from_secs(60)).await;
+
+        sleep.await.unwrap();
+        assert_eq!(timer.sleeps(), vec![Duration::from_secs(60)]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn the_tokio_timer_sleeps_on_tokio_time() {
+        let started = TokioTimer.abx_now();
+
+        TokioTimer.abx_sleep(Duration::from_millis(250)).await;
+
+        assert_eq!(started.elapsed(), Duration::from_millis(250));
+    }
+}
+
+mod http {
+    use depabx::builtins::http::{AbxHttp, CannedHttp, HttpError, Method, Request, Response};
+    use futures::executor::block_on;
+
+    #[test]
+    fn it_serves_canned_responses_by_method_and_url() {
+        let http = CannedHttp::new()
+            .on(
+                Method::Get,
+                "https://api.test/health",
+                Response::new(200, "ok"),
+            )
+            .on(
+                Method::Delete,
+                "https://api.test/health",
+                Response::new(405, ""),
+            );
+        let delete = Request::new(Method::Delete, "https://api.test/health", b"now".to_vec());
+
+        let health =
+            block_on(http.abx_send(Request::get("https://api.test/health").header("X-Trace", "1")));
+        let deleted = block_on(http.abx_send(delete.clone()));
+        let missing = block_on(http.abx_send(Request::get("https://api.test/users")));
+
+        assert_eq!(health.unwrap().text(), "ok");
+        assert_eq!(deleted.unwrap().status, 405);
+        let error = HttpError("no canned response for Get https://api.test/users".to_string());
+        assert_eq!(missing, Err(error.clone()));
+        assert_eq!(
+            error.to_string(),
+            "HTTP request failed: no canned response for Get https://api.test/users"
+        );
+        let requests = http.requests();
+        assert_eq!(
+            requests[0].headers,
+            vec![("X-Trace".to_string(), "1".to_string())]
+        );
+        assert_eq!(requests[1], delete);
+        assert_eq!(requests.len(), 3);
+    }
+
+    // Only built with the `reqwest` feature, which the workspace leaves off
+    #[cfg(feature = "reqwest")]
+    #[tokio::test]
+    async fn the_reqwest_client_sends_the_request() {
+        use depabx::builtins::http::ReqwestHttp;
+        use std::io::{BufRead, BufReader, Write};
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/users/1/name", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut head = Vec::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" {
+                    break;
+                }
+                head.push(line.trim_end().to_lowercase());
+            }
+            let response = "HTTP/1.1 200 OK\r\ncontent-length: 3\r\nconnection: close\r\n\r\nAda";
+            reader.get_mut().write_all(response.as_bytes()).unwrap();
+            head
+        });
+
+        let response = ReqwestHttp::new()
+            .abx_send(Request::get(&url).header("Accept", "text/plain"))
+            .await
+            .unwrap();
+
+        assert_eq!(response, Response::new(200, "Ada"));
+        let head = server.join().unwrap();
+        assert_eq!(head[0], "get /users/1/name http/1.1");
+        assert!(head.contains(&"accept: text/plain".to_string()));
+    }
+}
+
+mod signals {
+    use depabx::builtins::signals::{AbxSignals, Signal, TestSignals};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn it_wakes_waiters_when_triggered() {
+        let signals = Arc::new(TestSignals::new());
+        let waiter = Arc::clone(&signals);
+        let waiting = tokio::spawn(async move { waiter.abx_wait().await.unwrap() });
+
+        tokio::task::yield_now().await;
+        signals.trigger(Signal::Terminate);
+
+        assert_eq!(waiting.await.unwrap(), Signal::Terminate);
+    }
+
+    #[tokio::test]
+    async fn it_stays_signalled() {
+        let signals = TestSignals::new();
+        signals.trigger(Signal::Interrupt);
+
+        assert_eq!(signals.abx_wait().await.unwrap(), Signal::Interrupt);
+        assert_eq!(signals.abx_wait().await.unwrap(), Signal::Interrupt);
+    }
+}
@@ -0,0 +1,1984 @@
+// The test doubles `#[wrap]` generates next to the impl.
+
+use depabx::wrap;
+
+mod recording_fake {
+    use super::wrap;
+    use futures::executor::block_on;
+
+    pub struct Logger;
+
+    impl Logger {
+        pub fn log_event(&self, description: &str) {
+            println!("Event: {description}");
+        }
+
+        pub fn log_error(&self, description: &str, code: u32) {
+            println!("Error {code}: {description}");
+        }
+
+        pub async fn flush(&self) {}
+    }
+
+    #[wrap(Logger, fake)]
+    trait AbxLogger {
+        fn abx_log_event(&self, description: &str);
+        fn abx_log_error(&self, description: &str, code: u32);
+        async fn abx_flush(&self);
+    }
+
+    fn run<L: AbxLogger>(logger: &L) {
+        logger.abx_log_event("started");
+        logger.abx_log_error("disk full", 500);
+        block_on(logger.abx_flush());
+    }
+
+    #[test]
+    fn it_records_every_call_in_order() {
+        let logger = FakeLogger::new();
+
+        run(&logger);
+        run(&Logger);
+
+        let calls = logger.calls();
+        assert_eq!(calls.len(), 3);
+        assert_eq!(calls[0].method, "abx_log_event");
+        assert_eq!(calls[0].args, vec!["\"started\""]);
+        assert_eq!(calls[1].to_string(), "abx_log_error(\"disk full\", 500)");
+        assert_eq!(calls[2].method, "abx_flush");
+    }
+
+    #[test]
+    fn it_records_async_calls_when_theyre_made() {
+        let logger = FakeLogger::new();
+
+        let flushed = logger.abx_flush();
+        assert_eq!(logger.calls()[0].method, "abx_flush");
+        block_on(flushed);
+    }
+}
+
+mod stub_builder {
+    use super::wrap;
+    use futures::executor::block_on;
+
+    pub struct WeatherApi;
+
+    impl WeatherApi {
+        pub fn temperature(&self, city: &str) -> f64 {
+            if city == "Oslo" {
+                -3.0
+            } else {
+                18.0
+            }
+        }
+
+        pub fn report_usage(&self, _calls: u32) {}
+
+        pub async fn forecast(&self, days: u8) -> Vec<f64> {
+            vec![18.0; days as usize]
+        }
+    }
+
+    #[wrap(WeatherApi, stub)]
+    trait AbxWeatherApi {
+        fn abx_temperature(&self, city: &str) -> f64;
+        fn abx_report_usage(&self, calls: u32);
+        async fn abx_forecast(&self, days: u8) -> Vec<f64>;
+    }
+
+    fn run<W: AbxWeatherApi>(weather: &W) -> (f64, Vec<f64>) {
+        weather.abx_report_usage(2);
+        (
+            weather.abx_temperature("Oslo"),
+            block_on(weather.abx_forecast(2)),
+        )
+    }
+
+    #[test]
+    fn it_answers_with_the_configured_closures() {
+        let usage = std::cell::Cell::new(0);
+        let weather = StubWeatherApi::new()
+            .on_temperature(|city| if city == "Oslo" { 1.0 } else { 2.0 })
+            .on_report_usage(|calls| usage.set(calls))
+            .on_forecast(|days| vec![0.5; days.into()]);
+
+        assert_eq!(run(&weather), (1.0, vec![0.5, 0.5]));
+        assert_eq!(usage.get(), 2);
+        assert_eq!(run(&WeatherApi).0, -3.0);
+    }
+
+    #[test]
+    fn it_does_nothing_for_unconfigured_unit_methods() {
+        StubWeatherApi::default().abx_report_usage(1);
+    }
+
+    #[test]
+    #[should_panic(expected = "configure it with `on_temperature`")]
+    fn it_panics_naming_unconfigured_methods() {
+        StubWeatherApi::new().abx_temperature("Oslo");
+    }
+}
+
+mod queued_returns {
+    use super::wrap;
+    use futures::executor::block_on;
+
+    #[derive(Debug, PartialEq)]
+    pub enum InventoryError {
+        Unavailable,
+    }
+
+    pub struct InventoryApi;
+
+    impl InventoryApi {
+        pub fn get(&self, _sku: &str) -> Result<u32, InventoryError> {
+            Ok(12)
+        }
+
+        pub async fn reserve(&self, _sku: &str, count: u32) -> u32 {
+            count
+        }
+    }
+
+    #[wrap(InventoryApi, fake)]
+    trait AbxInventoryApi {
+        fn abx_get(&self, sku: &str) -> Result<u32, InventoryError>;
+        async fn abx_reserve(&self, sku: &str, count: u32) -> u32;
+    }
+
+    fn run<I: AbxInventoryApi>(inventory: &I) -> Result<u32, InventoryError> {
+        let mut result = inventory.abx_get("sku-1");
+        while result == Err(InventoryError::Unavailable) {
+            result = inventory.abx_get("sku-1");
+        }
+        Ok(block_on(inventory.abx_reserve("sku-1", result?)))
+    }
+
+    #[test]
+    fn it_answers_calls_from_the_queue_in_order() {
+        let inventory = FakeInventoryApi::new();
+        inventory.returns_for_get([Err(InventoryError::Unavailable), Ok(4)]);
+        inventory.returns_for_reserve([3]);
+
+        assert_eq!(run(&inventory), Ok(3));
+        assert_eq!(inventory.calls().len(), 3);
+        assert_eq!(run(&InventoryApi), Ok(12));
+    }
+
+    #[test]
+    #[should_panic(expected = "`abx_get` was called with nothing queued for it to return")]
+    fn it_panics_when_nothing_is_queued() {
+        let inventory = FakeInventoryApi::new();
+        inventory.returns_for_get([Ok(1)]);
+
+        inventory.abx_get("sku-1").unwrap();
+        inventory.abx_get("sku-1").unwrap();
+    }
+}
+
+mod strict_stubs {
+    use super::wrap;
+
+    pub struct Storage;
+
+    impl Storage {
+        pub fn list(&self, prefix: &str) -> Vec<String> {
+            vec![format!("{prefix}/a")]
+        }
+
+        pub fn delete(&self, _key: &str, _force: bool) {}
+    }
+
+    #[wrap(Storage, stub(strict))]
+    trait AbxStorage {
+        fn abx_list(&self, prefix: &str) -> Vec<String>;
+        fn abx_delete(&self, key: &str, force: bool);
+    }
+
+    fn run<S: AbxStorage>(storage: &S) {
+        for key in storage.abx_list("logs") {
+            storage.abx_delete(&key, true);
+        }
+    }
+
+    #[test]
+    fn it_runs_configured_methods() {
+        let storage = StubStorage::new()
+            .on_list(|_| vec![])
+            .on_delete(|_, _| unreachable!());
+
+        run(&storage);
+        run(&Storage);
+    }
+
+    #[test]
+    #[should_panic(expected = "unexpected call to AbxStorage::abx_delete(\"logs/a\", true)")]
+    fn it_panics_with_unexpected_calls() {
+        run(&StubStorage::new().on_list(|prefix| vec![format!("{prefix}/a")]));
+    }
+}
+
+mod nice_stubs {
+    use super::wrap;
+
+    pub struct Profile {
+        pub name: String,
+    }
+
+    pub struct ProfileService;
+
+    impl ProfileService {
+        pub fn display_name(&self, id: u32) -> Option<String> {
+            Some(format!("user {id}"))
+        }
+
+        pub fn follower_count(&self, _id: u32) -> u64 {
+            1_200
+        }
+
+        pub fn load(&self, id: u32) -> Profile {
+            Profile {
+                name: format!("user {id}"),
+            }
+        }
+    }
+
+    #[wrap(ProfileService, stub(nice))]
+    trait AbxProfileService {
+        fn abx_display_name(&self, id: u32) -> Option<String>;
+        fn abx_follower_count(&self, id: u32) -> u64;
+        #[abx(no_default)]
+        fn abx_load(&self, id: u32) -> Profile;
+    }
+
+    fn run<P: AbxProfileService>(profiles: &P) -> (Option<String>, u64) {
+        (profiles.abx_display_name(1), profiles.abx_follower_count(1))
+    }
+
+    #[test]
+    fn it_returns_defaults_for_unconfigured_methods() {
+        let profiles = StubProfileService::new().on_follower_count(|_| 3);
+
+        assert_eq!(run(&profiles), (None, 3));
+        assert_eq!(run(&ProfileService).1, 1_200);
+        assert_eq!(ProfileService.abx_load(1).name, "user 1");
+    }
+
+    #[test]
+    #[should_panic(expected = "configure it with `on_load`")]
+    fn it_panics_for_methods_without_a_default() {
+        StubProfileService::new().abx_load(1);
+    }
+}
+
+mod call_counts {
+    use super::wrap;
+
+    pub struct Tracker;
+
+    impl Tracker {
+        pub fn page_view(&self, _path: &str) {}
+
+        pub fn conversion(&self, _value_cents: u64) {}
+    }
+
+    #[wrap(Tracker, fake)]
+    trait AbxTracker {
+        fn abx_page_view(&self, path: &str);
+        fn abx_conversion(&self, value_cents: u64);
+    }
+
+    fn run<T: AbxTracker>(tracker: &T, paths: &[&str]) {
+        for path in paths {
+            tracker.abx_page_view(path);
+        }
+        tracker.abx_conversion(100);
+    }
+
+    #[test]
+    fn it_counts_calls_per_method() {
+        let tracker = FakeTracker::new();
+
+        run(&tracker, &["/", "/a"]);
+        run(&Tracker, &[]);
+
+        tracker.calls_of("abx_page_view").assert_called_times(2);
+        tracker.conversion_calls().assert_called_once();
+        assert_eq!(tracker.page_view_calls()[1].args, ["\"/a\""]);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "expected `abx_conversion` to be called twice, but it was called once: [abx_conversion(100)]"
+    )]
+    fn it_lists_the_calls_when_the_count_is_wrong() {
+        let tracker = FakeTracker::new();
+
+        run(&tracker, &[]);
+
+        tracker.conversion_calls().assert_called_times(2);
+    }
+
+    #[test]
+    #[should_panic(expected = "`FakeTracker` has no method named `abx_pageview`")]
+    fn it_panics_on_unknown_method_names() {
+        FakeTracker::new().calls_of("abx_pageview");
+    }
+}
+
+mod matchers {
+    use super::wrap;
+    use depabx::matchers::{any, eq, predicate};
+
+    pub struct SmsGateway;
+
+    impl SmsGateway {
+        pub fn send(&self, number: &str, text: &str) -> bool {
+            !number.is_empty() && !text.is_empty()
+        }
+
+        pub fn balance(&self, account: u32) -> u32 {
+            account
+        }
+    }
+
+    #[wrap(SmsGateway, fake)]
+    trait AbxSmsGateway {
+        fn abx_send(&self, number: &str, text: &str) -> bool;
+        fn abx_balance(&self, account: u32) -> u32;
+    }
+
+    fn run<G: AbxSmsGateway>(gateway: &G, numbers: &[&str]) -> Vec<bool> {
+        let sent = numbers
+            .iter()
+            .map(|number| gateway.abx_send(number, "Database is down"))
+            .collect();
+        gateway.abx_balance(7);
+        sent
+    }
+
+    #[test]
+    fn it_answers_matching_calls_from_their_own_queues() {
+        let gateway = FakeSmsGateway::new();
+        gateway.returns_for_send_when((eq("+1"), any()), [true, true]);
+        gateway.returns_for_send_when((eq("+2"), any()), [false]);
+        gateway.returns_for_balance_when(eq(7), [70]);
+
+        assert_eq!(run(&gateway, &["+1", "+2", "+1"]), [true, false, true]);
+        assert_eq!(run(&SmsGateway, &["+1"]), [true]);
+    }
+
+    #[test]
+    fn it_narrows_down_recorded_calls() {
+        let gateway = FakeSmsGateway::new();
+        gateway.returns_for_send([true, false]);
+        gateway.returns_for_balance([0]);
+
+        run(&gateway, &["+1", "+2"]);
+
+        let calls = gateway.send_calls();
+        calls
+            .clone()
+            .matching((any(), predicate(|text: &str| text.contains("down"))))
+            .assert_called_times(2);
+        calls.matching((eq("+3"), any())).assert_not_called();
+        gateway.balance_calls().matching(eq(7)).assert_called_once();
+    }
+
+    #[test]
+    #[should_panic(expected = "`abx_balance` was called with nothing queued for it to return")]
+    fn it_panics_when_no_queue_matches() {
+        let gateway = FakeSmsGateway::new();
+        gateway.returns_for_balance_when(eq(1), [10]);
+
+        gateway.abx_balance(2);
+    }
+}
+
+mod call_order {
+    use super::wrap;
+    use depabx::verify_sequence;
+    use std::fmt::Display;
+
+    pub struct AuditLog;
+    pub struct PaymentApi;
+
+    impl AuditLog {
+        pub fn append(&self, _entry: &str) {}
+    }
+
+    impl PaymentApi {
+        pub fn transfer(&self, cents: u64) -> bool {
+            cents > 0
+        }
+    }
+
+    #[wrap(AuditLog, fake)]
+    trait AbxAuditLog {
+        fn abx_append(&self, entry: &str);
+    }
+
+    #[wrap(PaymentApi, fake)]
+    trait AbxPaymentApi {
+        fn abx_transfer(&self, cents: u64) -> bool;
+    }
+
+    fn run<A: AbxAuditLog, P: AbxPaymentApi>(audit: &A, payments: &P) -> bool {
+        audit.abx_append("transfer 100");
+        let ok = payments.abx_transfer(100);
+        audit.abx_append("done");
+        ok
+    }
+
+    fn fakes() -> (FakeAuditLog, FakePaymentApi) {
+        let (audit, payments) = (FakeAuditLog::new(), FakePaymentApi::new());
+        payments.returns_for_transfer([true]);
+        assert!(run(&audit, &payments));
+        assert!(run(&AuditLog, &PaymentApi));
+        (audit, payments)
+    }
+
+    #[test]
+    fn it_verifies_calls_across_fakes() {
+        let (audit, payments) = fakes();
+
+        verify_sequence![
+            audit.abx_append("transfer 100"),
+            payments.abx_transfer(100),
+            audit.abx_append(..),
+        ];
+        verify_sequence![payments.abx_transfer(..), audit.abx_append("done")];
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "but `abx_append(\"transfer 100\")` wasn't made after the ones before it"
+    )]
+    fn it_panics_on_calls_out_of_order() {
+        let (audit, payments) = fakes();
+
+        verify_sequence![payments.abx_transfer(..), audit.abx_append("transfer 100")];
+    }
+
+    pub struct Logger;
+
+    impl Logger {
+        pub fn log_event<T: Display>(&self, _description: T) {}
+
+        pub fn log_error<T: Display>(&self, _description: T) {}
+    }
+
+    #[wrap(Logger, fake)]
+    trait AbxLogger {
+        fn abx_log_event<T: Display>(&self, description: T);
+        fn abx_log_error<T: Display>(&self, description: T);
+    }
+
+    // An argument of a generic `Display` type is recorded with `to_string()`, and matched the same way
+    #[test]
+    fn it_verifies_display_arguments() {
+        let logger = FakeLogger::new();
+
+        logger.abx_log_event("started");
+        logger.abx_log_error(500);
+        Logger.abx_log_event("started");
+
+        verify_sequence![logger.abx_log_event("started"), logger.abx_log_error(500)];
+        verify_sequence![logger.abx_log_event(String::from("started"))];
+    }
+}
+
+mod sync_fakes {
+    use super::wrap;
+    use depabx::matchers::eq;
+    use std::thread;
+
+    pub struct Resizer;
+
+    impl Resizer {
+        pub fn resize(&self, image: &str, width: u32) -> String {
+            format!("{image}@{width}w")
+        }
+    }
+
+    #[wrap(Resizer, fake(sync))]
+    trait AbxResizer: Sync {
+        fn abx_resize(&self, image: &str, width: u32) -> String;
+    }
+
+    fn run<R: AbxResizer>(resizer: &R, images: &[&str]) -> Vec<String> {
+        thread::scope(|scope| {
+            let jobs: Vec<_> = images
+                .iter()
+                .map(|image| scope.spawn(move || resizer.abx_resize(image, 320)))
+                .collect();
+            jobs.into_iter().map(|job| job.join().unwrap()).collect()
+        })
+    }
+
+    #[test]
+    fn it_can_be_shared_with_threads() {
+        let resizer = FakeResizer::new();
+        resizer.returns_for_resize_when(eq(("b.png".to_string(), 320)), ["b".to_string()]);
+        resizer.returns_for_resize_when(eq(("a.png".to_string(), 320)), ["a".to_string()]);
+
+        let mut resized = run(&resizer, &["a.png", "b.png"]);
+        resized.sort();
+
+        assert_eq!(resized, ["a", "b"]);
+        resizer.resize_calls().assert_called_times(2);
+        assert_eq!(run(&Resizer, &["c.png"]), ["c.png@320w"]);
+    }
+
+    // Each call is answered for its own arguments, even while other threads are calling too
+    #[test]
+    fn it_answers_each_thread_for_its_own_arguments() {
+        let resizer = FakeResizer::new();
+        let images: Vec<String> = (0..64).map(|i| format!("{i}.png")).collect();
+        for image in &images {
+            resizer.returns_for_resize_when(eq((image.clone(), 320)), [image.replace(".png", "")]);
+        }
+
+        let images: Vec<&str> = images.iter().map(String::as_str).collect();
+        let resized = run(&resizer, &images);
+
+        let expected: Vec<String> = (0..64).map(|i| i.to_string()).collect();
+        assert_eq!(resized, expected);
+    }
+
+    pub struct Thumbnailer;
+
+    impl Thumbnailer {
+        pub fn size(&self, image: &str) -> u32 {
+            image.len() as u32
+        }
+
+        pub async fn thumbnail(&self, image: &str) -> String {
+            format!("{image}@64w")
+        }
+    }
+
+    // A plain `fake` of a trait that has to be `Sync`, or whose futures are `Send`, is backed by a `Mutex` as if it
+    // were `fake(sync)`
+    #[wrap(Thumbnailer, fake)]
+    trait AbxImageSize: Sync {
+        fn abx_size(&self, image: &str) -> u32;
+    }
+
+    #[wrap(Thumbnailer, fake)]
+    trait AbxThumbnailer {
+        #[abx(send)]
+        async fn abx_thumbnail(&self, image: &str) -> String;
+    }
+
+    #[test]
+    fn it_is_sync_when_the_trait_needs_it() {
+        let (sizes, thumbnailer) = (FakeImageSize::new(), FakeThumbnailer::new());
+        sizes.returns_for_size([48]);
+        thumbnailer.returns_for_thumbnail(["thumb".to_string()]);
+
+        let (size, thumbnail) = thread::scope(|scope| {
+            let size = scope.spawn(|| sizes.abx_size("a.png"));
+            let thumbnail =
+                scope.spawn(|| futures::executor::block_on(thumbnailer.abx_thumbnail("a.png")));
+            (size.join().unwrap(), thumbnail.join().unwrap())
+        });
+
+        assert_eq!((size, thumbnail.as_str()), (48, "thumb"));
+        assert_eq!(Thumbnailer.abx_size("a.png"), 5);
+    }
+}
+
+mod async_fakes {
+    use super::wrap;
+    use futures::{executor::block_on, poll};
+    use std::pin::pin;
+
+    pub struct PriceFeed;
+
+    impl PriceFeed {
+        pub async fn quote(&self, _symbol: &str) -> f64 {
+            12.5
+        }
+    }
+
+    #[wrap(PriceFeed, fake)]
+    trait AbxPriceFeed {
+        async fn abx_quote(&self, symbol: &str) -> f64;
+    }
+
+    #[wrap(PriceFeed, fake(sync), futures = "boxed")]
+    #[abx(send)]
+    trait AbxSharedPriceFeed {
+        async fn abx_quote(&self, symbol: &str) -> f64;
+    }
+
+    async fn run<F: AbxPriceFeed>(feed: &F, shares: u32) -> f64 {
+        feed.abx_quote("ACME").await * shares as f64
+    }
+
+    #[test]
+    fn it_resolves_queued_values_right_away() {
+        let feed = FakePriceFeed::new();
+        feed.returns_for_quote([2.0]);
+
+        assert_eq!(block_on(run(&feed, 3)), 6.0);
+        assert_eq!(block_on(run(&PriceFeed, 2)), 25.0);
+    }
+
+    #[test]
+    fn it_stays_pending_until_the_gate_is_released() {
+        let feed = FakePriceFeed::new();
+        feed.returns_for_quote([2.0]);
+        let gate = feed.hold_quote();
+
+        block_on(async {
+            let mut valuation = pin!(run(&feed, 3));
+            assert!(poll!(valuation.as_mut()).is_pending());
+            feed.quote_calls().assert_called_once();
+
+            gate.release();
+
+            assert_eq!(valuation.await, 6.0);
+        });
+    }
+
+    #[test]
+    fn it_holds_sendable_futures() {
+        let feed = FakeSharedPriceFeed::new();
+        feed.returns_for_quote([1.0, 2.0]);
+        let gate = feed.hold_quote();
+
+        let held = feed.abx_quote("ACME");
+        gate.release();
+
+        let sent = std::thread::scope(|scope| scope.spawn(|| block_on(held)).join().unwrap());
+        assert_eq!(sent + block_on(feed.abx_quote("ACME")), 3.0);
+        assert_eq!(
+            block_on(AbxSharedPriceFeed::abx_quote(&PriceFeed, "ACME")),
+            12.5
+        );
+    }
+}
+
+mod spies {
+    use super::wrap;
+    use futures::executor::block_on;
+    use std::collections::HashMap;
+
+    #[derive(Default)]
+    pub struct KvStore {
+        entries: HashMap<String, String>,
+    }
+
+    impl KvStore {
+        pub fn get(&self, key: &str) -> Option<String> {
+            self.entries.get(key).cloned()
+        }
+
+        pub fn set(&mut self, key: &str, value: &str) {
+            self.entries.insert(key.to_string(), value.to_string());
+        }
+
+        pub async fn len(&self) -> usize {
+            self.entries.len()
+        }
+    }
+
+    #[wrap(KvStore, spy)]
+    trait AbxKvStore {
+        fn abx_get(&self, key: &str) -> Option<String>;
+        fn abx_set(&mut self, key: &str, value: &str);
+        async fn abx_len(&self) -> usize;
+    }
+
+    fn run<S: AbxKvStore>(store: &mut S, key: &str) -> String {
+        store.abx_get(key).unwrap_or_else(|| {
+            store.abx_set(key, "light");
+            "light".to_string()
+        })
+    }
+
+    #[test]
+    fn it_passes_calls_on_and_records_them() {
+        let mut store = SpyKvStore::new(KvStore::default());
+
+        assert_eq!(run(&mut store, "theme"), "light");
+        assert_eq!(run(&mut store, "theme"), "light");
+        assert_eq!(block_on(store.abx_len()), 1);
+
+        store.set_calls().assert_called_once();
+        store.get_calls().assert_called_times(2);
+        assert_eq!(
+            store.calls()[1].to_string(),
+            "abx_set(\"theme\", \"light\")"
+        );
+        assert_eq!(store.into_inner().get("theme").as_deref(), Some("light"));
+        assert_eq!(run(&mut KvStore::default(), "theme"), "light");
+    }
+
+    pub struct Mailer {
+        host: String,
+    }
+
+    impl Mailer {
+        pub fn new(host: &str) -> Self {
+            Mailer {
+                host: host.to_string(),
+            }
+        }
+
+        pub fn send(&self, to: &str) -> String {
+            format!("{to} via {}", self.host)
+        }
+
+        pub async fn queue(&self, to: &str) -> usize {
+            to.len()
+        }
+    }
+
+    // The real constructor returns a `Mailer`, not a spy, so the spy's panics
+    #[wrap(Mailer, spy)]
+    trait AbxMailer {
+        fn abx_new(host: &str) -> Self;
+        fn abx_send(&self, to: &str) -> String;
+    }
+
+    #[test]
+    fn it_passes_on_the_methods_it_can() {
+        let mailer = SpyMailer::new(Mailer::abx_new("smtp.test"));
+
+        assert_eq!(mailer.abx_send("ada"), "ada via smtp.test");
+        mailer.send_calls().assert_called_once();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "`SpyMailer` can't pass `abx_new` on to the real `Mailer`, since its signature names `Self`"
+    )]
+    fn it_panics_in_methods_naming_self() {
+        SpyMailer::abx_new("smtp.test");
+    }
+
+    // The spy of a trait that has to be `Send + Sync`, or whose futures are `Send`, records its calls behind a `Mutex`
+    #[wrap(Mailer, spy)]
+    trait AbxSharedMailer: Send + Sync {
+        fn abx_send(&self, to: &str) -> String;
+    }
+
+    #[wrap(Mailer, spy)]
+    trait AbxMailQueue {
+        #[abx(send)]
+        async fn abx_queue(&self, to: &str) -> usize;
+    }
+
+    #[test]
+    fn it_is_sync_when_the_trait_needs_it() {
+        let mailer = SpySharedMailer::new(Mailer::new("smtp.test"));
+
+        let sent = std::thread::scope(|scope| {
+            let ada = scope.spawn(|| mailer.abx_send("ada"));
+            let alan = scope.spawn(|| mailer.abx_send("alan"));
+            [ada.join().unwrap(), alan.join().unwrap()]
+        });
+
+        assert_eq!(sent, ["ada via smtp.test", "alan via smtp.test"]);
+        mailer.send_calls().assert_called_times(2);
+        let queue = SpyMailQueue::new(Mailer::new("smtp.test"));
+        let queued = queue.abx_queue("grace");
+        assert_eq!(
+            std::thread::scope(|scope| scope.spawn(|| block_on(queued)).join().unwrap()),
+            5
+        );
+        queue.queue_calls().assert_called_once();
+    }
+}
+
+mod partial_fakes {
+    use super::wrap;
+    use futures::executor::block_on;
+
+    pub struct System {
+        pub host: String,
+    }
+
+    impl System {
+        pub fn now_unix(&self) -> u64 {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs()
+        }
+
+        pub fn hostname(&self) -> String {
+            self.host.clone()
+        }
+
+        pub fn rename(&mut self, host: &str) {
+            self.host = host.to_string();
+        }
+
+        pub async fn uptime(&self) -> u64 {
+            60
+        }
+    }
+
+    #[wrap(System, fake)]
+    trait AbxSystem {
+        fn abx_now_unix(&self) -> u64;
+        fn abx_hostname(&self) -> String;
+        fn abx_rename(&mut self, host: &str);
+        async fn abx_uptime(&self) -> u64;
+    }
+
+    fn run<S: AbxSystem>(system: &mut S) -> String {
+        system.abx_rename("box");
+        let uptime = block_on(system.abx_uptime());
+        format!(
+            "{}-{}-{uptime}",
+            system.abx_hostname(),
+            system.abx_now_unix()
+        )
+    }
+
+    fn system() -> System {
+        System {
+            host: "localhost".to_string(),
+        }
+    }
+
+    #[test]
+    fn it_passes_unanswered_calls_on_to_the_real_instance() {
+        let mut system = FakeSystem::with_real(self::system());
+        system.returns_for_now_unix([1_700_000_000]);
+
+        assert_eq!(run(&mut system), "box-1700000000-60");
+        system.hostname_calls().assert_called_once();
+        system.rename_calls().assert_called_once();
+        assert!(run(&mut self::system()).starts_with("box-"));
+    }
+
+    #[test]
+    #[should_panic(expected = "`abx_now_unix` was called with nothing queued for it to return")]
+    fn it_still_panics_without_a_real_instance() {
+        FakeSystem::new().abx_now_unix();
+    }
+}
+
+mod resettable_fakes {
+    use super::wrap;
+    use depabx::matchers::eq;
+
+    pub struct FeatureFlags;
+
+    impl FeatureFlags {
+        pub fn is_enabled(&self, flag: &str) -> bool {
+            flag == "new-checkout"
+        }
+    }
+
+    #[wrap(FeatureFlags, fake)]
+    trait AbxFeatureFlags {
+        fn abx_is_enabled(&self, flag: &str) -> bool;
+    }
+
+    fn run<F: AbxFeatureFlags>(flags: &F) -> &'static str {
+        match flags.abx_is_enabled("new-checkout") {
+            true => "v2",
+            false => "v1",
+        }
+    }
+
+    #[test]
+    fn it_forgets_calls_and_answers() {
+        let flags = FakeFeatureFlags::new();
+
+        for (enabled, expected) in [(true, "v2"), (false, "v1")] {
+            flags.reset();
+            flags.returns_for_is_enabled([enabled, enabled]);
+            flags.returns_for_is_enabled_when(eq("new-checkout"), [!enabled]);
+
+            assert_eq!(run(&flags), expected);
+            flags.is_enabled_calls().assert_called_once();
+        }
+        assert_eq!(run(&FeatureFlags), "v2");
+    }
+
+    #[test]
+    #[should_panic(expected = "nothing queued")]
+    fn it_forgets_answers_queued_before() {
+        let flags = FakeFeatureFlags::new();
+        flags.returns_for_is_enabled([true]);
+
+        flags.reset();
+        run(&flags);
+    }
+}
+
+mod expectations {
+    use super::wrap;
+    use depabx::matchers::{any, eq};
+
+    pub struct Mailer;
+
+    impl Mailer {
+        pub fn send_template(&self, _to: &str, _template: &str) {}
+    }
+
+    #[wrap(Mailer, fake)]
+    trait AbxMailer {
+        fn abx_send_template(&self, to: &str, template: &str);
+    }
+
+    fn run<M: AbxMailer>(mailer: &M, users: &[&str]) {
+        for user in users {
+            mailer.abx_send_template(user, "welcome");
+        }
+    }
+
+    #[test]
+    fn it_checks_expectations_at_checkpoints_and_on_drop() {
+        let mailer = FakeMailer::new();
+        mailer
+            .expect_send_template()
+            .with((any(), eq("welcome")))
+            .times(2);
+        mailer
+            .expect_send_template()
+            .with((eq("c"), any()))
+            .times(0);
+
+        run(&mailer, &["a", "b"]);
+        mailer.checkpoint();
+
+        mailer.expect_send_template();
+        run(&mailer, &["c"]);
+        run(&Mailer, &["d"]);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "expected `abx_send_template` to be called twice with matching arguments, but it was called once: \
+        [abx_send_template(\"a\", \"welcome\")]"
+    )]
+    fn it_panics_at_a_checkpoint_with_unmet_expectations() {
+        let mailer = FakeMailer::new();
+        run(&mailer, &["z"]);
+        mailer
+            .expect_send_template()
+            .with((any(), eq("welcome")))
+            .times(2);
+
+        run(&mailer, &["a"]);
+        mailer.checkpoint();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "expected `abx_send_template` to be called at least once, but it was called 0 times: []"
+    )]
+    fn it_panics_on_drop_with_unmet_expectations() {
+        let mailer = FakeMailer::new();
+        mailer.expect_send_template();
+    }
+}
+
+mod generic_method_fakes {
+    use super::wrap;
+    use std::fmt::{Debug, Display};
+
+    pub struct Logger;
+
+    impl Logger {
+        pub fn log_event<T: Display>(&self, _description: T) {}
+
+        pub fn log_value<K, V>(&self, _key: &K, _value: V)
+        where
+            K: Display + ?Sized,
+            V: Debug,
+        {
+        }
+
+        pub fn log_any<T>(&self, _value: T) {}
+
+        pub fn log_impl(&self, _value: impl Display) {}
+    }
+
+    #[wrap(Logger, fake, stub(strict))]
+    trait AbxLogger {
+        fn abx_log_event<T: Display>(&self, description: T);
+        fn abx_log_value<K, V>(&self, key: &K, value: V)
+        where
+            K: Display + ?Sized,
+            V: Debug;
+        fn abx_log_any<T>(&self, value: T);
+        fn abx_log_impl(&self, value: impl Display);
+    }
+
+    fn run<L: AbxLogger>(logger: &L) {
+        logger.abx_log_event("started");
+        logger.abx_log_event(404);
+        logger.abx_log_value("retries", Some(3));
+        logger.abx_log_any(1u8);
+        logger.abx_log_impl('x');
+    }
+
+    #[test]
+    fn it_records_generic_arguments_through_their_bounds() {
+        let logger = FakeLogger::new();
+
+        run(&logger);
+        run(&Logger);
+
+        let calls: Vec<_> = logger.calls().iter().map(ToString::to_string).collect();
+        assert_eq!(
+            calls,
+            [
+                "abx_log_event(started)",
+                "abx_log_event(404)",
+                "abx_log_value(retries, Some(3))",
+                "abx_log_any(u8)",
+                "abx_log_impl(x)",
+            ]
+        );
+        assert_eq!(logger.log_value_calls().len(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "unexpected call to AbxLogger::abx_log_value(retries, None)")]
+    fn it_shows_generic_arguments_of_unexpected_calls() {
+        StubLogger::new().abx_log_value("retries", None::<u8>);
+    }
+}
+
+mod default_fakes {
+    use super::wrap;
+
+    pub struct Billing;
+    pub struct Notifications;
+
+    impl Billing {
+        pub fn invoice(&self, customer: &str, cents: u64) -> String {
+            format!("inv-{customer}-{cents}")
+        }
+    }
+
+    impl Notifications {
+        pub fn notify(&self, _customer: &str, _message: &str) {}
+    }
+
+    #[wrap(Billing, fake)]
+    trait AbxBilling {
+        fn abx_invoice(&self, customer: &str, cents: u64) -> String;
+    }
+
+    #[wrap(Notifications, fake)]
+    trait AbxNotifications {
+        fn abx_notify(&self, customer: &str, message: &str);
+    }
+
+    fn run<B: AbxBilling, N: AbxNotifications>(billing: &B, notifications: &N) -> String {
+        let invoice = billing.abx_invoice("acme", 100);
+        notifications.abx_notify("acme", &invoice);
+        invoice
+    }
+
+    #[derive(Default)]
+    struct Fakes {
+        billing: FakeBilling,
+        notifications: FakeNotifications,
+    }
+
+    #[test]
+    fn it_builds_fixtures_with_default() {
+        let fakes = Fakes::default();
+        fakes.billing.returns_for_invoice(["inv-1".to_string()]);
+
+        assert_eq!(run(&fakes.billing, &fakes.notifications), "inv-1");
+        fakes.notifications.notify_calls().assert_called_once();
+        assert_eq!(run(&Billing, &Notifications), "inv-acme-100");
+    }
+}
+
+mod associated_type_fakes {
+    use super::wrap;
+    use std::fmt::Display;
+
+    pub struct Document {
+        pub nodes: Vec<String>,
+    }
+
+    impl Display for Document {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{}", self.nodes.join(" > "))
+        }
+    }
+
+    pub struct Parser;
+
+    impl Parser {
+        pub fn parse(&self, source: &str) -> Document {
+            Document {
+                nodes: source.split('/').map(String::from).collect(),
+            }
+        }
+
+        pub fn root(&self, document: &Document) -> Option<String> {
+            document.nodes.first().cloned()
+        }
+    }
+
+    #[wrap(Parser, fake(type Output = String))]
+    #[abx(type Output = Document)]
+    trait AbxParser {
+        type Output: Display;
+        fn abx_parse(&self, source: &str) -> Self::Output;
+        fn abx_root(&self, document: &Self::Output) -> Option<String>;
+    }
+
+    fn run<P: AbxParser>(parser: &P, source: &str) -> String {
+        let document = parser.abx_parse(source);
+        format!("{document} from {:?}", parser.abx_root(&document))
+    }
+
+    #[test]
+    fn it_uses_the_fakes_own_binding() {
+        let parser = FakeParser::new();
+        parser.returns_for_parse(["root".to_string()]);
+        parser.returns_for_root([None]);
+
+        assert_eq!(run(&parser, "html"), "root from None");
+        assert_eq!(parser.root_calls()[0].args, ["\"root\""]);
+        assert_eq!(run(&Parser, "html/body"), "html > body from Some(\"html\")");
+    }
+}
+
+mod inline_fakes {
+    use super::wrap;
+    use std::cell::RefCell;
+
+    pub struct Inventory;
+
+    impl Inventory {
+        pub fn stock(&self, sku: &str) -> u32 {
+            sku.len() as u32
+        }
+
+        pub fn reserve(&self, sku: &str, quantity: u32) -> bool {
+            quantity <= self.stock(sku)
+        }
+    }
+
+    #[wrap(Inventory, stub)]
+    trait AbxInventory {
+        fn abx_stock(&self, sku: &str) -> u32;
+        fn abx_reserve(&self, sku: &str, quantity: u32) -> bool;
+    }
+
+    fn run<I: AbxInventory>(inventory: &I, sku: &str) -> bool {
+        inventory.abx_stock(sku) > 0 && inventory.abx_reserve(sku, 1)
+    }
+
+    #[test]
+    fn it_answers_with_the_closures_given() {
+        let reserved = RefCell::new(Vec::new());
+        let inventory = depabx::fake!(AbxInventory {
+            abx_stock: |_| 1,
+            abx_reserve: |sku, quantity| {
+                reserved.borrow_mut().push((sku.to_string(), quantity));
+                true
+            },
+        });
+
+        assert!(run(&inventory, "gadget"));
+        assert_eq!(*reserved.borrow(), [("gadget".to_string(), 1)]);
+        assert!(run(&Inventory, "widget"));
+    }
+
+    #[test]
+    #[should_panic(expected = "configure it with `on_reserve`")]
+    fn it_leaves_the_rest_to_the_stub() {
+        let inventory = depabx::fake!(AbxInventory {
+            abx_stock: |_| 1,
+            ..
+        });
+
+        run(&inventory, "gadget");
+    }
+}
+
+mod failure_injection {
+    use super::wrap;
+    use std::io;
+
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum StorageError {
+        Timeout,
+        NotFound,
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub struct CacheError(StorageError);
+
+    impl From<StorageError> for CacheError {
+        fn from(error: StorageError) -> Self {
+            CacheError(error)
+        }
+    }
+
+    pub struct Storage;
+
+    impl Storage {
+        pub fn read(&self, key: &str) -> Result<Vec<u8>, StorageError> {
+            match key {
+                "" => Err(StorageError::NotFound),
+                _ => Ok(key.as_bytes().to_vec()),
+            }
+        }
+
+        pub fn cached(&self, key: &str) -> Result<bool, CacheError> {
+            Ok(!key.is_empty())
+        }
+
+        pub fn len(&self) -> usize {
+            1
+        }
+    }
+
+    #[wrap(Storage, fake)]
+    trait AbxStorage {
+        fn abx_read(&self, key: &str) -> Result<Vec<u8>, StorageError>;
+        fn abx_cached(&self, key: &str) -> Result<bool, CacheError>;
+        fn abx_len(&self) -> usize;
+    }
+
+    #[test]
+    fn it_fails_every_result_ahead_of_whats_queued() {
+        let storage = FakeStorage::new();
+        storage.returns_for_read([Ok(vec![1])]);
+        storage.returns_for_len([3]);
+        storage.fail_all_with(StorageError::Timeout);
+
+        assert_eq!(storage.abx_read("config"), Err(StorageError::Timeout));
+        assert_eq!(storage.abx_read("config"), Err(StorageError::Timeout));
+        assert_eq!(
+            storage.abx_cached("config"),
+            Err(CacheError(StorageError::Timeout))
+        );
+        assert_eq!(storage.abx_len(), 3);
+        assert_eq!(Storage.abx_read(""), Err(StorageError::NotFound));
+        assert_eq!(Storage.abx_cached("config"), Ok(true));
+        assert_eq!(Storage.abx_len(), 1);
+    }
+
+    #[test]
+    fn it_stops_failing_once_reset() {
+        let storage = FakeStorage::new();
+        storage.fail_all_with(StorageError::Timeout);
+        storage.reset();
+        storage.returns_for_read([Ok(vec![1])]);
+
+        assert_eq!(storage.abx_read("config"), Ok(vec![1]));
+    }
+
+    pub struct Disk;
+
+    impl Disk {
+        pub fn read(&self, path: &str) -> io::Result<String> {
+            Ok(path.to_string())
+        }
+
+        pub fn size(&self) -> io::Result<u64> {
+            Ok(1)
+        }
+    }
+
+    #[wrap(Disk, fake)]
+    trait AbxDisk {
+        fn abx_read(&self, path: &str) -> io::Result<String>;
+        fn abx_size(&self) -> std::io::Result<u64>;
+    }
+
+    // A module's alias of `Result` fails with that module's `Error`
+    #[test]
+    fn it_fails_results_of_an_alias() {
+        let disk = FakeDisk::new();
+        disk.fail_all_with(io::ErrorKind::TimedOut);
+
+        assert_eq!(
+            disk.abx_read("a.txt").unwrap_err().kind(),
+            io::ErrorKind::TimedOut
+        );
+        assert_eq!(disk.abx_size().unwrap_err().kind(), io::ErrorKind::TimedOut);
+        assert_eq!(Disk.abx_read("a.txt").unwrap(), "a.txt");
+        assert_eq!(Disk.abx_size().unwrap(), 1);
+    }
+}
+
+mod latency {
+    use super::wrap;
+    use std::time::{Duration, Instant};
+
+    pub struct PriceFeed;
+
+    impl PriceFeed {
+        pub async fn quote(&self, symbol: &str) -> f64 {
+            symbol.len() as f64
+        }
+    }
+
+    #[wrap(PriceFeed, fake)]
+    trait AbxPriceFeed {
+        async fn abx_quote(&self, symbol: &str) -> f64;
+    }
+
+    // Falls back to the last known price if the feed takes longer than 100ms
+    async fn run<F: AbxPriceFeed>(feed: &F) -> f64 {
+        tokio::time::timeout(Duration::from_millis(100), feed.abx_quote("ACME"))
+            .await
+            .unwrap_or(10.0)
+    }
+
+    #[tokio::test]
+    async fn it_waits_before_answering() {
+        let feed = FakePriceFeed::new().with_latency(Duration::from_millis(20));
+        feed.returns_for_quote([12.5, 13.0]);
+
+        let start = Instant::now();
+        assert_eq!(run(&feed).await, 12.5);
+        assert!(start.elapsed() >= Duration::from_millis(20));
+        let feed = feed.with_latency(Duration::from_millis(500));
+        assert_eq!(run(&feed).await, 10.0);
+        assert_eq!(run(&PriceFeed).await, 4.0);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test(start_paused = true)]
+    async fn it_waits_on_paused_time() {
+        let feed = FakePriceFeed::new().with_latency(Duration::from_secs(60));
+        feed.returns_for_quote([12.5]);
+
+        let start = Instant::now();
+        assert_eq!(run(&feed).await, 10.0);
+        assert!(start.elapsed() < Duration::from_secs(60));
+    }
+}
+
+mod in_memory_stores {
+    use super::wrap;
+    use futures::executor::block_on;
+
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Session {
+        pub user: String,
+    }
+
+    pub struct SessionStore;
+
+    impl SessionStore {
+        pub fn get(&self, _id: &str) -> Option<Session> {
+            None
+        }
+
+        pub fn put(&self, _id: &str, _session: Session) {}
+
+        pub fn expire(&self, _id: &str) -> bool {
+            false
+        }
+
+        pub async fn remove_all(&self, _user: String) -> Result<Option<Session>, String> {
+            Err("unsupported".to_string())
+        }
+    }
+
+    #[wrap(SessionStore, store)]
+    trait AbxSessionStore {
+        fn abx_get(&self, id: &str) -> Option<Session>;
+        fn abx_put(&self, id: &str, session: Session);
+        #[abx(store = "delete")]
+        fn abx_expire(&self, id: &str) -> bool;
+        async fn abx_remove_all(&self, user: String) -> Result<Option<Session>, String>;
+    }
+
+    fn session(user: &str) -> Session {
+        Session {
+            user: user.to_string(),
+        }
+    }
+
+    #[test]
+    fn it_reads_back_what_was_put() {
+        let store = InMemorySessionStore::new();
+        store.abx_put("s-1", session("ada"));
+
+        assert_eq!(store.abx_get("s-1"), Some(session("ada")));
+        assert_eq!(store.abx_get("s-2"), None);
+        assert!(store.contains("s-1"));
+        assert!(store.abx_expire("s-1"));
+        assert!(!store.abx_expire("s-1"));
+        assert_eq!(store.abx_get("s-1"), None);
+        assert_eq!(SessionStore.abx_get("s-1"), None);
+        SessionStore.abx_put("s-1", session("ada"));
+        assert!(!SessionStore.abx_expire("s-1"));
+    }
+
+    #[test]
+    fn it_starts_with_the_entries_given() {
+        let store = InMemorySessionStore::with_entries([("s-1".to_string(), session("ada"))]);
+
+        assert_eq!(
+            block_on(store.abx_remove_all("s-1".to_string())),
+            Ok(Some(session("ada")))
+        );
+        assert!(!store.contains("s-1"));
+        assert!(block_on(SessionStore.abx_remove_all("s-1".to_string())).is_err());
+    }
+}
+
+mod shared_fakes {
+    use super::wrap;
+
+    pub struct Metrics;
+
+    impl Metrics {
+        pub fn increment(&self, counter: &str) -> u64 {
+            counter.len() as u64
+        }
+    }
+
+    #[wrap(Metrics, fake(clone))]
+    trait AbxMetrics {
+        fn abx_increment(&self, counter: &str) -> u64;
+    }
+
+    #[test]
+    fn its_clones_share_the_calls_and_answers() {
+        let metrics = FakeMetrics::new();
+        let api = metrics.clone();
+        let db = metrics.clone();
+        metrics.returns_for_increment([1, 2]);
+
+        assert_eq!(api.abx_increment("api.requests"), 1);
+        assert_eq!(db.abx_increment("db.queries"), 2);
+        let counters: Vec<_> = metrics.calls().into_iter().map(|call| call.args).collect();
+        assert_eq!(counters, [["\"api.requests\""], ["\"db.queries\""]]);
+        assert_eq!(Metrics.abx_increment("db.queries"), 10);
+    }
+}
+
+mod argument_capture {
+    use super::wrap;
+
+    #[derive(Debug)]
+    pub struct Attachment {
+        pub name: String,
+        pub handle: u32,
+    }
+
+    pub struct Mailer;
+
+    impl Mailer {
+        pub fn send(&self, to: &str, attachment: Attachment) -> usize {
+            to.len() + attachment.name.len() + attachment.handle as usize
+        }
+
+        pub fn bounce(&self, to: String) -> bool {
+            to.is_empty()
+        }
+    }
+
+    #[wrap(Mailer, fake)]
+    trait AbxMailer {
+        fn abx_send(&self, to: &str, #[abx(capture = "debug")] attachment: Attachment) -> usize;
+        fn abx_bounce(&self, to: String) -> bool;
+    }
+
+    fn attachment(handle: u32) -> Attachment {
+        Attachment {
+            name: "report.pdf".to_string(),
+            handle,
+        }
+    }
+
+    #[test]
+    fn it_captures_each_calls_arguments() {
+        let mailer = FakeMailer::new();
+        mailer.returns_for_send([1, 2]);
+        mailer.returns_for_bounce([false]);
+
+        mailer.abx_send("ada@example.com", attachment(3));
+        mailer.abx_send("alan@example.com", attachment(4));
+        mailer.abx_bounce("ada@example.com".to_string());
+
+        let sent = mailer.captured_send();
+        assert_eq!(sent[1].0, "alan@example.com");
+        assert_eq!(sent[1].1, format!("{:?}", attachment(4)));
+        assert_eq!(mailer.captured_bounce(), ["ada@example.com"]);
+        assert_eq!(Mailer.abx_send("ada", attachment(1)), 14);
+        assert!(!Mailer.abx_bounce("ada".to_string()));
+    }
+}
+
+mod panic_messages {
+    use super::wrap;
+    use std::panic::{self, AssertUnwindSafe};
+
+    pub struct Payments;
+
+    impl Payments {
+        pub fn authorize(&self, card: &str, cents: u64) -> bool {
+            !card.is_empty() && cents < 100_000
+        }
+
+        pub fn capture(&self, _card: &str, _cents: u64) {}
+    }
+
+    #[wrap(Payments, stub(strict))]
+    trait AbxPayments {
+        fn abx_authorize(&self, card: &str, cents: u64) -> bool;
+        fn abx_capture(&self, card: &str, cents: u64);
+    }
+
+    fn run<P: AbxPayments>(payments: &P, card: &str, cents: u64) -> bool {
+        let authorized = payments.abx_authorize(card, cents);
+        if authorized {
+            payments.abx_capture(card, cents);
+        }
+        authorized
+    }
+
+    // The message a call panics with
+    fn message(call: impl FnOnce()) -> String {
+        let panic = panic::catch_unwind(AssertUnwindSafe(call)).unwrap_err();
+        panic.downcast::<String>().map(|message| *message).unwrap()
+    }
+
+    #[test]
+    fn it_shows_the_call_where_it_was_made_and_whats_configured() {
+        let payments = StubPayments::new().on_authorize(|_, _| true);
+
+        let message = message(|| {
+            run(&payments, "4242", 500);
+        });
+        let (call, configured) = message.split_once('\n').unwrap();
+        let location = format!(" at {}:", file!());
+        assert!(call.starts_with("unexpected call to AbxPayments::abx_capture(\"4242\", 500)"));
+        assert!(call.contains(&location), "{call}");
+        assert_eq!(configured, "configured: abx_authorize");
+        assert!(run(&Payments, "4242", 500));
+    }
+
+    #[test]
+    fn it_says_when_nothing_was_configured() {
+        let message = message(|| {
+            StubPayments::new().abx_capture("4242", 500);
+        });
+
+        assert!(message.ends_with("configured: nothing"), "{message}");
+    }
+}
+
+mod debug_output {
+    use super::wrap;
+
+    pub struct Cache;
+
+    impl Cache {
+        pub fn get(&self, key: &str) -> Option<String> {
+            (key == "greeting").then(|| "hello".to_string())
+        }
+
+        pub fn set(&self, _key: &str, _value: &str) {}
+    }
+
+    #[wrap(Cache, fake)]
+    trait AbxCache {
+        fn abx_get(&self, key: &str) -> Option<String>;
+        fn abx_set(&self, key: &str, value: &str);
+    }
+
+    #[test]
+    fn it_shows_the_calls_and_whats_queued() {
+        let cache = FakeCache::new();
+        cache.returns_for_get([None, Some("hi".to_string())]);
+        cache.returns_for_get_when(depabx::matchers::eq("x"), [None]);
+
+        cache.abx_get("greeting");
+        cache.abx_set("greeting", "hi");
+
+        assert_eq!(
+            format!("{cache:?}"),
+            "FakeCache { calls: [abx_get(\"greeting\"), abx_set(\"greeting\", \"hi\")], queued: {abx_get: 2} }"
+        );
+        assert_eq!(Cache.abx_get("greeting"), Some("hello".to_string()));
+        Cache.abx_set("greeting", "hi");
+    }
+}
+
+mod conditional_returns {
+    use super::wrap;
+    use depabx::matchers::{any, eq, predicate};
+
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum UserError {
+        NotFound,
+    }
+
+    pub struct Users;
+
+    impl Users {
+        pub fn get(&self, id: &str) -> Result<String, UserError> {
+            match id {
+                "root" => Ok("Root".to_string()),
+                _ => Err(UserError::NotFound),
+            }
+        }
+    }
+
+    #[wrap(Users, fake)]
+    trait AbxUsers {
+        fn abx_get(&self, id: &str) -> Result<String, UserError>;
+    }
+
+    #[wrap(Users, fake(sync))]
+    trait AbxSharedUsers: Send + Sync {
+        fn abx_get(&self, id: &str) -> Result<String, UserError>;
+    }
+
+    #[test]
+    fn it_answers_matching_calls_for_as_long_as_it_lives() {
+        let users = FakeUsers::new();
+        users.when_get(eq("user-1")).returns(Ok("Ada".to_string()));
+        users.when_get(any()).returns(Err(UserError::NotFound));
+        users.returns_for_get([Ok("Alan".to_string())]);
+
+        assert_eq!(users.abx_get("user-1"), Ok("Alan".to_string()));
+        assert_eq!(users.abx_get("user-1"), Ok("Ada".to_string()));
+        assert_eq!(users.abx_get("user-2"), Err(UserError::NotFound));
+        assert_eq!(users.abx_get("user-1"), Ok("Ada".to_string()));
+        assert_eq!(AbxUsers::abx_get(&Users, "root"), Ok("Root".to_string()));
+    }
+
+    #[test]
+    fn its_sync_fakes_take_send_matchers() {
+        let users = FakeSharedUsers::new();
+        users
+            .when_get(predicate(|id: &String| id.starts_with("admin-")))
+            .returns(Ok("Root".to_string()));
+
+        assert_eq!(users.abx_get("admin-1"), Ok("Root".to_string()));
+        assert_eq!(
+            AbxSharedUsers::abx_get(&Users, "user-1"),
+            Err(UserError::NotFound)
+        );
+    }
+}
+
+mod null_objects {
+    use super::wrap;
+    use futures::executor::block_on;
+
+    pub struct Tracer;
+
+    impl Tracer {
+        pub fn start_span(&self, name: &str) -> u64 {
+            name.len() as u64
+        }
+
+        pub fn end_span(&self, _id: u64) {}
+
+        pub async fn flush(&self) -> Option<usize> {
+            Some(1)
+        }
+    }
+
+    #[wrap(Tracer, noop)]
+    trait AbxTracer {
+        fn abx_start_span(&self, name: &str) -> u64;
+        fn abx_end_span(&self, id: u64);
+        async fn abx_flush(&self) -> Option<usize>;
+    }
+
+    fn run<T: AbxTracer>(tracer: &T) -> Option<usize> {
+        let span = tracer.abx_start_span("sum");
+        tracer.abx_end_span(span);
+        block_on(tracer.abx_flush()).map(|flushed| flushed + span as usize)
+    }
+
+    #[test]
+    fn it_does_nothing_and_returns_defaults() {
+        assert_eq!(run(&NoopTracer), None);
+        assert_eq!(run(&Tracer), Some(4));
+    }
+
+    pub struct SpanId(pub u64);
+
+    pub struct Exporter;
+
+    impl Exporter {
+        pub fn export(&self, _span: u64) {}
+
+        pub fn endpoint(&self) -> &str {
+            "localhost:4317"
+        }
+
+        pub fn open(&self) -> SpanId {
+            SpanId(1)
+        }
+    }
+
+    // Methods returning something `Default::default()` can't stand for panic instead
+    #[wrap(Exporter, noop)]
+    trait AbxExporter {
+        fn abx_export(&self, span: u64);
+        fn abx_endpoint(&self) -> &str;
+        #[abx(no_default)]
+        fn abx_open(&self) -> SpanId;
+    }
+
+    #[test]
+    #[should_panic(expected = "`NoopExporter` can't return anything from `abx_endpoint`")]
+    fn it_panics_on_a_borrowed_return() {
+        NoopExporter.abx_export(1);
+        NoopExporter.abx_endpoint();
+    }
+
+    #[test]
+    #[should_panic(expected = "`NoopExporter` can't return anything from `abx_open`")]
+    fn it_panics_on_a_return_without_a_default() {
+        assert_eq!(Exporter.abx_endpoint(), "localhost:4317");
+        assert_eq!(Exporter.abx_open().0, 1);
+        NoopExporter.abx_open();
+    }
+}
+
+mod test_only_fakes {
+    use super::wrap;
+
+    pub struct JobQueue;
+
+    impl JobQueue {
+        pub fn enqueue(&self, job: &str) -> u64 {
+            job.len() as u64
+        }
+    }
+
+    #[wrap(JobQueue, fake(test_only))]
+    trait AbxJobQueue {
+        fn abx_enqueue(&self, job: &str) -> u64;
+    }
+
+    #[test]
+    fn it_is_compiled_for_tests() {
+        let queue = FakeJobQueue::new();
+        queue.returns_for_enqueue([7]);
+
+        assert_eq!(queue.abx_enqueue("resize"), 7);
+        assert_eq!(JobQueue.abx_enqueue("resize"), 6);
+    }
+
+    pub struct Scheduler;
+
+    impl Scheduler {
+        pub fn schedule(&self, job: &str) -> u64 {
+            job.len() as u64
+        }
+    }
+
+    // Gated on a feature this crate names itself
+    #[wrap(Scheduler, fake(test_only = "test-doubles"))]
+    trait AbxScheduler {
+        fn abx_schedule(&self, job: &str) -> u64;
+    }
+
+    #[test]
+    fn it_is_compiled_for_tests_with_a_named_feature() {
+        let scheduler = FakeScheduler::new();
+        scheduler.returns_for_schedule([7]);
+
+        assert_eq!(scheduler.abx_schedule("resize"), 7);
+        assert_eq!(Scheduler.abx_schedule("resize"), 6);
+    }
+}
+
+mod arbitrary_fakes {
+    use super::wrap;
+    use futures::executor::block_on;
+    use proptest::prelude::*;
+
+    pub struct Pricing;
+
+    impl Pricing {
+        pub fn discount_percent(&self, sku: &str) -> u8 {
+            if sku.starts_with("sale-") {
+                30
+            } else {
+                0
+            }
+        }
+
+        pub async fn currency(&self) -> (char, bool) {
+            ('$', true)
+        }
+
+        pub fn refresh(&self) {}
+    }
+
+    #[wrap(Pricing, fake(arbitrary))]
+    trait AbxPricing {
+        fn abx_discount_percent(&self, sku: &str) -> u8;
+        async fn abx_currency(&self) -> (char, bool);
+        fn abx_refresh(&self);
+    }
+
+    fn run<P: AbxPricing>(pricing: &P, cents: u32) -> u32 {
+        pricing.abx_refresh();
+        let percent = u64::from(pricing.abx_discount_percent("sale-shoes").min(100));
+        cents - (u64::from(cents) * percent / 100) as u32
+    }
+
+    proptest! {
+        #[test]
+        fn it_answers_each_case_with_arbitrary_values(
+            pricing in any::<ArbitraryPricing>(),
+            cents in any::<u32>(),
+        ) {
+            prop_assert!(run(&pricing, cents) <= cents);
+        }
+    }
+
+    #[test]
+    fn it_answers_the_same_for_the_same_seed() {
+        let answers = |pricing: ArbitraryPricing| {
+            let percents: Vec<_> = (0..8)
+                .map(|_| pricing.abx_discount_percent("shoes"))
+                .collect();
+            (percents, block_on(pricing.abx_currency()))
+        };
+
+        assert_eq!(
+            answers(ArbitraryPricing::from_seed(7)),
+            answers(ArbitraryPricing::from_seed(7))
+        );
+        assert_ne!(
+            answers(ArbitraryPricing::from_seed(7)),
+            answers(ArbitraryPricing::from_seed(8))
+        );
+        assert_eq!(
+            format!("{:?}", ArbitraryPricing::from_seed(7)),
+            "ArbitraryPricing { seed: 7 }"
+        );
+        assert_eq!(run(&Pricing, 100), 70);
+        assert_eq!(block_on(Pricing.abx_currency()), ('$', true));
+    }
+
+    pub struct Inventory;
+
+    impl Inventory {
+        pub fn stock(&self, _sku: &str) -> u16 {
+            4
+        }
+    }
+
+    // Gated with the fake, so it's there for the tests of crates turning the feature on too
+    #[wrap(Inventory, fake(test_only, arbitrary))]
+    trait AbxInventory {
+        fn abx_stock(&self, sku: &str) -> u16;
+    }
+
+    #[test]
+    fn it_is_gated_like_a_test_only_fake() {
+        let inventory = ArbitraryInventory::from_seed(7);
+
+        assert_eq!(
+            inventory.abx_stock("shoes"),
+            ArbitraryInventory::from_seed(7).abx_stock("shoes")
+        );
+        assert_eq!(FakeInventory::new().stock_calls().len(), 0);
+        assert_eq!(Inventory.abx_stock("shoes"), 4);
+    }
+}
+
+mod registries {
+    use super::wrap;
+    use depabx::Registry;
+
+    pub struct Cache;
+    pub struct HttpClient;
+
+    impl Cache {
+        pub fn get(&self, url: &str) -> Option<String> {
+            url.ends_with("/cached").then(|| "cached body".to_string())
+        }
+
+        pub fn set(&self, _url: &str, _body: &str) {}
+    }
+
+    impl HttpClient {
+        pub fn fetch(&self, url: &str) -> String {
+            format!("body of {url}")
+        }
+    }
+
+    #[wrap(Cache, fake)]
+    trait AbxCache {
+        fn abx_get(&self, url: &str) -> Option<String>;
+        fn abx_set(&self, url: &str, body: &str);
+    }
+
+    #[wrap(HttpClient, fake)]
+    trait AbxHttpClient {
+        fn abx_fetch(&self, url: &str) -> String;
+    }
+
+    fn run<C: AbxCache, H: AbxHttpClient>(cache: &C, http: &H, url: &str) -> String {
+        if let Some(body) = cache.abx_get(url) {
+            return body;
+        }
+        let body = http.abx_fetch(url);
+        cache.abx_set(url, &body);
+        body
+    }
+
+    #[test]
+    fn it_records_the_calls_on_every_fake_in_order() {
+        let registry = Registry::new();
+        let cache = FakeCache::with_registry(&registry);
+        let http = FakeHttpClient::with_registry(&registry);
+        cache.returns_for_get([None]);
+        http.returns_for_fetch(["[]".to_string()]);
+
+        assert_eq!(run(&cache, &http, "/users"), "[]");
+        registry.assert_order([
+            "AbxCache::abx_get",
+            "AbxHttpClient::abx_fetch",
+            "AbxCache::abx_set",
+        ]);
+        registry.assert_order(["AbxCache::abx_get", "AbxCache::abx_set"]);
+        assert_eq!(
+            registry.interactions(),
+            [
+                "AbxCache::abx_get(\"/users\")",
+                "AbxHttpClient::abx_fetch(\"/users\")",
+                "AbxCache::abx_set(\"/users\", \"[]\")",
+            ]
+        );
+        assert_eq!(cache.calls().len(), 2);
+        assert_eq!(run(&Cache, &HttpClient, "/cached"), "cached body");
+        assert_eq!(run(&Cache, &HttpClient, "/users"), "body of /users");
+    }
+
+    #[test]
+    #[should_panic(expected = "but `AbxCache::abx_get` wasn't called after the ones before it")]
+    fn it_panics_with_calls_out_of_order() {
+        let registry = Registry::new();
+        let cache = FakeCache::with_registry(&registry);
+        let http = FakeHttpClient::with_registry(&registry);
+        cache.returns_for_get([None]);
+        http.returns_for_fetch(["[]".to_string()]);
+
+        run(&cache, &http, "/users");
+        registry.assert_order(["AbxHttpClient::abx_fetch", "AbxCache::abx_get"]);
+    }
+}
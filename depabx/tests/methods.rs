@@ -0,0 +1,1030 @@
+// Delegation of the different method shapes an Abx trait can declare.
+
+use depabx::wrap;
+
+mod async_methods {
+    use super::wrap;
+    use futures::executor::block_on;
+
+    pub struct Client {
+        prefix: String,
+    }
+
+    impl Client {
+        pub async fn send(&self, body: &str) -> String {
+            format!("{}{body}", self.prefix)
+        }
+
+        pub async fn ping(&self) -> bool {
+            true
+        }
+
+        pub async fn first<T: Clone>(&self, items: &[T]) -> Option<T> {
+            items.first().cloned()
+        }
+    }
+
+    #[wrap(Client)]
+    trait AbxClient {
+        async fn abx_send(&self, body: &str) -> String;
+    }
+
+    #[wrap(Client, futures = "boxed")]
+    trait AbxPinger {
+        async fn abx_ping(&self) -> bool;
+    }
+
+    // Borrowed arguments and generics get their own lifetime, which the boxed future is bound by
+    #[wrap(Client, futures = "boxed")]
+    trait AbxPicker {
+        async fn abx_first<T: Clone>(&self, items: &[T]) -> Option<T>;
+    }
+
+    fn client() -> Client {
+        Client {
+            prefix: "> ".to_string(),
+        }
+    }
+
+    #[test]
+    fn it_awaits_the_concrete_method() {
+        assert_eq!(block_on(client().abx_send("hi")), "> hi");
+    }
+
+    #[test]
+    fn it_boxes_futures_for_trait_objects() {
+        let pinger: Box<dyn AbxPinger> = Box::new(client());
+        assert!(block_on(pinger.abx_ping()));
+    }
+
+    #[test]
+    fn it_boxes_futures_borrowing_arguments() {
+        let items = vec![1, 2];
+        assert_eq!(block_on(client().abx_first(&items)), Some(1));
+    }
+}
+
+mod mut_receivers {
+    use super::wrap;
+
+    pub struct Counter {
+        count: u32,
+    }
+
+    impl Counter {
+        pub fn increment(&mut self) -> u32 {
+            self.count += 1;
+            self.count
+        }
+
+        pub fn get(&self) -> u32 {
+            self.count
+        }
+    }
+
+    #[wrap(Counter)]
+    trait AbxCounter {
+        fn abx_increment(&mut self) -> u32;
+        fn abx_get(&self) -> u32;
+    }
+
+    #[test]
+    fn it_delegates_mutable_and_shared_receivers() {
+        let mut counter = Counter { count: 0 };
+        assert_eq!(counter.abx_increment(), 1);
+        assert_eq!(counter.abx_increment(), 2);
+        assert_eq!(counter.abx_get(), 2);
+    }
+}
+
+mod consuming_methods {
+    use super::wrap;
+
+    pub struct Builder {
+        parts: Vec<String>,
+    }
+
+    impl Builder {
+        pub fn push(&mut self, part: &str) {
+            self.parts.push(part.to_string());
+        }
+
+        pub fn finish(self) -> String {
+            self.parts.join("/")
+        }
+    }
+
+    #[wrap(Builder)]
+    trait AbxBuilder {
+        fn abx_push(&mut self, part: &str);
+        fn abx_finish(self) -> String;
+    }
+
+    fn build<B: AbxBuilder>(mut builder: B) -> String {
+        builder.abx_push("a");
+        builder.abx_push("b");
+        builder.abx_finish()
+    }
+
+    #[test]
+    fn it_moves_the_value_into_the_concrete_method() {
+        assert_eq!(build(Builder { parts: Vec::new() }), "a/b");
+    }
+}
+
+mod associated_functions {
+    use super::wrap;
+
+    pub struct Mailer {
+        host: String,
+    }
+
+    impl Mailer {
+        pub fn connect(host: &str) -> Self {
+            Mailer {
+                host: host.to_string(),
+            }
+        }
+
+        pub fn default_port() -> u16 {
+            25
+        }
+
+        pub fn host(&self) -> &str {
+            &self.host
+        }
+    }
+
+    #[wrap(Mailer)]
+    trait AbxMailer {
+        fn abx_connect(host: &str) -> Self;
+        fn abx_default_port() -> u16;
+        fn abx_host(&self) -> &str;
+    }
+
+    fn connect<M: AbxMailer>() -> M {
+        M::abx_connect("smtp.example.com")
+    }
+
+    #[test]
+    fn it_calls_associated_functions_on_the_concrete_type() {
+        let mailer: Mailer = connect();
+        assert_eq!(mailer.abx_host(), "smtp.example.com");
+        assert_eq!(<Mailer as AbxMailer>::abx_default_port(), 25);
+    }
+}
+
+mod result_errors {
+    use super::wrap;
+    use futures::executor::block_on;
+    use std::io;
+
+    #[derive(Debug, PartialEq)]
+    pub struct VendorError(u16);
+
+    #[derive(Debug, PartialEq)]
+    pub enum AppError {
+        Vendor(u16),
+    }
+
+    impl From<VendorError> for AppError {
+        fn from(e: VendorError) -> Self {
+            AppError::Vendor(e.0)
+        }
+    }
+
+    pub struct Store;
+
+    impl Store {
+        pub fn fetch(&self, id: u32) -> Result<u32, VendorError> {
+            if id == 0 {
+                Err(VendorError(404))
+            } else {
+                Ok(id)
+            }
+        }
+
+        pub async fn fetch_later(&self, id: u32) -> Result<u32, VendorError> {
+            self.fetch(id)
+        }
+
+        pub fn read(&self) -> io::Result<u32> {
+            Ok(7)
+        }
+    }
+
+    #[wrap(Store)]
+    trait AbxStore {
+        fn abx_fetch(&self, id: u32) -> Result<u32, AppError>;
+        async fn abx_fetch_later(&self, id: u32) -> Result<u32, AppError>;
+        // Identical error types convert as a no-op, and aliases of `Result` are recognized too
+        fn abx_read(&self) -> io::Result<u32>;
+    }
+
+    #[test]
+    fn it_converts_the_error_with_into() {
+        assert_eq!(Store.abx_fetch(0), Err(AppError::Vendor(404)));
+        assert_eq!(Store.abx_fetch(3), Ok(3));
+        assert_eq!(
+            block_on(Store.abx_fetch_later(0)),
+            Err(AppError::Vendor(404))
+        );
+        assert_eq!(Store.abx_read().unwrap(), 7);
+    }
+}
+
+mod borrowed_returns {
+    use super::wrap;
+
+    pub struct Profile {
+        name: String,
+        tags: Vec<String>,
+    }
+
+    impl Profile {
+        pub fn name(&self) -> &str {
+            &self.name
+        }
+
+        pub fn tag(&self, index: usize) -> Option<&str> {
+            self.tags.get(index).map(String::as_str)
+        }
+    }
+
+    #[wrap(Profile)]
+    trait AbxProfile {
+        fn abx_name(&self) -> &str;
+        #[allow(clippy::needless_lifetimes)]
+        fn abx_tag<'a>(&'a self, index: usize) -> Option<&'a str>;
+    }
+
+    #[test]
+    fn it_returns_references_borrowed_from_the_receiver() {
+        let profile = Profile {
+            name: "prod".to_string(),
+            tags: vec!["eu".to_string()],
+        };
+        assert_eq!(profile.abx_name(), "prod");
+        assert_eq!(profile.abx_tag(0), Some("eu"));
+        assert_eq!(profile.abx_tag(1), None);
+    }
+}
+
+mod generic_methods {
+    use super::wrap;
+    use std::fmt::Display;
+
+    pub struct Index;
+
+    impl Index {
+        pub fn put<K, V>(&self, key: K, value: V) -> String
+        where
+            K: AsRef<str>,
+            V: Display,
+        {
+            format!("{}={value}", key.as_ref())
+        }
+
+        pub fn first_match<'a, T: PartialEq>(&self, items: &'a [T], wanted: &T) -> Option<&'a T> {
+            items.iter().find(|item| *item == wanted)
+        }
+    }
+
+    #[wrap(Index)]
+    trait AbxIndex {
+        fn abx_put<K, V>(&self, key: K, value: V) -> String
+        where
+            K: AsRef<str>,
+            V: Display;
+        fn abx_first_match<'a, T: PartialEq>(&self, items: &'a [T], wanted: &T) -> Option<&'a T>;
+    }
+
+    #[test]
+    fn it_copies_generics_and_where_clauses() {
+        assert_eq!(Index.abx_put("retries", 3), "retries=3");
+        assert_eq!(Index.abx_first_match(&[1, 2, 3], &2), Some(&2));
+    }
+}
+
+mod impl_trait_args {
+    use super::wrap;
+    use std::fmt::Display;
+
+    pub struct Tracer;
+
+    impl Tracer {
+        pub fn span(&self, name: impl Into<String>, detail: impl Display) -> String {
+            format!("[{}] {detail}", name.into())
+        }
+    }
+
+    #[wrap(Tracer)]
+    trait AbxTracer {
+        fn abx_span(&self, name: impl Into<String>, detail: impl Display) -> String;
+    }
+
+    #[test]
+    fn it_passes_impl_trait_arguments_through() {
+        assert_eq!(Tracer.abx_span("db", 42), "[db] 42");
+        assert_eq!(
+            Tracer.abx_span(String::from("http"), "GET /"),
+            "[http] GET /"
+        );
+    }
+}
+
+mod impl_trait_returns {
+    use super::wrap;
+
+    pub struct Table;
+
+    impl Table {
+        pub fn ids(&self) -> impl Iterator<Item = u32> {
+            1..=3
+        }
+    }
+
+    #[wrap(Table)]
+    trait AbxTable {
+        fn abx_ids(&self) -> impl Iterator<Item = u32>;
+    }
+
+    #[test]
+    fn it_returns_the_concrete_opaque_type() {
+        assert_eq!(Table.abx_ids().sum::<u32>(), 6);
+    }
+}
+
+mod boxed_returns {
+    use super::wrap;
+    use std::error::Error;
+    use std::io::Read;
+
+    pub struct Bucket;
+
+    impl Bucket {
+        pub fn open(&self, key: &str) -> Box<dyn Read + Send + 'static> {
+            Box::new(std::io::Cursor::new(key.as_bytes().to_vec()))
+        }
+
+        pub fn size(&self, key: &str) -> Result<u64, Box<dyn Error + Send + Sync>> {
+            if key.is_empty() {
+                return Err("empty key".into());
+            }
+            Ok(key.len() as u64)
+        }
+    }
+
+    #[wrap(Bucket)]
+    trait AbxBucket {
+        fn abx_open(&self, key: &str) -> Box<dyn Read + Send + 'static>;
+        fn abx_size(&self, key: &str) -> Result<u64, Box<dyn Error + Send + Sync>>;
+    }
+
+    #[test]
+    fn it_returns_trait_objects_unchanged() {
+        let mut contents = String::new();
+        Bucket
+            .abx_open("abc")
+            .read_to_string(&mut contents)
+            .unwrap();
+        assert_eq!(contents, "abc");
+        assert_eq!(Bucket.abx_size("abcd").unwrap(), 4);
+        assert_eq!(Bucket.abx_size("").unwrap_err().to_string(), "empty key");
+    }
+}
+
+mod closure_params {
+    use super::wrap;
+
+    pub struct Jobs;
+
+    impl Jobs {
+        pub fn for_each(&self, mut f: impl FnMut(u32)) {
+            (1..=3).for_each(&mut f);
+        }
+
+        pub fn retry<F: Fn() -> bool>(&self, attempts: u32, f: F) -> bool {
+            (0..attempts).any(|_| f())
+        }
+
+        pub fn finish<F>(&self, f: F) -> String
+        where
+            F: FnOnce(u32) -> String,
+        {
+            f(2)
+        }
+    }
+
+    #[wrap(Jobs)]
+    trait AbxJobs {
+        fn abx_for_each(&self, f: impl FnMut(u32));
+        fn abx_retry<F: Fn() -> bool>(&self, attempts: u32, f: F) -> bool;
+        fn abx_finish<F>(&self, f: F) -> String
+        where
+            F: FnOnce(u32) -> String;
+    }
+
+    #[test]
+    fn it_forwards_every_kind_of_closure() {
+        let mut total = 0;
+        Jobs.abx_for_each(|n| total += n);
+        assert_eq!(total, 6);
+        assert!(!Jobs.abx_retry(3, || false));
+        let name = String::from("done");
+        assert_eq!(
+            Jobs.abx_finish(move |n| format!("{name} after {n}")),
+            "done after 2"
+        );
+    }
+}
+
+mod iterator_returns {
+    use super::wrap;
+
+    pub struct Scanner {
+        keys: Vec<String>,
+    }
+
+    impl Scanner {
+        pub fn scan(&self) -> std::vec::IntoIter<String> {
+            self.keys.clone().into_iter()
+        }
+
+        pub fn lengths(&self) -> impl Iterator<Item = usize> + '_ {
+            self.keys.iter().map(String::len)
+        }
+    }
+
+    #[wrap(Scanner)]
+    trait AbxScanner {
+        fn abx_scan(&self) -> std::vec::IntoIter<String>;
+        #[abx(boxed)]
+        fn abx_lengths(&self) -> Box<dyn Iterator<Item = usize> + '_>;
+    }
+
+    #[test]
+    fn it_keeps_concrete_iterators_and_boxes_marked_ones() {
+        let scanner = Scanner {
+            keys: vec!["a".to_string(), "bcd".to_string()],
+        };
+        assert_eq!(scanner.abx_scan().collect::<Vec<_>>(), ["a", "bcd"]);
+        assert_eq!(scanner.abx_lengths().collect::<Vec<_>>(), [1, 3]);
+    }
+}
+
+mod future_returns {
+    use super::wrap;
+    use futures::executor::block_on;
+    use std::future::Future;
+
+    pub struct Api;
+
+    impl Api {
+        pub fn get(&self, path: &str) -> impl Future<Output = String> {
+            let url = format!("https://api{path}");
+            async move { url }
+        }
+    }
+
+    #[wrap(Api)]
+    trait AbxApi {
+        fn abx_get(&self, path: &str) -> impl Future<Output = String>;
+    }
+
+    #[test]
+    fn it_returns_the_concrete_future_without_awaiting_it() {
+        let future = Api.abx_get("/users");
+        assert_eq!(block_on(future), "https://api/users");
+    }
+}
+
+mod stream_returns {
+    use super::wrap;
+    use futures::executor::block_on;
+    use futures::{stream, Stream, StreamExt};
+    use std::pin::Pin;
+
+    pub struct Broker;
+
+    impl Broker {
+        pub fn subscribe(&self, topic: &str) -> impl Stream<Item = String> {
+            stream::iter(vec![format!("{topic}:1"), format!("{topic}:2")])
+        }
+
+        pub fn firehose(&self) -> Pin<Box<dyn Stream<Item = String> + Send>> {
+            Box::pin(stream::empty())
+        }
+    }
+
+    #[wrap(Broker)]
+    trait AbxBroker {
+        fn abx_subscribe(&self, topic: &str) -> impl Stream<Item = String>;
+        fn abx_firehose(&self) -> Pin<Box<dyn Stream<Item = String> + Send>>;
+    }
+
+    #[test]
+    fn it_forwards_opaque_and_boxed_streams() {
+        let messages: Vec<_> = block_on(Broker.abx_subscribe("jobs").collect());
+        assert_eq!(messages, ["jobs:1", "jobs:2"]);
+        assert_eq!(block_on(Broker.abx_firehose().count()), 0);
+    }
+}
+
+mod unsafe_methods {
+    use super::wrap;
+
+    pub struct Device {
+        registers: Vec<u8>,
+    }
+
+    impl Device {
+        /// # Safety
+        ///
+        /// `index` must be less than the number of registers.
+        pub unsafe fn register(&self, index: usize) -> u8 {
+            unsafe { *self.registers.get_unchecked(index) }
+        }
+    }
+
+    #[wrap(Device)]
+    trait AbxDevice {
+        /// # Safety
+        ///
+        /// `index` must be less than the number of registers.
+        unsafe fn abx_register(&self, index: usize) -> u8;
+    }
+
+    #[test]
+    fn it_calls_the_concrete_method_in_an_unsafe_block() {
+        let device = Device {
+            registers: vec![7, 9],
+        };
+        assert_eq!(unsafe { device.abx_register(1) }, 9);
+    }
+}
+
+mod smart_pointer_receivers {
+    use super::wrap;
+    use std::pin::Pin;
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    pub struct Actor {
+        ticks: u32,
+    }
+
+    impl Actor {
+        pub fn handle(self: Arc<Self>, msg: &str) -> String {
+            format!("{msg} after {}", self.ticks)
+        }
+
+        pub fn owners(self: Rc<Self>) -> usize {
+            Rc::strong_count(&self)
+        }
+
+        pub fn tick(mut self: Pin<&mut Self>) -> u32 {
+            self.ticks += 1;
+            self.ticks
+        }
+    }
+
+    #[wrap(Actor)]
+    trait AbxActor {
+        fn abx_handle(self: Arc<Self>, msg: &str) -> String;
+        fn abx_owners(self: Rc<Self>) -> usize;
+        fn abx_tick(self: Pin<&mut Self>) -> u32;
+    }
+
+    #[test]
+    fn it_passes_typed_receivers_along() {
+        let mut actor = Actor { ticks: 0 };
+        assert_eq!(Pin::new(&mut actor).abx_tick(), 1);
+        assert_eq!(Arc::new(actor).abx_handle("ping"), "ping after 1");
+        let shared = Rc::new(Actor { ticks: 0 });
+        let _other = Rc::clone(&shared);
+        assert_eq!(shared.abx_owners(), 2);
+    }
+}
+
+mod const_generics {
+    use super::wrap;
+
+    pub struct Reader {
+        data: Vec<u8>,
+    }
+
+    impl Reader {
+        pub fn chunk<const N: usize>(&self) -> [u8; N] {
+            let mut chunk = [0; N];
+            for (slot, byte) in chunk.iter_mut().zip(&self.data) {
+                *slot = *byte;
+            }
+            chunk
+        }
+
+        pub fn fill<T: Copy, const N: usize>(&self, value: T) -> [T; N] {
+            [value; N]
+        }
+    }
+
+    #[wrap(Reader)]
+    trait AbxReader {
+        fn abx_chunk<const N: usize>(&self) -> [u8; N];
+        fn abx_fill<T: Copy, const N: usize>(&self, value: T) -> [T; N];
+    }
+
+    #[test]
+    fn it_forwards_const_parameters_with_a_turbofish() {
+        let reader = Reader {
+            data: vec![1, 2, 3],
+        };
+        assert_eq!(reader.abx_chunk::<2>(), [1, 2]);
+        assert_eq!(reader.abx_fill::<char, 3>('x'), ['x'; 3]);
+    }
+}
+
+mod higher_ranked_bounds {
+    use super::wrap;
+
+    pub struct Document {
+        text: String,
+    }
+
+    impl Document {
+        pub fn count_matching<F>(&self, f: F) -> usize
+        where
+            for<'a> F: Fn(&'a str) -> bool,
+        {
+            self.text.lines().filter(|line| f(line)).count()
+        }
+
+        pub fn map_first<F>(&self, f: F) -> Option<String>
+        where
+            F: for<'a> Fn(&'a str) -> &'a str,
+        {
+            self.text.lines().next().map(|line| f(line).to_string())
+        }
+    }
+
+    #[wrap(Document)]
+    trait AbxDocument {
+        fn abx_count_matching<F>(&self, f: F) -> usize
+        where
+            for<'a> F: Fn(&'a str) -> bool;
+        fn abx_map_first<F>(&self, f: F) -> Option<String>
+        where
+            F: for<'a> Fn(&'a str) -> &'a str;
+    }
+
+    #[test]
+    fn it_copies_higher_ranked_bounds() {
+        let document = Document {
+            text: " a\nbb\n a".to_string(),
+        };
+        assert_eq!(document.abx_count_matching(|line| line.starts_with(' ')), 2);
+        assert_eq!(document.abx_map_first(str::trim), Some("a".to_string()));
+    }
+}
+
+mod method_attributes {
+    use super::wrap;
+    use std::cell::Cell;
+
+    pub struct Lock {
+        held: Cell<bool>,
+    }
+
+    impl Lock {
+        pub fn acquire(&self) -> bool {
+            !self.held.replace(true)
+        }
+
+        pub fn release(&self) {
+            self.held.set(false);
+        }
+    }
+
+    #[wrap(Lock)]
+    trait AbxLock {
+        #[must_use]
+        fn abx_acquire(&self) -> bool;
+        #[deprecated(note = "use abx_acquire")]
+        fn abx_release(&self);
+        // Without the cfg on the impl method too, this would fail to build: `Lock` has no such method
+        #[cfg(any())]
+        fn abx_owner(&self) -> u32;
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn it_keeps_attributes_and_copies_cfgs() {
+        let lock = Lock {
+            held: Cell::new(false),
+        };
+        assert!(lock.abx_acquire());
+        assert!(!lock.abx_acquire());
+        lock.abx_release();
+        assert!(lock.abx_acquire());
+    }
+}
+
+mod argument_coercion {
+    use super::wrap;
+
+    pub struct Analytics;
+
+    impl Analytics {
+        pub fn track(&self, event: String) -> String {
+            format!("tracked {event}")
+        }
+
+        pub fn tag(&self, tag: &str) -> usize {
+            tag.len()
+        }
+    }
+
+    #[wrap(Analytics)]
+    trait AbxAnalytics {
+        fn abx_track(&self, #[abx(into)] event: &str) -> String;
+        fn abx_tag(&self, #[abx(as_ref)] tag: String) -> usize;
+    }
+
+    #[test]
+    fn it_converts_marked_arguments() {
+        assert_eq!(Analytics.abx_track("signup"), "tracked signup");
+        assert_eq!(Analytics.abx_tag("beta".to_string()), 4);
+    }
+}
+
+mod slice_params {
+    use super::wrap;
+
+    pub struct Cipher;
+
+    impl Cipher {
+        pub fn checksum(&self, data: &[u8]) -> u32 {
+            data.iter().map(|b| u32::from(*b)).sum()
+        }
+
+        pub fn xor_in_place(&self, key: [u8; 2], buf: &mut [u8]) {
+            for (i, byte) in buf.iter_mut().enumerate() {
+                *byte ^= key[i % key.len()];
+            }
+        }
+    }
+
+    #[wrap(Cipher)]
+    trait AbxCipher {
+        fn abx_checksum(&self, data: &[u8]) -> u32;
+        fn abx_xor_in_place(&self, key: [u8; 2], buf: &mut [u8]);
+    }
+
+    #[test]
+    fn it_forwards_slices_and_arrays() {
+        let mut buf = [1, 2, 3];
+        Cipher.abx_xor_in_place([1, 1], &mut buf);
+        assert_eq!(buf, [0, 3, 2]);
+        assert_eq!(Cipher.abx_checksum(&buf), 5);
+    }
+}
+
+mod cow_returns {
+    use super::wrap;
+    use std::borrow::Cow;
+
+    pub struct Normalizer {
+        label: String,
+    }
+
+    impl Normalizer {
+        pub fn label(&self) -> Cow<'_, str> {
+            if self.label.contains(' ') {
+                Cow::Owned(self.label.replace(' ', "_"))
+            } else {
+                Cow::Borrowed(&self.label)
+            }
+        }
+
+        pub fn trim<'a>(&self, input: &'a str) -> Cow<'a, str> {
+            Cow::Borrowed(input.trim())
+        }
+    }
+
+    #[wrap(Normalizer)]
+    trait AbxNormalizer {
+        fn abx_label(&self) -> Cow<'_, str>;
+        fn abx_trim<'a>(&self, input: &'a str) -> Cow<'a, str>;
+    }
+
+    #[test]
+    fn it_keeps_the_borrows_of_cow_returns() {
+        let normalizer = Normalizer {
+            label: "a b".to_string(),
+        };
+        assert!(matches!(normalizer.abx_label(), Cow::Owned(label) if label == "a_b"));
+        let trimmed = {
+            let input = String::from(" x ");
+            normalizer.abx_trim(&input).into_owned()
+        };
+        assert_eq!(trimmed, "x");
+    }
+}
+
+mod fluent_self {
+    use super::wrap;
+
+    pub struct Request {
+        parts: Vec<String>,
+    }
+
+    impl Request {
+        pub fn new(url: &str) -> Self {
+            Request {
+                parts: vec![url.to_string()],
+            }
+        }
+
+        pub fn header(mut self, name: &str) -> Self {
+            self.parts.push(name.to_string());
+            self
+        }
+
+        pub fn retries(&mut self, count: u8) -> &mut Self {
+            self.parts.push(count.to_string());
+            self
+        }
+
+        pub fn build(self) -> String {
+            self.parts.join(" ")
+        }
+    }
+
+    #[wrap(Request)]
+    trait AbxRequest {
+        fn abx_header(self, name: &str) -> Self;
+        fn abx_retries(&mut self, count: u8) -> &mut Self;
+        fn abx_build(self) -> String;
+    }
+
+    #[test]
+    fn it_hands_back_the_concrete_builder() {
+        let mut request = Request::new("/").abx_header("accept");
+        request.abx_retries(2).abx_retries(3);
+        assert_eq!(request.abx_build(), "/ accept 2 3");
+    }
+}
+
+mod nested_abstractions {
+    use super::wrap;
+
+    pub struct Database;
+
+    pub struct Session {
+        id: u32,
+    }
+
+    impl Database {
+        pub fn session(&self) -> Session {
+            Session { id: 7 }
+        }
+    }
+
+    impl Session {
+        pub fn query(&self, sql: &str) -> String {
+            format!("{} ran {sql}", self.id)
+        }
+    }
+
+    #[wrap(Session)]
+    trait AbxSession {
+        fn abx_query(&self, sql: &str) -> String;
+    }
+
+    #[wrap(Database)]
+    trait AbxDatabase {
+        fn abx_session(&self) -> impl AbxSession;
+    }
+
+    #[test]
+    fn it_returns_the_concrete_type_behind_the_nested_trait() {
+        assert_eq!(
+            Database.abx_session().abx_query("SELECT 1"),
+            "7 ran SELECT 1"
+        );
+    }
+}
+
+mod ffi_types {
+    use super::wrap;
+    use std::ffi::{c_char, c_int, CStr};
+
+    pub struct Binding {
+        buffer: [u8; 4],
+    }
+
+    impl Binding {
+        pub fn name(&self) -> *const c_char {
+            c"shim".as_ptr()
+        }
+
+        pub fn buffer(&mut self) -> *mut u8 {
+            self.buffer.as_mut_ptr()
+        }
+
+        pub unsafe fn length(&self, s: *const c_char) -> c_int {
+            unsafe { CStr::from_ptr(s) }.to_bytes().len() as c_int
+        }
+    }
+
+    #[wrap(Binding)]
+    trait AbxBinding {
+        fn abx_name(&self) -> *const c_char;
+        fn abx_buffer(&mut self) -> *mut u8;
+        unsafe fn abx_length(&self, s: *const c_char) -> c_int;
+    }
+
+    #[test]
+    fn it_passes_raw_pointers_through() {
+        let mut binding = Binding { buffer: [0; 4] };
+        unsafe { *binding.abx_buffer() = 1 };
+        assert_eq!(binding.buffer[0], 1);
+        assert_eq!(unsafe { binding.abx_length(binding.abx_name()) }, 4);
+    }
+}
+
+mod send_futures {
+    use super::wrap;
+    use futures::executor::block_on;
+    use std::thread;
+
+    pub struct Feed;
+
+    impl Feed {
+        pub async fn fetch(&self, topic: &str) -> String {
+            format!("{topic}: headline")
+        }
+
+        pub async fn count(&self) -> usize {
+            2
+        }
+    }
+
+    #[wrap(Feed)]
+    trait AbxFeed {
+        #[abx(send)]
+        async fn abx_fetch(&self, topic: &str) -> String;
+    }
+
+    #[wrap(Feed, futures = "boxed")]
+    #[abx(send)]
+    trait AbxCounter {
+        async fn abx_count(&self) -> usize;
+    }
+
+    fn on_thread<T: Send + 'static>(
+        task: impl std::future::Future<Output = T> + Send + 'static,
+    ) -> T {
+        thread::spawn(move || block_on(task)).join().unwrap()
+    }
+
+    #[test]
+    fn it_makes_the_future_send() {
+        fn fetch<F: AbxFeed + Sync + 'static>(feed: &'static F) -> String {
+            on_thread(feed.abx_fetch("news"))
+        }
+
+        assert_eq!(fetch(&Feed), "news: headline");
+    }
+
+    #[test]
+    fn it_makes_boxed_futures_send() {
+        let counter: &'static (dyn AbxCounter + Sync) = &Feed;
+        assert_eq!(on_thread(counter.abx_count()), 2);
+    }
+}
+
+mod feature_gated_methods {
+    use super::wrap;
+
+    pub struct Archive;
+
+    impl Archive {
+        pub fn add(&self, name: &str) -> String {
+            format!("added {name}")
+        }
+    }
+
+    // `compress` doesn't exist on the concrete type, which is fine while the method is configured out
+    #[wrap(Archive)]
+    trait AbxArchive {
+        fn abx_add(&self, name: &str) -> String;
+        #[cfg(any())]
+        fn abx_compress(&self, level: u8) -> String;
+    }
+
+    #[test]
+    fn it_gates_the_delegated_method_like_the_trait_method() {
+        assert_eq!(Archive.abx_add("notes.txt"), "added notes.txt");
+    }
+}
@@ -0,0 +1,979 @@
+// The options of `#[wrap(...)]`, and the `#[abx(...)]` options that change how a single method is delegated.
+
+use depabx::wrap;
+
+mod prefix {
+    use super::wrap;
+
+    pub struct Queue;
+
+    impl Queue {
+        pub fn publish(&self, msg: &str) -> usize {
+            msg.len()
+        }
+    }
+
+    #[wrap(Queue, prefix = "dep_")]
+    trait DepQueue {
+        fn dep_publish(&self, msg: &str) -> usize;
+    }
+
+    #[test]
+    fn it_strips_the_configured_prefix() {
+        assert_eq!(Queue.dep_publish("hi"), 2);
+    }
+}
+
+mod no_prefix {
+    use super::wrap;
+
+    pub struct Cache;
+
+    impl Cache {
+        pub fn lookup(&self, key: &str) -> Option<String> {
+            (key == "hit").then(|| "cached".to_string())
+        }
+    }
+
+    // The impl calls `<Cache>::lookup`, which resolves to the inherent method rather than back to the trait's
+    #[wrap(Cache, prefix = "")]
+    trait AbxCache {
+        fn lookup(&self, key: &str) -> Option<String>;
+    }
+
+    #[test]
+    fn it_delegates_to_the_method_of_the_same_name() {
+        fn lookup<C: AbxCache>(cache: &C, key: &str) -> Option<String> {
+            cache.lookup(key)
+        }
+
+        assert_eq!(lookup(&Cache, "hit"), Some("cached".to_string()));
+        assert_eq!(lookup(&Cache, "miss"), None);
+    }
+}
+
+mod target_rename {
+    use super::wrap;
+
+    pub struct ObjectStore;
+
+    impl ObjectStore {
+        pub fn upload_object(&self, key: &str, body: &[u8]) -> String {
+            format!("{key}: {} bytes", body.len())
+        }
+    }
+
+    // With a target, the trait method's own name is free of the prefix convention
+    #[wrap(ObjectStore)]
+    trait AbxBlobs {
+        #[abx(target = "upload_object")]
+        fn abx_put(&self, key: &str, body: &[u8]) -> String;
+        #[abx(target = "upload_object")]
+        fn store(&self, key: &str, body: &[u8]) -> String;
+    }
+
+    #[test]
+    fn it_delegates_to_the_named_method() {
+        assert_eq!(ObjectStore.abx_put("a", b"xy"), "a: 2 bytes");
+        assert_eq!(ObjectStore.store("b", b""), "b: 0 bytes");
+    }
+}
+
+mod skip {
+    use super::wrap;
+    use std::time::Duration;
+
+    pub struct Stopwatch;
+
+    impl Stopwatch {
+        pub fn elapsed_ms(&self) -> u64 {
+            1500
+        }
+    }
+
+    #[wrap(Stopwatch)]
+    trait AbxStopwatch {
+        fn abx_elapsed_ms(&self) -> u64;
+
+        #[abx(skip)]
+        fn abx_elapsed(&self) -> Duration {
+            Duration::from_millis(self.abx_elapsed_ms())
+        }
+    }
+
+    #[test]
+    fn it_keeps_the_skipped_method_body() {
+        assert_eq!(Stopwatch.abx_elapsed(), Duration::from_millis(1500));
+    }
+}
+
+mod target_paths {
+    use super::wrap;
+
+    pub mod sdk {
+        pub mod v2 {
+            pub struct Client<T>(pub T);
+
+            impl<T: Clone> Client<T> {
+                pub fn endpoint(&self) -> T {
+                    self.0.clone()
+                }
+            }
+        }
+
+        pub use v2::Client;
+        pub type DefaultClient = v2::Client<&'static str>;
+    }
+
+    // Qualified paths, re-exports, aliases and other crates' types are all used as written
+    #[wrap(crate::target_paths::sdk::v2::Client<u16>)]
+    trait AbxPort {
+        fn abx_endpoint(&self) -> u16;
+    }
+
+    #[wrap(sdk::DefaultClient)]
+    trait AbxHost {
+        fn abx_endpoint(&self) -> &'static str;
+    }
+
+    #[wrap(::std::vec::Vec<u8>)]
+    trait AbxBytes {
+        fn abx_len(&self) -> usize;
+    }
+
+    #[test]
+    fn it_implements_the_trait_for_the_type_at_the_path() {
+        assert_eq!(sdk::Client(8080).abx_endpoint(), 8080);
+        assert_eq!(sdk::Client("localhost").abx_endpoint(), "localhost");
+        assert_eq!(vec![1, 2].abx_len(), 2);
+    }
+}
+
+mod multiple_targets {
+    use super::wrap;
+
+    pub struct Console;
+    pub struct File;
+
+    impl Console {
+        pub fn write(&self, line: &str) -> String {
+            format!("console: {line}")
+        }
+    }
+
+    impl File {
+        pub fn write(&self, line: &str) -> String {
+            format!("file: {line}")
+        }
+    }
+
+    #[wrap(Console, File, prefix = "sink_")]
+    trait Sink {
+        fn sink_write(&self, line: &str) -> String;
+    }
+
+    #[test]
+    fn it_implements_the_trait_for_every_target() {
+        let sinks: [&dyn Sink; 2] = [&Console, &File];
+        assert_eq!(
+            sinks.map(|sink| sink.sink_write("up")),
+            ["console: up", "file: up"]
+        );
+    }
+}
+
+mod cfg_impl {
+    use super::wrap;
+
+    pub struct Sensor;
+
+    impl Sensor {
+        #[allow(dead_code)]
+        pub fn read_celsius(&self) -> f64 {
+            21.5
+        }
+    }
+
+    // Tests are built with `cfg(test)`, so the generated impl is left out and the test's own takes its place
+    #[wrap(Sensor, cfg(not(test)))]
+    trait AbxSensor: Send {
+        fn abx_read_celsius(&self) -> f64;
+    }
+
+    impl AbxSensor for Sensor {
+        fn abx_read_celsius(&self) -> f64 {
+            35.0
+        }
+    }
+
+    #[test]
+    fn it_compiles_the_impl_only_under_the_predicate() {
+        assert_eq!(Sensor.abx_read_celsius(), 35.0);
+    }
+}
+
+mod visibility {
+    use super::wrap;
+
+    pub struct AuditTrail;
+
+    impl AuditTrail {
+        pub fn record(&self, action: &str) -> String {
+            format!("recorded {action}")
+        }
+    }
+
+    // Nothing with a name is generated here, so only `doc(hidden)` has an effect, on the impl and its assertions
+    #[wrap(AuditTrail, vis = "pub(crate)", doc(hidden))]
+    pub trait AbxAuditTrail: Send + Sync {
+        fn abx_record(&self, action: &str) -> String;
+    }
+
+    #[test]
+    fn it_accepts_visibility_and_doc_options() {
+        assert_eq!(AuditTrail.abx_record("login"), "recorded login");
+    }
+}
+
+mod field_delegation {
+    use super::wrap;
+
+    pub struct Smtp {
+        sent: Vec<String>,
+    }
+
+    impl Smtp {
+        pub fn send(&mut self, to: &str) -> usize {
+            self.sent.push(to.to_string());
+            self.sent.len()
+        }
+
+        pub fn outbox(&self) -> &[String] {
+            &self.sent
+        }
+    }
+
+    pub struct Notifier {
+        client: Smtp,
+    }
+
+    pub struct Relay(Smtp);
+
+    // The impls call `self.client.send(..)` and `self.0.send(..)`
+    #[wrap(Notifier, via = client)]
+    trait AbxMailer {
+        fn abx_send(&mut self, to: &str) -> usize;
+        fn abx_outbox(&self) -> &[String];
+    }
+
+    #[wrap(Relay, via = 0)]
+    trait AbxRelay {
+        fn abx_send(&mut self, to: &str) -> usize;
+    }
+
+    #[test]
+    fn it_delegates_through_the_field() {
+        let mut notifier = Notifier {
+            client: Smtp { sent: Vec::new() },
+        };
+        assert_eq!(notifier.abx_send("ops"), 1);
+        assert_eq!(notifier.abx_outbox(), ["ops"]);
+
+        let mut relay = Relay(Smtp { sent: Vec::new() });
+        assert_eq!(relay.abx_send("dev"), 1);
+    }
+}
+
+mod newtype {
+    use super::wrap;
+
+    pub struct Pager {
+        team: String,
+    }
+
+    impl Pager {
+        pub fn page(&self, summary: &str) -> String {
+            format!("paged {}: {summary}", self.team)
+        }
+    }
+
+    // The trait is implemented on `ProdPager`, which is as visible as `vis` makes it, and `Pager` is left alone
+    #[wrap(Pager, newtype = ProdPager, vis = "pub")]
+    trait AbxPager: Send {
+        fn abx_page(&self, summary: &str) -> String;
+    }
+
+    #[test]
+    fn it_implements_the_trait_on_the_newtype() {
+        let pager = ProdPager::from(Pager {
+            team: "payments".to_string(),
+        });
+        assert_eq!(pager.abx_page("down"), "paged payments: down");
+        assert_eq!(pager.0.team, "payments");
+    }
+}
+
+mod map_return {
+    use super::wrap;
+
+    pub struct UsersApi;
+
+    pub struct Record {
+        name: String,
+    }
+
+    impl UsersApi {
+        pub fn fetch(&self, id: u32) -> Record {
+            Record {
+                name: format!("user{id}"),
+            }
+        }
+
+        pub async fn count(&self) -> Result<u32, String> {
+            Err("offline".to_string())
+        }
+    }
+
+    mod convert {
+        pub fn name(record: super::Record) -> String {
+            record.name
+        }
+
+        pub fn or_zero(count: Result<u32, String>) -> u32 {
+            count.unwrap_or(0)
+        }
+    }
+
+    // The mapping is applied to the awaited value, and replaces the `Into` conversion of the error
+    #[wrap(UsersApi)]
+    trait AbxUsers {
+        #[abx(target = "fetch", map_return = "convert::name")]
+        fn abx_name(&self, id: u32) -> String;
+        #[abx(map_return = "convert::or_zero")]
+        async fn abx_count(&self) -> u32;
+    }
+
+    #[test]
+    fn it_passes_the_return_value_through_the_mapping() {
+        assert_eq!(UsersApi.abx_name(3), "user3");
+        assert_eq!(futures::executor::block_on(UsersApi.abx_count()), 0);
+    }
+}
+
+mod map_arg {
+    use super::wrap;
+
+    pub struct Request {
+        query: String,
+        limit: usize,
+    }
+
+    pub struct SearchApi;
+
+    impl SearchApi {
+        pub fn search(&self, request: Request, page: u32) -> String {
+            format!("{} x{} p{page}", request.query, request.limit)
+        }
+    }
+
+    // A mapping can use the other arguments too
+    #[wrap(SearchApi)]
+    trait AbxSearch {
+        #[abx(map_arg(
+            query = "Request { query: query.to_string(), limit: 10 }",
+            page = "page + 1"
+        ))]
+        fn abx_search(&self, query: &str, page: u32) -> String;
+    }
+
+    #[test]
+    fn it_passes_the_mapped_arguments() {
+        assert_eq!(SearchApi.abx_search("rust", 0), "rust x10 p1");
+    }
+}
+
+mod module {
+    use super::wrap;
+
+    pub struct Geocoder;
+
+    impl Geocoder {
+        pub fn lookup(&self, address: &str) -> usize {
+            address.len()
+        }
+    }
+
+    // The newtype is as private as the trait, which inside the module means `pub(super)`
+    #[wrap(Geocoder, newtype = ProdGeocoder, module = abstractions)]
+    trait AbxGeocoder: Send {
+        fn abx_lookup(&self, address: &str) -> usize;
+    }
+
+    #[test]
+    fn it_puts_the_generated_items_in_the_module() {
+        let geocoder = abstractions::ProdGeocoder::from(Geocoder);
+        assert_eq!(geocoder.abx_lookup("1 Main St"), 9);
+    }
+}
+
+mod inherent_impls {
+    use depabx::wrap_impl;
+
+    pub struct RateLimiter {
+        limit: u32,
+    }
+
+    // Generates `trait AbxRateLimiter` with `abx_allow` and `abx_new`, leaving out the private `remaining`
+    #[wrap_impl]
+    impl RateLimiter {
+        pub fn new(limit: u32) -> Self {
+            RateLimiter { limit }
+        }
+
+        pub fn allow(&self, mut used: u32) -> bool {
+            used += 1;
+            self.remaining(used) > 0
+        }
+
+        fn remaining(&self, used: u32) -> u32 {
+            self.limit.saturating_sub(used)
+        }
+    }
+
+    // Options work as they do on `#[wrap]`
+    pub struct Counter;
+
+    #[wrap_impl(prefix = "dep_", vis = "pub")]
+    impl Counter {
+        pub fn get(&self) -> u8 {
+            1
+        }
+    }
+
+    #[test]
+    fn it_generates_the_trait_from_the_public_methods() {
+        fn allowed<R: AbxRateLimiter>(limiter: &R) -> Vec<bool> {
+            (0..3).map(|used| limiter.abx_allow(used)).collect()
+        }
+
+        assert_eq!(allowed(&RateLimiter::abx_new(2)), [true, false, false]);
+        assert_eq!(AbxCounter::dep_get(&Counter), 1);
+    }
+}
+
+mod strict {
+    use super::wrap;
+
+    pub struct Ledger {
+        entries: Vec<u64>,
+    }
+
+    impl Ledger {
+        pub fn credit(&mut self, cents: u64) -> usize {
+            self.entries.push(cents);
+            self.entries.len()
+        }
+
+        pub fn last(&self) -> Option<&u64> {
+            self.entries.last()
+        }
+
+        pub fn sum<T: From<u64>>(&self) -> T {
+            T::from(self.entries.iter().sum())
+        }
+    }
+
+    // Generic methods aren't compared, since there's no function pointer type to compare them as
+    #[wrap(Ledger, strict)]
+    trait AbxLedger {
+        fn abx_credit(&mut self, cents: u64) -> usize;
+        fn abx_last(&self) -> Option<&u64>;
+        fn abx_sum<T: From<u64>>(&self) -> T;
+    }
+
+    #[test]
+    fn it_accepts_exactly_matching_signatures() {
+        let mut ledger = Ledger {
+            entries: Vec::new(),
+        };
+        assert_eq!(ledger.abx_credit(250), 1);
+        assert_eq!(ledger.abx_last(), Some(&250));
+        assert_eq!(ledger.abx_sum::<u128>(), 250);
+    }
+}
+
+mod deref {
+    use super::wrap;
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    pub struct Translator {
+        uses: u32,
+    }
+
+    impl Translator {
+        pub fn translate(&self, text: &str) -> String {
+            format!("¡{text}!")
+        }
+
+        pub fn count(&mut self) -> u32 {
+            self.uses += 1;
+            self.uses
+        }
+    }
+
+    #[wrap(Translator, deref)]
+    trait AbxTranslator {
+        fn abx_translate(&self, text: &str) -> String;
+    }
+
+    // A `&mut self` method makes the blanket impl require `DerefMut` as well
+    #[wrap(Translator, deref)]
+    trait AbxCounter {
+        fn abx_count(&mut self) -> u32;
+    }
+
+    fn translator() -> Translator {
+        Translator { uses: 0 }
+    }
+
+    #[test]
+    fn it_implements_the_trait_for_pointers_to_the_target() {
+        fn run<T: AbxTranslator>(translator: &T) -> String {
+            translator.abx_translate("hola")
+        }
+
+        assert_eq!(run(&translator()), "¡hola!");
+        assert_eq!(run(&Arc::new(translator())), "¡hola!");
+        assert_eq!(run(&Rc::new(translator())), "¡hola!");
+    }
+
+    #[test]
+    fn it_forwards_mutable_methods_through_deref_mut() {
+        let mut boxed = Box::new(translator());
+        boxed.abx_count();
+        assert_eq!(boxed.abx_count(), 2);
+        let mut target = translator();
+        assert_eq!(AbxCounter::abx_count(&mut &mut target), 1);
+    }
+
+    // Another crate's type, which a blanket impl over `Deref` would conflict with
+    #[wrap(std::time::Duration, deref)]
+    trait AbxDuration {
+        fn abx_as_secs(&self) -> u64;
+    }
+
+    #[test]
+    fn it_implements_the_trait_for_pointers_to_another_crates_type() {
+        fn run<D: AbxDuration>(duration: D) -> u64 {
+            duration.abx_as_secs()
+        }
+        let duration = std::time::Duration::from_secs(90);
+
+        let borrowed: &std::time::Duration = &duration;
+
+        assert_eq!(run(duration), 90);
+        assert_eq!(run(borrowed), 90);
+        assert_eq!(run(Box::new(duration)), 90);
+        assert_eq!(run(Rc::new(duration)), 90);
+        assert_eq!(run(Arc::new(duration)), 90);
+    }
+}
+
+mod rename_all {
+    use super::wrap;
+
+    pub struct CLogger;
+
+    #[allow(non_snake_case)]
+    impl CLogger {
+        pub fn LoggerLogEvent(&self, message: &str) -> usize {
+            message.len()
+        }
+
+        pub fn LoggerGetLastErrorCode(&self) -> i32 {
+            -1
+        }
+
+        pub fn logFlush(&self) -> bool {
+            true
+        }
+
+        pub fn LOG_LEVEL(&self) -> u8 {
+            3
+        }
+
+        pub fn LoggerÄndereStufe(&self, stufe: u8) -> u8 {
+            stufe
+        }
+    }
+
+    #[wrap(CLogger, rename_all = "PascalCase", target_prefix = "Logger")]
+    trait AbxCLogger {
+        fn abx_log_event(&self, message: &str) -> usize;
+        #[abx(target = "LoggerGetLastErrorCode")]
+        fn abx_last_error(&self) -> i32;
+        // Words starting with a character that isn't ASCII are capitalized like any other
+        fn abx_ändere_stufe(&self, stufe: u8) -> u8;
+    }
+
+    #[wrap(CLogger, rename_all = "camelCase")]
+    trait AbxFlush {
+        fn abx_log_flush(&self) -> bool;
+    }
+
+    #[wrap(CLogger, rename_all = "SCREAMING_SNAKE_CASE")]
+    trait AbxLevel {
+        fn abx_log_level(&self) -> u8;
+    }
+
+    #[test]
+    fn it_converts_the_case_of_the_concrete_method_names() {
+        assert_eq!(CLogger.abx_log_event("started"), 7);
+        assert_eq!(CLogger.abx_last_error(), -1);
+        assert!(CLogger.abx_log_flush());
+        assert_eq!(CLogger.abx_log_level(), 3);
+        assert_eq!(CLogger.abx_ändere_stufe(2), 2);
+    }
+}
+
+mod object_safety {
+    use super::wrap;
+    use std::fmt::Display;
+
+    pub struct Webhook;
+
+    impl Webhook {
+        pub fn post(&self, payload: &str) -> String {
+            format!("POST {payload}")
+        }
+
+        pub fn post_display<T: Display>(&self, payload: T) -> String {
+            self.post(&payload.to_string())
+        }
+
+        pub async fn send(&self, payload: &str) -> usize {
+            payload.len()
+        }
+    }
+
+    // Methods bound by `Self: Sized` aren't part of the trait object, and boxed futures keep async methods in it
+    #[wrap(Webhook, dyn, futures = "boxed")]
+    trait AbxWebhook {
+        fn abx_post(&self, payload: &str) -> String;
+        fn abx_post_display<T: Display>(&self, payload: T) -> String
+        where
+            Self: Sized;
+        async fn abx_send(&self, payload: &str) -> usize;
+    }
+
+    #[test]
+    fn it_keeps_the_trait_usable_as_a_trait_object() {
+        let hook: Box<dyn AbxWebhook> = Box::new(Webhook);
+        assert_eq!(hook.abx_post("{}"), "POST {}");
+        assert_eq!(futures::executor::block_on(hook.abx_send("{}")), 2);
+        assert_eq!(Webhook.abx_post_display(500), "POST 500");
+    }
+}
+
+mod extension_traits {
+    use super::wrap;
+    use std::fmt::Display;
+
+    pub struct EventSink;
+
+    impl EventSink {
+        pub fn emit(&self, level: u8, message: &str) -> String {
+            format!("<{level}> {message}")
+        }
+    }
+
+    #[wrap(EventSink, ext)]
+    trait AbxEventSink {
+        fn abx_emit(&self, level: u8, message: &str) -> String;
+
+        #[abx(ext)]
+        fn abx_info<T: Display>(&self, message: T) -> String {
+            self.abx_emit(6, &message.to_string())
+        }
+    }
+
+    pub struct Cache<K, V>(Vec<(K, V)>);
+
+    impl<K: PartialEq, V: Clone> Cache<K, V> {
+        pub fn get(&self, key: K) -> Option<V> {
+            self.0
+                .iter()
+                .find(|(k, _)| *k == key)
+                .map(|(_, v)| v.clone())
+        }
+    }
+
+    #[wrap(Cache<u32, String>, ext)]
+    trait AbxCache<K, V> {
+        fn abx_get(&self, key: K) -> Option<V>;
+
+        #[abx(ext)]
+        fn abx_get_or(&self, key: K, default: V) -> V {
+            self.abx_get(key).unwrap_or(default)
+        }
+    }
+
+    struct FakeEventSink;
+
+    impl AbxEventSink for FakeEventSink {
+        fn abx_emit(&self, level: u8, message: &str) -> String {
+            format!("{level}:{message}")
+        }
+    }
+
+    #[test]
+    fn it_moves_the_helpers_to_the_extension_trait() {
+        use self::AbxEventSinkExt;
+
+        assert_eq!(EventSink.abx_info(404), "<6> 404");
+        assert_eq!(FakeEventSink.abx_info("started"), "6:started");
+        let sink: &dyn AbxEventSink = &FakeEventSink;
+        assert_eq!(sink.abx_info(1), "6:1");
+    }
+
+    #[test]
+    fn it_carries_the_generic_parameters_of_the_trait() {
+        let cache = Cache(vec![(1, "one".to_string())]);
+        assert_eq!(cache.abx_get_or(1, String::new()), "one");
+        assert_eq!(cache.abx_get_or(2, "none".to_string()), "none");
+    }
+}
+
+mod role_traits {
+    use super::wrap;
+    use std::cell::RefCell;
+
+    pub struct BlobClient {
+        blobs: RefCell<Vec<String>>,
+    }
+
+    impl BlobClient {
+        pub fn read(&self, index: usize) -> Option<String> {
+            self.blobs.borrow().get(index).cloned()
+        }
+
+        pub fn write(&self, blob: &str) -> usize {
+            self.blobs.borrow_mut().push(blob.to_string());
+            self.blobs.borrow().len() - 1
+        }
+    }
+
+    #[wrap(BlobClient, role)]
+    trait AbxBlobReader {
+        fn abx_read(&self, index: usize) -> Option<String>;
+    }
+
+    #[wrap(BlobClient, role)]
+    trait AbxBlobWriter {
+        fn abx_write(&self, blob: &str) -> usize;
+    }
+
+    #[test]
+    fn it_implements_every_role_for_the_target() {
+        fn run<R: AbxBlobReader, W: AbxBlobWriter>(reader: &R, writer: &W) -> Option<String> {
+            let index = writer.abx_write("hello");
+            reader.abx_read(index)
+        }
+
+        let client = BlobClient {
+            blobs: RefCell::new(Vec::new()),
+        };
+        assert_eq!(run(&client, &client), Some("hello".to_string()));
+    }
+
+    pub struct Cache<T>(Vec<T>);
+
+    impl<T> Cache<T> {
+        pub fn len(&self) -> usize {
+            self.0.len()
+        }
+    }
+
+    // Each target gets markers of its own, so the same role over two instances of a generic type isn't a conflict
+    #[wrap(Cache<u32>, Cache<String>, role)]
+    trait AbxCacheSize {
+        fn abx_len(&self) -> usize;
+    }
+
+    #[test]
+    fn it_takes_roles_over_several_instances_of_a_type() {
+        assert_eq!(Cache(vec![1_u32, 2]).abx_len(), 2);
+        assert_eq!(Cache(vec![String::new()]).abx_len(), 1);
+    }
+}
+
+mod send_sync {
+    use super::wrap;
+    use std::thread;
+
+    pub struct GeoIp;
+
+    impl GeoIp {
+        pub fn country(&self, ip: &str) -> &'static str {
+            if ip.starts_with("10.") {
+                "internal"
+            } else {
+                "unknown"
+            }
+        }
+    }
+
+    #[wrap(GeoIp, send_sync)]
+    trait AbxGeoIp {
+        fn abx_country(&self, ip: &str) -> &'static str;
+    }
+
+    #[test]
+    fn it_makes_the_trait_send_and_sync() {
+        // No `Send` or `Sync` bounds needed: they come with the trait
+        fn run<G: AbxGeoIp>(geoip: &G) -> &'static str {
+            thread::scope(|scope| {
+                scope
+                    .spawn(|| geoip.abx_country("10.0.0.1"))
+                    .join()
+                    .unwrap()
+            })
+        }
+
+        assert_eq!(run(&GeoIp), "internal");
+        let shared: Box<dyn AbxGeoIp> = Box::new(GeoIp);
+        thread::spawn(move || assert_eq!(shared.abx_country("8.8.8.8"), "unknown"))
+            .join()
+            .unwrap();
+    }
+}
+
+mod method_docs {
+    use super::wrap;
+
+    pub struct PaymentGateway;
+
+    impl PaymentGateway {
+        pub fn charge_card(&self, cents: u64) -> Result<String, String> {
+            Ok(format!("ch_{cents}"))
+        }
+    }
+
+    // The impl method repeats these docs, then links `PaymentGateway::charge_card`
+    #[wrap(PaymentGateway)]
+    trait AbxPaymentGateway {
+        /// Charges the customer's default card and returns the charge id.
+        #[abx(target = "charge_card")]
+        fn abx_charge(&self, cents: u64) -> Result<String, String>;
+    }
+
+    #[test]
+    fn it_delegates_documented_methods() {
+        assert_eq!(PaymentGateway.abx_charge(1999), Ok("ch_1999".to_string()));
+    }
+}
+
+// `s3` isn't one of this crate's features, which is the point: the impl is never compiled
+#[allow(unexpected_cfgs)]
+mod feature_gated_impls {
+    use super::wrap;
+
+    // Without the feature the target isn't even resolved, so it can name a crate that isn't a dependency
+    #[wrap(aws_sdk_s3::Client, feature = "s3")]
+    trait AbxObjectStore {
+        fn abx_put_object(&self, key: &str, body: &[u8]) -> String;
+    }
+
+    struct FakeObjectStore;
+
+    impl AbxObjectStore for FakeObjectStore {
+        fn abx_put_object(&self, key: &str, body: &[u8]) -> String {
+            format!("{key}:{}", body.len())
+        }
+    }
+
+    #[test]
+    fn it_keeps_the_trait_without_the_feature() {
+        assert_eq!(
+            FakeObjectStore.abx_put_object("daily.csv", b"1,20"),
+            "daily.csv:4"
+        );
+    }
+}
+
+mod dump {
+    use super::wrap;
+
+    pub struct SlackClient;
+
+    impl SlackClient {
+        pub fn post_message(&self, channel: &str, text: &str) -> String {
+            format!("#{channel}: {text}")
+        }
+    }
+
+    #[wrap(SlackClient, dump = "target/depabx/slack.rs")]
+    trait AbxSlackClient {
+        fn abx_post_message(&self, channel: &str, text: &str) -> String;
+    }
+
+    #[test]
+    fn it_writes_the_expansion_relative_to_the_crate_root() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/target/depabx/slack.rs");
+        let dumped = std::fs::read_to_string(path).unwrap();
+
+        assert!(dumped.contains("trait AbxSlackClient {"));
+        assert!(dumped.contains("impl AbxSlackClient for SlackClient {"));
+        assert!(dumped.contains("<SlackClient>::post_message(self, channel, text)"));
+        assert_eq!(
+            SlackClient.abx_post_message("deploys", "live"),
+            "#deploys: live"
+        );
+    }
+}
+
+mod method_list {
+    use super::wrap;
+    use std::cell::RefCell;
+
+    pub struct Alerter {
+        sent: RefCell<Vec<String>>,
+    }
+
+    impl Alerter {
+        pub fn warn(&self, message: &str) {
+            self.sent.borrow_mut().push(format!("WARN {message}"));
+        }
+
+        pub fn critical(&self, message: &str) -> bool {
+            self.sent.borrow_mut().push(format!("CRIT {message}"));
+            true
+        }
+
+        pub fn first<T: Clone>(&self, items: &[T]) -> Option<T> {
+            items.first().cloned()
+        }
+    }
+
+    #[wrap(Alerter, methods(
+        warn(&self, message: &str),
+        critical(&self, message: &str) -> bool,
+        first<T>(&self, items: &[T]) -> Option<T> where T: Clone,
+    ))]
+    trait AbxAlerter {}
+
+    #[test]
+    fn it_declares_and_delegates_the_listed_methods() {
+        let alerter = Alerter {
+            sent: RefCell::new(Vec::new()),
+        };
+        alerter.abx_warn("disk filling up");
+        assert!(alerter.abx_critical("disk full"));
+        assert_eq!(alerter.abx_first(&[3, 4]), Some(3));
+        assert_eq!(
+            *alerter.sent.borrow(),
+            vec!["WARN disk filling up", "CRIT disk full"]
+        );
+    }
+}
@@ -0,0 +1,280 @@
+// Abx traits with more than methods in them, and targets other than plain named types.
+
+use depabx::wrap;
+
+mod generic_traits {
+    use super::wrap;
+    use std::cell::RefCell;
+
+    pub struct Slot<T, const N: usize> {
+        values: RefCell<Vec<T>>,
+    }
+
+    impl<T: Clone, const N: usize> Slot<T, N> {
+        pub fn put(&self, value: T) {
+            self.values.borrow_mut().push(value);
+        }
+
+        pub fn last(&self) -> Option<T> {
+            self.values.borrow().last().cloned()
+        }
+
+        pub fn window(&self) -> [usize; N] {
+            [self.values.borrow().len(); N]
+        }
+    }
+
+    // The trait's type and const parameters are filled in from the target's arguments, in the signatures too
+    #[wrap(Slot<String, 2>)]
+    trait AbxSlot<T, const N: usize> {
+        fn abx_put(&self, value: T);
+        fn abx_last(&self) -> Option<T>;
+        fn abx_window(&self) -> [usize; N];
+    }
+
+    #[test]
+    fn it_implements_the_trait_for_the_target_instantiation() {
+        fn run<S: AbxSlot<String, 2>>(slot: &S) -> (Option<String>, [usize; 2]) {
+            slot.abx_put("a".to_string());
+            (slot.abx_last(), slot.abx_window())
+        }
+
+        let slot = Slot {
+            values: RefCell::new(Vec::new()),
+        };
+        assert_eq!(run(&slot), (Some("a".to_string()), [1, 1]));
+    }
+}
+
+mod associated_types {
+    use super::wrap;
+
+    pub struct Parser;
+
+    impl Parser {
+        pub fn parse(&self, input: &str) -> Result<u32, std::num::ParseIntError> {
+            input.parse()
+        }
+    }
+
+    #[wrap(Parser)]
+    #[abx(type Output = u32, type Error = std::num::ParseIntError)]
+    trait AbxParser {
+        type Output;
+        type Error;
+        fn abx_parse(&self, input: &str) -> Result<Self::Output, Self::Error>;
+    }
+
+    #[test]
+    fn it_binds_the_associated_types() {
+        let parsed: Result<u32, _> = Parser.abx_parse("42");
+        assert_eq!(parsed, Ok(42));
+        assert!(Parser.abx_parse("forty-two").is_err());
+    }
+}
+
+mod associated_consts {
+    use super::wrap;
+
+    pub struct Queue;
+
+    impl Queue {
+        pub const CAPACITY: usize = 8;
+
+        pub fn len(&self) -> usize {
+            3
+        }
+    }
+
+    // Consts are bound to any const expression, and consts with a default can be left alone
+    #[wrap(Queue)]
+    #[abx(const CAPACITY = Queue::CAPACITY, const NAME = "queue")]
+    trait AbxQueue {
+        const CAPACITY: usize;
+        const NAME: &'static str;
+        const RETRIES: u32 = 3;
+        fn abx_len(&self) -> usize;
+    }
+
+    #[test]
+    fn it_binds_the_associated_consts() {
+        fn free<Q: AbxQueue>(queue: &Q) -> usize {
+            Q::CAPACITY - queue.abx_len()
+        }
+
+        assert_eq!(free(&Queue), 5);
+        assert_eq!(<Queue as AbxQueue>::NAME, "queue");
+        assert_eq!(<Queue as AbxQueue>::RETRIES, 3);
+    }
+}
+
+mod default_methods {
+    use super::wrap;
+
+    pub struct Greeter;
+
+    impl Greeter {
+        pub fn greet(&self, name: &str) -> String {
+            format!("Hello, {name}")
+        }
+    }
+
+    // `shout` has no concrete counterpart, and needs no `abx_` prefix since nothing is delegated to
+    #[wrap(Greeter)]
+    trait AbxGreeter {
+        fn abx_greet(&self, name: &str) -> String;
+
+        fn shout(&self, name: &str) -> String {
+            self.abx_greet(name).to_uppercase()
+        }
+    }
+
+    #[test]
+    fn it_keeps_default_bodies() {
+        assert_eq!(Greeter.shout("ada"), "HELLO, ADA");
+    }
+}
+
+mod generic_targets {
+    use super::wrap;
+    use std::marker::PhantomData;
+
+    pub struct Json;
+    pub struct Yaml;
+
+    pub struct Codec<F, const V: u8> {
+        format: PhantomData<F>,
+    }
+
+    impl<F, const V: u8> Codec<F, V> {
+        pub fn version(&self) -> u8 {
+            V
+        }
+    }
+
+    // Each instantiation gets its own impl, whether or not it's written with a turbofish
+    #[wrap(Codec<Json, 1>, Codec::<Yaml, 2>)]
+    trait AbxCodec {
+        fn abx_version(&self) -> u8;
+    }
+
+    #[test]
+    fn it_implements_the_trait_for_each_instantiation() {
+        let json: Codec<Json, 1> = Codec {
+            format: PhantomData,
+        };
+        let yaml: Codec<Yaml, 2> = Codec {
+            format: PhantomData,
+        };
+        let codecs: [&dyn AbxCodec; 2] = [&json, &yaml];
+        assert_eq!(codecs.map(|codec| codec.abx_version()), [1, 2]);
+    }
+}
+
+mod lifetime_targets {
+    use super::wrap;
+
+    pub struct Cursor<'a> {
+        keys: &'a [&'a str],
+    }
+
+    impl<'a> Cursor<'a> {
+        pub fn first(&self) -> Option<&'a str> {
+            self.keys.first().copied()
+        }
+    }
+
+    // The target's lifetimes become the impl's, and fill in the trait's: `impl<'a> AbxCursor<'a> for Cursor<'a>`
+    #[wrap(Cursor<'a>)]
+    trait AbxCursor<'k> {
+        fn abx_first(&self) -> Option<&'k str>;
+    }
+
+    #[test]
+    fn it_declares_the_target_lifetimes_on_the_impl() {
+        let keys = ["a", "b"];
+        let first = Cursor { keys: &keys }.abx_first();
+        assert_eq!(first, Some("a"));
+    }
+}
+
+mod supertraits {
+    use super::wrap;
+    use std::fmt::Debug;
+
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Handle<'a> {
+        name: &'a str,
+    }
+
+    impl<'a> Handle<'a> {
+        pub fn name(&self) -> &'a str {
+            self.name
+        }
+    }
+
+    // The supertraits are checked for the target, lifetimes included, and stay usable through the trait
+    #[wrap(Handle<'a>)]
+    trait AbxHandle<'n>: Clone + Debug + Send + Sync {
+        fn abx_name(&self) -> &'n str;
+    }
+
+    #[test]
+    fn it_keeps_the_supertraits() {
+        fn both<'n, H: AbxHandle<'n> + PartialEq>(handle: &H) -> (bool, &'n str) {
+            (handle.clone() == *handle, handle.abx_name())
+        }
+
+        assert_eq!(both(&Handle { name: "main" }), (true, "main"));
+    }
+}
+
+mod iterator_dependencies {
+    use super::wrap;
+
+    pub struct Countdown(u32);
+
+    impl Iterator for Countdown {
+        type Item = u32;
+
+        fn next(&mut self) -> Option<u32> {
+            self.0 = self.0.checked_sub(1)?;
+            Some(self.0)
+        }
+    }
+
+    pub struct Tags(Vec<String>);
+
+    impl<'a> IntoIterator for &'a Tags {
+        type Item = &'a String;
+        type IntoIter = std::slice::Iter<'a, String>;
+
+        fn into_iter(self) -> Self::IntoIter {
+            self.0.iter()
+        }
+    }
+
+    #[wrap(Countdown)]
+    trait AbxCountdown {
+        #[abx(iter)]
+        fn abx_iter(&mut self) -> Box<dyn Iterator<Item = u32> + '_>;
+    }
+
+    #[wrap(Tags)]
+    trait AbxTags {
+        #[abx(iter)]
+        fn abx_iter(&self) -> Box<dyn Iterator<Item = &String> + '_>;
+    }
+
+    #[test]
+    fn it_boxes_the_iterator_of_an_iterator() {
+        let mut countdown = Countdown(3);
+        assert_eq!(countdown.abx_iter().collect::<Vec<_>>(), [2, 1, 0]);
+    }
+
+    #[test]
+    fn it_boxes_the_iterator_of_a_borrowed_collection() {
+        let tags = Tags(vec!["a".to_string(), "b".to_string()]);
+        assert_eq!(tags.abx_iter().count(), 2);
+    }
+}
@@ -0,0 +1,6 @@
+// Code the macro has to reject, with the errors it's rejected with.
+
+#[test]
+fn ui() {
+    trybuild::TestCases::new().compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,15 @@
+use depabx::wrap;
+
+pub struct SearchApi;
+
+impl SearchApi {
+    pub fn search(&self, _query: String) {}
+}
+
+#[wrap(SearchApi)]
+trait AbxSearch {
+    #[abx(map_arg(text = "query.to_string()"))]
+    fn abx_search(&self, query: &str);
+}
+
+fn main() {}
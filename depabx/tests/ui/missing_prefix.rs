@@ -0,0 +1,15 @@
+use depabx::wrap;
+
+pub struct Queue;
+
+impl Queue {
+    pub fn publish(&self, _msg: &str) {}
+}
+
+// Every delegated method needs the configured prefix, even when it's not the default
+#[wrap(Queue, prefix = "dep_")]
+trait DepQueue {
+    fn abx_publish(&self, msg: &str);
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use depabx::wrap;
+
+// Not `Clone`, which the trait requires
+pub struct Metrics;
+
+impl Metrics {
+    pub fn increment(&self, _name: &str) {}
+}
+
+#[wrap(Metrics)]
+trait AbxMetrics: Clone + Send {
+    fn abx_increment(&self, name: &str);
+}
+
+fn main() {}
@@ -0,0 +1,30 @@
+use depabx::wrap;
+
+pub struct Database;
+pub struct Session;
+pub struct OtherSession;
+
+impl Database {
+    pub fn session(&self) -> Session {
+        Session
+    }
+}
+
+impl OtherSession {
+    pub fn query(&self) -> u32 {
+        1
+    }
+}
+
+// `Session` was never wrapped, so it doesn't implement the trait `abx_session` promises to return
+#[wrap(OtherSession)]
+trait AbxSession {
+    fn abx_query(&self) -> u32;
+}
+
+#[wrap(Database)]
+trait AbxDatabase {
+    fn abx_session(&self) -> impl AbxSession;
+}
+
+fn main() {}
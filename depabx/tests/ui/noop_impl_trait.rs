@@ -0,0 +1,16 @@
+use depabx::wrap;
+
+pub struct Exporter;
+
+impl Exporter {
+    pub fn pending(&self) -> impl Iterator<Item = u64> {
+        1..=2
+    }
+}
+
+#[wrap(Exporter, noop)]
+trait AbxExporter {
+    fn abx_pending(&self) -> impl Iterator<Item = u64>;
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+use depabx::wrap;
+use std::fmt::Display;
+
+pub struct Webhook;
+
+impl Webhook {
+    pub fn post_display<T: Display>(&self, payload: T) -> String {
+        payload.to_string()
+    }
+}
+
+#[wrap(Webhook, dyn)]
+trait AbxWebhook {
+    fn abx_post_display<T: Display>(&self, payload: T) -> String;
+}
+
+fn main() {}
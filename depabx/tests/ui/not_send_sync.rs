@@ -0,0 +1,19 @@
+use depabx::wrap;
+use std::rc::Rc;
+
+pub struct GeoIp {
+    pub cache: Rc<Vec<String>>,
+}
+
+impl GeoIp {
+    pub fn country(&self, ip: &str) -> String {
+        ip.to_string()
+    }
+}
+
+#[wrap(GeoIp, send_sync)]
+trait AbxGeoIp {
+    fn abx_country(&self, ip: &str) -> String;
+}
+
+fn main() {}
@@ -0,0 +1,26 @@
+use depabx::wrap;
+
+pub struct BlobClient;
+
+impl BlobClient {
+    pub fn write(&self, blob: &str) -> usize {
+        blob.len()
+    }
+
+    pub fn upload(&self, blob: &str) -> usize {
+        blob.len()
+    }
+}
+
+#[wrap(BlobClient, role)]
+trait AbxBlobWriter {
+    fn abx_write(&self, blob: &str) -> usize;
+}
+
+#[wrap(BlobClient, role)]
+trait AbxBlobUploader {
+    #[abx(target = "upload")]
+    fn abx_write(&self, blob: &str) -> usize;
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+use depabx::wrap;
+
+pub struct Stopwatch;
+
+impl Stopwatch {
+    pub fn elapsed_ms(&self) -> u64 {
+        0
+    }
+}
+
+#[wrap(Stopwatch)]
+trait AbxStopwatch {
+    fn abx_elapsed_ms(&self) -> u64;
+    #[abx(skip)]
+    fn abx_elapsed_secs(&self) -> u64;
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+use depabx::wrap;
+
+pub struct SessionStore;
+
+impl SessionStore {
+    pub fn put(&self, _id: &str, _user: String) {}
+
+    pub fn touch(&self, _id: &str) {}
+}
+
+#[wrap(SessionStore, store)]
+trait AbxSessionStore {
+    fn abx_put(&self, id: &str, user: String);
+    fn abx_touch(&self, id: &str);
+}
+
+fn main() {}
@@ -0,0 +1,26 @@
+use depabx::wrap;
+
+pub struct Ledger;
+
+impl Ledger {
+    pub fn label(&self, text: &str) -> String {
+        text.to_string()
+    }
+
+    pub fn credit(&self, cents: u64) -> u64 {
+        cents
+    }
+}
+
+// Deref coercion would turn the `&String` into a `&str`, but `strict` wants the same signature
+#[wrap(Ledger, strict)]
+trait AbxLabels {
+    fn abx_label(&self, text: &String) -> String;
+}
+
+#[wrap(Ledger, strict)]
+trait AbxLedger {
+    fn abx_credit(&self, #[abx(into)] cents: u32) -> u64;
+}
+
+fn main() {}
@@ -0,0 +1,19 @@
+#![deny(unexpected_cfgs)]
+
+use depabx::wrap;
+
+pub struct JobQueue;
+
+impl JobQueue {
+    pub fn enqueue(&self, job: &str) -> u64 {
+        job.len() as u64
+    }
+}
+
+// A crate that doesn't declare the feature `test_only` names
+#[wrap(JobQueue, fake(test_only = "fakes"))]
+trait AbxJobQueue {
+    fn abx_enqueue(&self, job: &str) -> u64;
+}
+
+fn main() {}
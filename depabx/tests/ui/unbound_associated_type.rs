@@ -0,0 +1,18 @@
+use depabx::wrap;
+
+pub struct SearchEngine;
+
+impl SearchEngine {
+    pub fn search(&self, _query: &str) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+// The impl can't know what `Output` is without an `#[abx(type Output = ...)]`
+#[wrap(SearchEngine)]
+trait AbxSearchEngine {
+    type Output;
+    fn abx_search(&self, query: &str) -> Self::Output;
+}
+
+fn main() {}
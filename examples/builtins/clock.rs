@@ -0,0 +1,42 @@
+use depabx::builtins::clock::{AbxClock, SystemClock};
+use std::time::{Duration, Instant};
+
+pub fn demo() {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .unwrap();
+    let clock = SystemClock;
+    let started = clock.abx_now();
+    println!(
+        "{:?}",
+        runtime.block_on(run(&clock, started, Duration::from_millis(10)))
+    );
+}
+
+// Waits out whatever is left of a cooldown that began at `started`, and returns how long that was
+async fn run<C: AbxClock>(clock: &C, started: Instant, cooldown: Duration) -> Duration {
+    let remaining = cooldown.saturating_sub(clock.abx_elapsed(started));
+    clock.abx_sleep(remaining).await;
+    remaining
+}
+
+#[cfg(test)]
+mod tests {
+    use super::run;
+    use depabx::builtins::clock::{AbxClock, TestClock};
+    use futures::executor::block_on;
+    use std::time::Duration;
+
+    #[test]
+    fn it_waits_out_the_rest_of_the_cooldown() {
+        let clock = TestClock::new();
+        let started = clock.abx_now();
+        clock.advance(Duration::from_secs(40));
+
+        let waited = block_on(run(&clock, started, Duration::from_secs(60)));
+
+        assert_eq!(waited, Duration::from_secs(20));
+        assert_eq!(clock.abx_elapsed(started), Duration::from_secs(60));
+    }
+}
@@ -0,0 +1,35 @@
+use depabx::builtins::console::AbxConsole;
+use std::io;
+
+// Asks for a name until it gets a non-empty one, then greets it. There's no demo, since it would wait for input on
+// the terminal, so only the tests call it.
+#[cfg_attr(not(test), allow(dead_code))]
+fn run<C: AbxConsole>(console: &C) -> io::Result<()> {
+    loop {
+        console.abx_print("Name: ");
+        match console.abx_read_line()? {
+            None => return Ok(()),
+            Some(name) if name.trim().is_empty() => console.abx_eprint("A name is required\n"),
+            Some(name) => {
+                console.abx_print(&format!("Hello, {}!\n", name.trim()));
+                return Ok(());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::run;
+    use depabx::builtins::console::ScriptedConsole;
+
+    #[test]
+    fn it_asks_again_until_it_gets_a_name() {
+        let console = ScriptedConsole::new(["", "  ada "]);
+
+        run(&console).unwrap();
+
+        assert_eq!(console.stdout(), "Name: Name: Hello, ada!\n");
+        assert_eq!(console.stderr(), "A name is required\n");
+    }
+}
@@ -0,0 +1,42 @@
+use depabx::builtins::env::AbxEnv;
+
+// Reads the port (defaulting it for child processes if unset) and the names of the `FLAG_` variables that are on.
+// There's no demo, since it would change the process environment, so only the tests call it.
+#[cfg_attr(not(test), allow(dead_code))]
+fn run<E: AbxEnv>(env: &E) -> (u16, Vec<String>) {
+    let port = env
+        .abx_get("PORT")
+        .and_then(|port| port.parse().ok())
+        .unwrap_or_else(|| {
+            env.abx_set("PORT", "8080");
+            8080
+        });
+    let flags = env
+        .abx_vars()
+        .into_iter()
+        .filter(|(_, value)| value == "1")
+        .filter_map(|(key, _)| key.strip_prefix("FLAG_").map(str::to_lowercase))
+        .collect();
+    (port, flags)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::run;
+    use depabx::builtins::env::{AbxEnv, MapEnv};
+
+    #[test]
+    fn it_reads_the_port_and_enabled_flags() {
+        let env = MapEnv::with_vars([
+            ("FLAG_BETA", "1"),
+            ("FLAG_DARK_MODE", "0"),
+            ("FLAG_AUDIT", "1"),
+        ]);
+
+        assert_eq!(
+            run(&env),
+            (8080, vec!["audit".to_string(), "beta".to_string()])
+        );
+        assert_eq!(env.abx_get("PORT"), Some("8080".to_string()));
+    }
+}
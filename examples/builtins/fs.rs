@@ -0,0 +1,34 @@
+use depabx::builtins::fs::AbxFs;
+use std::io;
+use std::path::Path;
+
+// Moves the log to `<log>.1` once it's grown past `limit` bytes, and says whether it did. There's no demo, since
+// it would touch the disk, so only the tests call it.
+#[cfg_attr(not(test), allow(dead_code))]
+fn run<F: AbxFs>(fs: &F, log: &Path, limit: u64) -> io::Result<bool> {
+    if fs.abx_metadata(log)?.len <= limit {
+        return Ok(false);
+    }
+    let mut rotated = log.as_os_str().to_owned();
+    rotated.push(".1");
+    fs.abx_write(Path::new(&rotated), &fs.abx_read(log)?)?;
+    fs.abx_remove(log)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::run;
+    use depabx::builtins::fs::{AbxFs, MemoryFs};
+    use std::{io::ErrorKind, path::Path};
+
+    #[test]
+    fn it_rotates_logs_over_the_limit() {
+        let fs = MemoryFs::with_files([("app.log", "0123456789")]);
+
+        assert!(run(&fs, Path::new("app.log"), 8).unwrap());
+        assert_eq!(fs.abx_read(Path::new("app.log.1")).unwrap(), b"0123456789");
+        let err = fs.abx_read(Path::new("app.log")).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+    }
+}
@@ -0,0 +1,49 @@
+use depabx::builtins::http::{AbxHttp, HttpError, Request};
+
+// Looks a user's name up by ID; an unknown user isn't an error. There's no demo, since it would send a request over
+// the network, so only the tests call it.
+#[cfg_attr(not(test), allow(dead_code))]
+async fn run<H: AbxHttp>(http: &H, base_url: &str, id: u32) -> Result<Option<String>, HttpError> {
+    let request =
+        Request::get(&format!("{base_url}/users/{id}/name")).header("Accept", "text/plain");
+    let response = http.abx_send(request).await?;
+    match response.status {
+        200 => Ok(Some(response.text())),
+        404 => Ok(None),
+        status => Err(HttpError(format!("unexpected status {status}"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::run;
+    use depabx::builtins::http::{CannedHttp, Method, Response};
+    use futures::executor::block_on;
+
+    #[test]
+    fn it_treats_unknown_users_as_missing() {
+        let http = CannedHttp::new()
+            .on(
+                Method::Get,
+                "https://api.test/users/1/name",
+                Response::new(200, "Ada"),
+            )
+            .on(
+                Method::Get,
+                "https://api.test/users/2/name",
+                Response::new(404, ""),
+            );
+
+        assert_eq!(
+            block_on(run(&http, "https://api.test", 1)),
+            Ok(Some("Ada".to_string()))
+        );
+        assert_eq!(block_on(run(&http, "https://api.test", 2)), Ok(None));
+        let requests = http.requests();
+        assert_eq!(requests[1].url, "https://api.test/users/2/name");
+        assert_eq!(
+            requests[1].headers,
+            vec![("Accept".to_string(), "text/plain".to_string())]
+        );
+    }
+}
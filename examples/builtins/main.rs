@@ -0,0 +1,22 @@
+// How code uses the abstractions in `depabx::builtins`, so that its tests can swap in their test doubles.
+//
+// Each module's `demo` wires up the real implementation the way `main` does in the sample, and its tests run with
+// `cargo test`. Modules whose real dependency has side effects, like writing files or running commands, have no demo,
+// so running the example doesn't touch the machine it runs on.
+mod clock;
+mod console;
+mod env;
+mod fs;
+mod http;
+mod process;
+mod rng;
+mod signals;
+mod tcp;
+mod timer;
+
+fn main() {
+    clock::demo();
+    rng::demo();
+    signals::demo();
+    timer::demo();
+}
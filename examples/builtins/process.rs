@@ -0,0 +1,44 @@
+use depabx::builtins::process::AbxProcess;
+
+// Describes the checked out commit, or says why it couldn't. There's no demo, since it would run git, so only the
+// tests call it.
+#[cfg_attr(not(test), allow(dead_code))]
+fn run<P: AbxProcess>(process: &P) -> Result<String, String> {
+    let output = process
+        .abx_output("git", &["rev-parse", "--short", "HEAD"])
+        .map_err(|err| format!("couldn't run git: {err}"))?;
+    match output.code {
+        Some(0) => Ok(String::from_utf8_lossy(&output.stdout).trim().to_string()),
+        _ => Err(String::from_utf8_lossy(&output.stderr).trim().to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::run;
+    use depabx::builtins::process::{CommandOutput, ScriptedProcess};
+
+    #[test]
+    fn it_describes_the_checked_out_commit() {
+        let process = ScriptedProcess::new().on(
+            "git rev-parse --short HEAD",
+            CommandOutput::success("ea5084f\n"),
+        );
+
+        assert_eq!(run(&process), Ok("ea5084f".to_string()));
+    }
+
+    #[test]
+    fn it_reports_why_git_failed() {
+        let process = ScriptedProcess::new().on(
+            "git rev-parse --short HEAD",
+            CommandOutput::failure(128, "fatal: not a git repository\n"),
+        );
+
+        assert_eq!(
+            run(&process),
+            Err("fatal: not a git repository".to_string())
+        );
+        assert_eq!(process.commands(), vec!["git rev-parse --short HEAD"]);
+    }
+}
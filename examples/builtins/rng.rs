@@ -0,0 +1,43 @@
+use depabx::builtins::rng::{AbxRng, SystemRng};
+
+pub fn demo() {
+    let mut reviewers = ["ada", "alan", "grace"];
+    println!("{}", run(&SystemRng, &mut reviewers));
+    println!("{reviewers:?}");
+}
+
+// Puts the reviewers in a random order, and returns an 8 character invite code for them
+fn run<R: AbxRng>(rng: &R, reviewers: &mut [&str]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+    rng.abx_shuffle(reviewers);
+    (0..8)
+        .map(|_| ALPHABET[rng.abx_range(0, ALPHABET.len() as u64) as usize] as char)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::run;
+    use depabx::builtins::rng::{ScriptedRng, SeededRng};
+
+    #[test]
+    fn it_is_reproducible_for_a_seed() {
+        let mut first = ["ada", "alan", "grace", "linus"];
+        let mut second = first;
+
+        let code = run(&SeededRng::new(42), &mut first);
+
+        assert_eq!(code.len(), 8);
+        assert_eq!(run(&SeededRng::new(42), &mut second), code);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn it_builds_the_code_from_the_numbers_drawn() {
+        let mut reviewers = ["ada", "alan", "grace"];
+        let rng = ScriptedRng::new([0, 1, 2, 3, 24, 25, 26, 27]);
+
+        assert_eq!(run(&rng, &mut reviewers), "ABCD2345");
+        assert_eq!(reviewers, ["ada", "alan", "grace"]);
+    }
+}
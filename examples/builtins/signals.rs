@@ -0,0 +1,43 @@
+use depabx::builtins::signals::{AbxSignals, OsSignals};
+use std::io;
+
+pub fn demo() {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    // The sleep is made inside the runtime, whose timer it registers with
+    let serve = async { tokio::time::sleep(std::time::Duration::from_millis(10)).await };
+    println!("{:?}", runtime.block_on(run(&OsSignals, serve)));
+}
+
+// Serves until the server finishes or the process is asked to stop, and says which it was
+async fn run<S: AbxSignals>(
+    signals: &S,
+    serve: impl std::future::Future<Output = ()>,
+) -> io::Result<String> {
+    tokio::select! {
+        () = serve => Ok("server exited".to_string()),
+        signal = signals.abx_wait() => Ok(format!("stopped by {:?}, connections drained", signal?)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::run;
+    use depabx::builtins::signals::{Signal, TestSignals};
+
+    #[tokio::test]
+    async fn it_shuts_down_when_interrupted() {
+        let signals = TestSignals::new();
+
+        let (stopped, ()) = tokio::join!(run(&signals, std::future::pending()), async {
+            signals.trigger(Signal::Interrupt);
+        });
+
+        assert_eq!(
+            stopped.unwrap(),
+            "stopped by Interrupt, connections drained"
+        );
+    }
+}
@@ -0,0 +1,31 @@
+use depabx::builtins::tcp::{AbxConnection, AbxTcp};
+use std::io;
+
+// Pings a Redis-style server, and says whether it answered. There's no demo, since it would connect to a server,
+// so only the tests call it.
+#[cfg_attr(not(test), allow(dead_code))]
+fn run<T: AbxTcp>(tcp: &T, addr: &str) -> io::Result<bool> {
+    let mut connection = tcp.abx_connect(addr)?;
+    connection.abx_write(b"PING\r\n")?;
+    let mut reply = [0; 7];
+    let read = connection.abx_read(&mut reply)?;
+    Ok(&reply[..read] == b"+PONG\r\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::run;
+    use depabx::builtins::tcp::{AbxConnection, MemoryTcp};
+
+    #[test]
+    fn it_pings_the_server() {
+        let tcp = MemoryTcp::new();
+        let mut server = tcp.listen("cache:6379");
+        server.abx_write(b"+PONG\r\n").unwrap();
+
+        assert!(run(&tcp, "cache:6379").unwrap());
+        let mut request = [0; 16];
+        let read = server.abx_read(&mut request).unwrap();
+        assert_eq!(&request[..read], b"PING\r\n");
+    }
+}
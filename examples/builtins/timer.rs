@@ -0,0 +1,58 @@
+use depabx::builtins::timer::{AbxTimer, TokioTimer};
+use std::time::Duration;
+
+pub fn demo() {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .unwrap();
+    let result: Result<&str, &str> = runtime.block_on(run(&TokioTimer, || Ok("connected")));
+    println!("{result:?}");
+}
+
+// Tries `attempt` up to four times, doubling the wait between tries from 100ms
+async fn run<T: AbxTimer, V, E>(
+    timer: &T,
+    mut attempt: impl FnMut() -> Result<V, E>,
+) -> Result<V, E> {
+    let mut backoff = Duration::from_millis(100);
+    for _ in 1..4 {
+        match attempt() {
+            Ok(value) => return Ok(value),
+            Err(_) => {
+                timer.abx_sleep(backoff).await;
+                backoff *= 2;
+            }
+        }
+    }
+    attempt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::run;
+    use depabx::builtins::timer::{AbxTimer, TestTimer};
+    use std::time::Duration;
+
+    #[tokio::test(start_paused = true)]
+    async fn it_backs_off_exponentially_between_attempts() {
+        let timer = TestTimer::new();
+        let started = timer.abx_now();
+        let mut failures = 3;
+
+        let result = run(&timer, || {
+            failures -= 1;
+            if failures < 0 {
+                Ok("connected")
+            } else {
+                Err("refused")
+            }
+        })
+        .await;
+
+        assert_eq!(result, Ok("connected"));
+        let millis = |ms| Duration::from_millis(ms);
+        assert_eq!(timer.sleeps(), vec![millis(100), millis(200), millis(400)]);
+        assert_eq!(started.elapsed(), millis(700));
+    }
+}
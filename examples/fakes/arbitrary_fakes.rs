@@ -0,0 +1,56 @@
+use depabx::wrap;
+use pricing::Pricing;
+
+// A pricing service that sets the discount for each product. Nothing stops it from answering with more than 100%.
+mod pricing {
+    pub struct Pricing;
+
+    impl Pricing {
+        pub fn discount_percent(&self, sku: &str) -> u8 {
+            if sku.starts_with("sale-") {
+                30
+            } else {
+                0
+            }
+        }
+    }
+}
+
+// `fake(arbitrary)` generates `ArbitraryPricing`, whose methods answer with values drawn from the return types'
+// `proptest::arbitrary::Arbitrary` impls. It implements `Arbitrary` itself, so `any::<ArbitraryPricing>()` yields a
+// fake per test case and proptest can replay a failing case. Outside proptest, `ArbitraryPricing::from_seed(seed)`
+// gives the same answers for the same seed. It's only compiled for tests, with the crate's own `proptest`
+// dev-dependency. Combined with `fake(test_only)`, it's gated like the fake, so a library's dependents get it through
+// the feature too; the library then needs proptest as an optional dependency the feature turns on, as in
+// `test-doubles = ["dep:proptest"]`.
+#[wrap(Pricing, fake(arbitrary))]
+trait AbxPricing {
+    fn abx_discount_percent(&self, sku: &str) -> u8;
+}
+
+pub fn demo() {
+    println!("{}", run(&Pricing {}, "sale-shoes", 5000));
+}
+
+// Applies the discount to a price in cents, treating anything over 100% as free
+fn run<P: AbxPricing>(pricing: &P, sku: &str, cents: u32) -> u32 {
+    let percent = u64::from(pricing.abx_discount_percent(sku).min(100));
+    let discount = u64::from(cents) * percent / 100;
+    cents - discount as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, ArbitraryPricing};
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn it_never_charges_more_than_the_price(
+            pricing in any::<ArbitraryPricing>(),
+            cents in any::<u32>(),
+        ) {
+            prop_assert!(run(&pricing, "sale-shoes", cents) <= cents);
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use depabx::wrap;
+use mailer::{Attachment, Mailer};
+
+// A mailer whose attachments hold an open file handle, so they can't be cloned.
+mod mailer {
+    #[derive(Debug)]
+    pub struct Attachment {
+        pub name: String,
+        pub handle: u32,
+    }
+
+    pub struct Mailer;
+
+    impl Mailer {
+        pub fn send(&self, to: &str, subject: &str, attachment: Attachment) {
+            println!(
+                "{to}: {subject} ({}, fd {})",
+                attachment.name, attachment.handle
+            );
+        }
+    }
+}
+
+// Generated fakes also keep each call's arguments with their types. `captured_send()` returns one entry per call:
+// the argument itself for a single argument, a tuple for several. Borrowed arguments are stored owned via `ToOwned`,
+// and other arguments must be `Clone`, unless marked `#[abx(capture = "debug")]` to be stored as their `{:?}` string.
+#[wrap(Mailer, fake)]
+trait AbxMailer {
+    fn abx_send(&self, to: &str, subject: &str, #[abx(capture = "debug")] attachment: Attachment);
+}
+
+pub fn demo() {
+    run(&Mailer {}, &["ada@example.com", "alan@example.com"]);
+}
+
+fn run<M: AbxMailer>(mailer: &M, recipients: &[&str]) {
+    for (handle, to) in (3..).zip(recipients) {
+        let attachment = Attachment {
+            name: "report.pdf".to_string(),
+            handle,
+        };
+        mailer.abx_send(to, "Monthly report", attachment);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, FakeMailer};
+
+    #[test]
+    fn it_sends_the_report_to_each_recipient() {
+        let mailer = FakeMailer::new();
+
+        run(&mailer, &["ada@example.com", "alan@example.com"]);
+
+        let sent = mailer.captured_send();
+        assert_eq!(sent.len(), 2);
+        let (to, subject, attachment) = &sent[1];
+        assert_eq!(to, "alan@example.com");
+        assert_eq!(subject, "Monthly report");
+        assert!(attachment.contains("handle: 4"));
+    }
+}
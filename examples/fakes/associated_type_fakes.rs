@@ -0,0 +1,56 @@
+use depabx::wrap;
+use parser::{Document, Parser};
+
+// A parser with its own document type, which would be a pain to construct in tests.
+mod parser {
+    pub struct Document {
+        pub nodes: Vec<String>,
+    }
+
+    impl std::fmt::Display for Document {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{}", self.nodes.join(" > "))
+        }
+    }
+
+    pub struct Parser;
+
+    impl Parser {
+        pub fn parse(&self, source: &str) -> Document {
+            Document {
+                nodes: source.split('/').map(String::from).collect(),
+            }
+        }
+    }
+}
+
+// The real impl binds `Output` to `Document` through `#[abx(type ...)]`. The fake can't use that type without building
+// real documents, so `fake(type Output = String)` gives it its own binding. Anything meeting the bounds will do, and
+// queued returns then have that type.
+#[wrap(Parser, fake(type Output = String))]
+#[abx(type Output = Document)]
+trait AbxParser {
+    type Output: std::fmt::Display;
+    fn abx_parse(&self, source: &str) -> Self::Output;
+}
+
+pub fn demo() {
+    println!("{}", run(&Parser {}, "html/body/div"));
+}
+
+fn run<P: AbxParser>(parser: &P, source: &str) -> String {
+    format!("parsed: {}", parser.abx_parse(source))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, FakeParser};
+
+    #[test]
+    fn it_describes_the_parsed_document() {
+        let parser = FakeParser::new();
+        parser.returns_for_parse(["root".to_string()]);
+
+        assert_eq!(run(&parser, "anything"), "parsed: root");
+    }
+}
@@ -0,0 +1,56 @@
+use depabx::wrap;
+use prices::PriceFeed;
+
+// An async price feed.
+mod prices {
+    pub struct PriceFeed;
+
+    impl PriceFeed {
+        pub async fn quote(&self, symbol: &str) -> f64 {
+            if symbol == "ACME" {
+                12.5
+            } else {
+                1.0
+            }
+        }
+    }
+}
+
+// Fakes of async methods hand back futures. A queued value resolves as soon as it's polled, so `.await` points in the
+// code under test run without any executor tricks. `hold_quote()` returns a `depabx::Gate`: until the gate is
+// released, calls to `abx_quote` stay pending, which lets tests observe what the code does while it waits.
+#[wrap(PriceFeed, fake)]
+trait AbxPriceFeed {
+    async fn abx_quote(&self, symbol: &str) -> f64;
+}
+
+pub fn demo() {
+    println!("{}", futures::executor::block_on(run(&PriceFeed {}, 10)));
+}
+
+async fn run<F: AbxPriceFeed>(feed: &F, shares: u32) -> f64 {
+    feed.abx_quote("ACME").await * shares as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, FakePriceFeed};
+    use futures::{executor::block_on, poll};
+    use std::pin::pin;
+
+    #[test]
+    fn it_waits_for_the_quote_before_valuing_shares() {
+        let feed = FakePriceFeed::new();
+        feed.returns_for_quote([2.0]);
+        let gate = feed.hold_quote();
+
+        block_on(async {
+            let mut valuation = pin!(run(&feed, 3));
+            assert!(poll!(valuation.as_mut()).is_pending());
+
+            gate.release();
+
+            assert_eq!(valuation.await, 6.0);
+        });
+    }
+}
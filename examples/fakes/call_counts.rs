@@ -0,0 +1,55 @@
+use depabx::wrap;
+use tracker::Tracker;
+
+// An analytics tracker.
+mod tracker {
+    pub struct Tracker;
+
+    impl Tracker {
+        pub fn page_view(&self, path: &str) {
+            println!("view {path}");
+        }
+
+        pub fn conversion(&self, value_cents: u64) {
+            println!("conversion worth {value_cents}");
+        }
+    }
+}
+
+// Generated fakes can be queried per method, either by name with `calls_of("abx_page_view")` or through a typed
+// accessor like `page_view_calls()`, which can't be misspelled. Both return a `depabx::Calls` with `len()` and
+// assertions such as `assert_called_once()`, `assert_called_times(n)` and `assert_not_called()`, whose panic messages
+// list the calls that were actually recorded.
+#[wrap(Tracker, fake)]
+trait AbxTracker {
+    fn abx_page_view(&self, path: &str);
+    fn abx_conversion(&self, value_cents: u64);
+}
+
+pub fn demo() {
+    run(&Tracker {}, &["/", "/pricing", "/checkout"], Some(4_900));
+}
+
+fn run<T: AbxTracker>(tracker: &T, paths: &[&str], order_total: Option<u64>) {
+    for path in paths {
+        tracker.abx_page_view(path);
+    }
+    if let Some(total) = order_total {
+        tracker.abx_conversion(total);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, FakeTracker};
+
+    #[test]
+    fn it_tracks_each_page_but_no_conversion_without_an_order() {
+        let tracker = FakeTracker::new();
+
+        run(&tracker, &["/", "/a", "/b"], None);
+
+        tracker.calls_of("abx_page_view").assert_called_times(3);
+        tracker.conversion_calls().assert_not_called();
+    }
+}
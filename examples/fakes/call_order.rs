@@ -0,0 +1,64 @@
+use depabx::wrap;
+use deps::{AuditLog, PaymentApi};
+
+// Two separate dependencies whose relative order matters: the audit entry has to be written before money moves.
+mod deps {
+    pub struct AuditLog;
+    pub struct PaymentApi;
+
+    impl AuditLog {
+        pub fn append(&self, entry: &str) {
+            println!("audit: {entry}");
+        }
+    }
+
+    impl PaymentApi {
+        pub fn transfer(&self, cents: u64) -> bool {
+            cents > 0
+        }
+    }
+}
+
+// Every generated fake stamps its calls from one shared sequence, so calls on different fakes can be ordered against
+// each other. `depabx::verify_sequence!` checks that the listed calls happened in that order. Arguments are compared by
+// their recorded `Debug` form, or left out with `..` for any arguments; other calls in between are ignored.
+#[wrap(AuditLog, fake)]
+trait AbxAuditLog {
+    fn abx_append(&self, entry: &str);
+}
+
+#[wrap(PaymentApi, fake)]
+trait AbxPaymentApi {
+    fn abx_transfer(&self, cents: u64) -> bool;
+}
+
+pub fn demo() {
+    println!("{}", run(&AuditLog {}, &PaymentApi {}, 2_500));
+}
+
+fn run<A: AbxAuditLog, P: AbxPaymentApi>(audit: &A, payments: &P, cents: u64) -> bool {
+    audit.abx_append(&format!("transfer {cents}"));
+    let ok = payments.abx_transfer(cents);
+    audit.abx_append(if ok { "transfer ok" } else { "transfer failed" });
+    ok
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, FakeAuditLog, FakePaymentApi};
+    use depabx::verify_sequence;
+
+    #[test]
+    fn it_audits_before_transferring() {
+        let audit = FakeAuditLog::new();
+        let payments = FakePaymentApi::new();
+        payments.returns_for_transfer([true]);
+
+        assert!(run(&audit, &payments, 100));
+        verify_sequence![
+            audit.abx_append("transfer 100"),
+            payments.abx_transfer(..),
+            audit.abx_append("transfer ok"),
+        ];
+    }
+}
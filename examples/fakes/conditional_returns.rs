@@ -0,0 +1,75 @@
+use depabx::wrap;
+use users::{User, UserError, Users};
+
+// A user directory, keyed by user ID.
+mod users {
+    #[derive(Clone)]
+    pub struct User {
+        pub name: String,
+        pub admin: bool,
+    }
+
+    #[derive(Clone)]
+    pub enum UserError {
+        NotFound,
+    }
+
+    pub struct Users;
+
+    impl Users {
+        pub fn get(&self, id: &str) -> Result<User, UserError> {
+            match id {
+                "root" => Ok(User {
+                    name: "Root".to_string(),
+                    admin: true,
+                }),
+                _ => Err(UserError::NotFound),
+            }
+        }
+    }
+}
+
+// `when_get(matcher).returns(value)` answers every matching call to `abx_get` with a clone of `value`, for as long as
+// the fake lives. Unlike queued returns it isn't used up, so one fake can stand in for a whole keyed dependency.
+// Conditions are tried in the order they were added; queued returns still take precedence over them.
+#[wrap(Users, fake)]
+trait AbxUsers {
+    fn abx_get(&self, id: &str) -> Result<User, UserError>;
+}
+
+pub fn demo() {
+    println!("{}", run(&Users {}, "root"));
+}
+
+fn run<U: AbxUsers>(users: &U, id: &str) -> String {
+    match users.abx_get(id) {
+        Ok(User { name, admin: true }) => format!("{name} (admin)"),
+        Ok(User { name, .. }) => name,
+        Err(UserError::NotFound) => "anonymous".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, FakeUsers, User, UserError};
+    use depabx::matchers::{any, eq};
+
+    #[test]
+    fn it_tells_admins_members_and_strangers_apart() {
+        let users = FakeUsers::new();
+        let user = |name: &str, admin| User {
+            name: name.to_string(),
+            admin,
+        };
+        users.when_get(eq("user-1")).returns(Ok(user("Ada", true)));
+        users
+            .when_get(eq("user-2"))
+            .returns(Ok(user("Alan", false)));
+        users.when_get(any()).returns(Err(UserError::NotFound));
+
+        assert_eq!(run(&users, "user-1"), "Ada (admin)");
+        assert_eq!(run(&users, "user-2"), "Alan");
+        assert_eq!(run(&users, "user-3"), "anonymous");
+        assert_eq!(run(&users, "user-1"), "Ada (admin)");
+    }
+}
@@ -0,0 +1,56 @@
+use cache::Cache;
+use depabx::wrap;
+
+// A key-value cache.
+mod cache {
+    pub struct Cache;
+
+    impl Cache {
+        pub fn get(&self, key: &str) -> Option<String> {
+            (key == "greeting").then(|| "hello".to_string())
+        }
+
+        pub fn set(&self, key: &str, value: &str) {
+            println!("{key} = {value}");
+        }
+    }
+}
+
+// Generated fakes implement `Debug`, listing the calls they recorded and what's still queued, e.g.
+//
+//     FakeCache { calls: [abx_get("greeting"), abx_set("greeting", "hi")], queued: {abx_get: 1} }
+//
+// so a failing assertion can print the whole fake with `{fake:?}`. Queued values are shown as counts, so their types
+// don't need to be `Debug`.
+#[wrap(Cache, fake)]
+trait AbxCache {
+    fn abx_get(&self, key: &str) -> Option<String>;
+    fn abx_set(&self, key: &str, value: &str);
+}
+
+pub fn demo() {
+    println!("{}", run(&Cache {}, "greeting", || "hi".to_string()));
+}
+
+// Reads through the cache, filling it on a miss
+fn run<C: AbxCache>(cache: &C, key: &str, load: impl FnOnce() -> String) -> String {
+    cache.abx_get(key).unwrap_or_else(|| {
+        let value = load();
+        cache.abx_set(key, &value);
+        value
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, FakeCache};
+
+    #[test]
+    fn it_fills_the_cache_on_a_miss() {
+        let cache = FakeCache::new();
+        cache.returns_for_get([None]);
+
+        assert_eq!(run(&cache, "greeting", || "hi".to_string()), "hi");
+        assert!(cache.set_calls().len() == 1, "{cache:?}");
+    }
+}
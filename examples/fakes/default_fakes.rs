@@ -0,0 +1,69 @@
+use depabx::wrap;
+use services::{Billing, Notifications};
+
+// Two dependencies that a piece of business logic always receives together.
+mod services {
+    pub struct Billing;
+    pub struct Notifications;
+
+    impl Billing {
+        pub fn invoice(&self, customer: &str, cents: u64) -> String {
+            format!("inv-{customer}-{cents}")
+        }
+    }
+
+    impl Notifications {
+        pub fn notify(&self, customer: &str, message: &str) {
+            println!("{customer}: {message}");
+        }
+    }
+}
+
+// Every generated fake implements `Default`, equivalent to `new()`: no recorded calls and nothing configured. Groups of
+// fakes can then derive `Default` themselves, which keeps test setup to a single line.
+#[wrap(Billing, fake)]
+trait AbxBilling {
+    fn abx_invoice(&self, customer: &str, cents: u64) -> String;
+}
+
+#[wrap(Notifications, fake)]
+trait AbxNotifications {
+    fn abx_notify(&self, customer: &str, message: &str);
+}
+
+pub fn demo() {
+    println!("{}", run(&Billing {}, &Notifications {}, "acme"));
+}
+
+fn run<B: AbxBilling, N: AbxNotifications>(
+    billing: &B,
+    notifications: &N,
+    customer: &str,
+) -> String {
+    let invoice = billing.abx_invoice(customer, 12_000);
+    notifications.abx_notify(customer, &format!("Invoice {invoice} is ready"));
+    invoice
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, FakeBilling, FakeNotifications};
+
+    #[derive(Default)]
+    struct Fixture {
+        billing: FakeBilling,
+        notifications: FakeNotifications,
+    }
+
+    #[test]
+    fn it_tells_the_customer_about_the_invoice() {
+        let fx = Fixture::default();
+        fx.billing.returns_for_invoice(["inv-1".to_string()]);
+
+        assert_eq!(run(&fx.billing, &fx.notifications, "acme"), "inv-1");
+        assert_eq!(
+            fx.notifications.calls()[0].args,
+            vec!["\"acme\"", "\"Invoice inv-1 is ready\""]
+        );
+    }
+}
@@ -0,0 +1,54 @@
+use depabx::wrap;
+use mailer::Mailer;
+
+// A transactional mail service.
+mod mailer {
+    pub struct Mailer;
+
+    impl Mailer {
+        pub fn send_template(&self, to: &str, template: &str) {
+            println!("{template} -> {to}");
+        }
+    }
+}
+
+// Expectations are declared before the code runs: `expect_send_template()` returns a builder taking `.times(n)` and
+// `.with(matchers)`, as with expectations in mockall. `checkpoint()` checks them and clears them, and whatever is left
+// is checked when the fake is dropped, panicking with each expectation that wasn't met. Calls that match no
+// expectation are recorded as usual.
+#[wrap(Mailer, fake)]
+trait AbxMailer {
+    fn abx_send_template(&self, to: &str, template: &str);
+}
+
+pub fn demo() {
+    run(&Mailer {}, &["a@example.com", "b@example.com"]);
+}
+
+fn run<M: AbxMailer>(mailer: &M, new_users: &[&str]) {
+    for user in new_users {
+        mailer.abx_send_template(user, "welcome");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, FakeMailer};
+    use depabx::matchers::{any, eq};
+
+    #[test]
+    fn it_welcomes_each_new_user() {
+        let mailer = FakeMailer::new();
+        mailer
+            .expect_send_template()
+            .with((any(), eq("welcome")))
+            .times(2);
+
+        run(&mailer, &["x@example.com", "y@example.com"]);
+        mailer.checkpoint();
+
+        // The checkpoint cleared the first expectation; this one is checked when `mailer` is dropped
+        mailer.expect_send_template().times(1);
+        run(&mailer, &["z@example.com"]);
+    }
+}
@@ -0,0 +1,73 @@
+use depabx::wrap;
+use storage::{Storage, StorageError};
+
+// A blob store whose every operation can fail.
+mod storage {
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum StorageError {
+        // Only the fake in the tests times out
+        #[cfg_attr(not(test), allow(dead_code))]
+        Timeout,
+        NotFound,
+    }
+
+    pub struct Storage;
+
+    impl Storage {
+        pub fn read(&self, key: &str) -> Result<Vec<u8>, StorageError> {
+            match key {
+                "" => Err(StorageError::NotFound),
+                _ => Ok(key.as_bytes().to_vec()),
+            }
+        }
+
+        pub fn write(&self, key: &str, data: &[u8]) -> Result<(), StorageError> {
+            println!("wrote {} bytes to {key}", data.len());
+            Ok(())
+        }
+    }
+}
+
+// `fail_all_with(error)` makes every `Result`-returning method of a generated fake return a clone of `error` (converted
+// with `From` if the method's error type differs), ahead of anything queued. It covers the error paths of the code
+// under test without queueing a failure per method; methods not returning a `Result` are unaffected. A module's alias
+// like `io::Result<T>` counts too, failing with that module's `Error`, but other aliases of `Result` are left be.
+#[wrap(Storage, fake)]
+trait AbxStorage {
+    fn abx_read(&self, key: &str) -> Result<Vec<u8>, StorageError>;
+    fn abx_write(&self, key: &str, data: &[u8]) -> Result<(), StorageError>;
+}
+
+pub fn demo() {
+    println!("{}", run(&Storage {}, "config"));
+}
+
+// Copies a blob to a backup key, describing what happened
+fn run<S: AbxStorage>(storage: &S, key: &str) -> String {
+    let backup = format!("{key}.bak");
+    match storage
+        .abx_read(key)
+        .and_then(|data| storage.abx_write(&backup, &data))
+    {
+        Ok(()) => format!("backed up {key}"),
+        Err(StorageError::Timeout) => format!("storage timed out, {key} not backed up"),
+        Err(StorageError::NotFound) => format!("{key} doesn't exist"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, FakeStorage, StorageError};
+
+    #[test]
+    fn it_reports_timeouts() {
+        let storage = FakeStorage::new();
+        storage.fail_all_with(StorageError::Timeout);
+
+        assert_eq!(
+            run(&storage, "config"),
+            "storage timed out, config not backed up"
+        );
+        storage.write_calls().assert_not_called();
+    }
+}
@@ -0,0 +1,56 @@
+use depabx::wrap;
+use logger::Logger;
+use std::fmt::{Debug, Display};
+
+// The sample's logger, generic methods and all.
+mod logger {
+    use std::fmt::{Debug, Display};
+
+    pub struct Logger;
+
+    impl Logger {
+        pub fn log_event<T: Display>(&self, description: T) {
+            println!("Event: {description}");
+        }
+
+        pub fn log_value<K: Display, V: Debug>(&self, key: K, value: V) {
+            println!("{key} = {value:?}");
+        }
+    }
+}
+
+// A generic argument has a different type on every call, so the fake can't store it as is. Instead it's recorded
+// through its bounds: with `to_string()` if it's `Display`, with `{:?}` if it's `Debug`, and as its type name if it's
+// neither. The recorded calls then look the same as for non-generic methods.
+#[wrap(Logger, fake)]
+trait AbxLogger {
+    fn abx_log_event<T: Display>(&self, description: T);
+    fn abx_log_value<K: Display, V: Debug>(&self, key: K, value: V);
+}
+
+pub fn demo() {
+    run(&Logger {});
+}
+
+fn run<L: AbxLogger>(logger: &L) {
+    logger.abx_log_event("Some event description");
+    logger.abx_log_event(404);
+    logger.abx_log_value("retries", Some(3));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, FakeLogger};
+
+    #[test]
+    fn it_logs_events_of_any_displayable_type() {
+        let logger = FakeLogger::new();
+
+        run(&logger);
+
+        let calls = logger.calls();
+        assert_eq!(calls[0].args, vec!["Some event description"]);
+        assert_eq!(calls[1].args, vec!["404"]);
+        assert_eq!(calls[2].args, vec!["retries", "Some(3)"]);
+    }
+}
@@ -0,0 +1,69 @@
+use depabx::wrap;
+use sessions::{Session, SessionStore};
+
+// A session store backed by a remote cache.
+mod sessions {
+    #[derive(Clone)]
+    pub struct Session {
+        pub user: String,
+    }
+
+    pub struct SessionStore;
+
+    impl SessionStore {
+        pub fn get(&self, id: &str) -> Option<Session> {
+            println!("GET session:{id}");
+            None
+        }
+
+        pub fn put(&self, id: &str, session: Session) {
+            println!("SET session:{id} {}", session.user);
+        }
+
+        pub fn expire(&self, id: &str) {
+            println!("DEL session:{id}");
+        }
+    }
+}
+
+// `store` generates `InMemorySessionStore`, a fake that keeps state in a `HashMap` so reads see earlier writes. Each
+// method's role comes from its name (`get`/`put`/`delete`, plus aliases like `insert` and `remove`) or, as for
+// `abx_expire`, from `#[abx(store = "...")]`. The first argument is the key, converted to an owned key with
+// `ToOwned`, and `get` needs the value to be `Clone`. A method that fits no role is rejected at compile time.
+#[wrap(SessionStore, store)]
+trait AbxSessionStore {
+    fn abx_get(&self, id: &str) -> Option<Session>;
+    fn abx_put(&self, id: &str, session: Session);
+    #[abx(store = "delete")]
+    fn abx_expire(&self, id: &str);
+}
+
+pub fn demo() {
+    println!("{:?}", run(&SessionStore {}, "s-1", "ada"));
+}
+
+// Logs a user in and out again, returning who the session belonged to
+fn run<S: AbxSessionStore>(store: &S, id: &str, user: &str) -> Option<String> {
+    store.abx_put(
+        id,
+        Session {
+            user: user.to_string(),
+        },
+    );
+    let session = store.abx_get(id);
+    store.abx_expire(id);
+    session.map(|s| s.user)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxSessionStore, InMemorySessionStore};
+
+    #[test]
+    fn it_reads_back_the_session_it_stored_then_removes_it() {
+        let store = InMemorySessionStore::new();
+
+        assert_eq!(run(&store, "s-1", "ada"), Some("ada".to_string()));
+        assert!(store.abx_get("s-1").is_none());
+    }
+}
@@ -0,0 +1,64 @@
+use depabx::wrap;
+use inventory::Inventory;
+
+// A warehouse inventory service.
+mod inventory {
+    pub struct Inventory;
+
+    impl Inventory {
+        pub fn stock(&self, sku: &str) -> u32 {
+            sku.len() as u32
+        }
+
+        pub fn reserve(&self, sku: &str, quantity: u32) {
+            println!("reserved {quantity} x {sku}");
+        }
+
+        pub fn log_event(&self, description: &str) {
+            println!("Event: {description}");
+        }
+    }
+}
+
+// `depabx::fake!` builds a one-off double in place, for tests that don't want a named fake. It takes the trait's
+// methods as fields, each given a closure with the method's arguments, and expands to the trait's `stub` configured
+// with them, so the stub has to be in scope. Ending with `..` lets the other methods keep the stub's fallbacks; leaving
+// it off makes a missed method a compile error.
+#[wrap(Inventory, stub)]
+trait AbxInventory {
+    fn abx_stock(&self, sku: &str) -> u32;
+    fn abx_reserve(&self, sku: &str, quantity: u32);
+    fn abx_log_event(&self, description: &str);
+}
+
+pub fn demo() {
+    println!("{}", run(&Inventory {}, "widget", 3));
+}
+
+fn run<I: AbxInventory>(inventory: &I, sku: &str, quantity: u32) -> bool {
+    if inventory.abx_stock(sku) < quantity {
+        inventory.abx_log_event(&format!("Out of stock: {sku}"));
+        return false;
+    }
+    inventory.abx_reserve(sku, quantity);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, StubInventory};
+    use std::cell::RefCell;
+
+    #[test]
+    fn it_logs_when_theres_not_enough_stock() {
+        let events = RefCell::new(Vec::new());
+        let inventory = depabx::fake!(AbxInventory {
+            abx_stock: |_| 1,
+            abx_log_event: |d| events.borrow_mut().push(d.to_string()),
+            ..
+        });
+
+        assert!(!run(&inventory, "gadget", 2));
+        assert_eq!(*events.borrow(), vec!["Out of stock: gadget"]);
+    }
+}
@@ -0,0 +1,52 @@
+use depabx::wrap;
+use prices::PriceFeed;
+use std::time::Duration;
+
+// An async price feed that can be slow to answer.
+mod prices {
+    pub struct PriceFeed;
+
+    impl PriceFeed {
+        pub async fn quote(&self, symbol: &str) -> f64 {
+            symbol.len() as f64
+        }
+    }
+}
+
+// `with_latency(duration)` makes every async method of a generated fake wait that long before answering. With
+// depabx's `tokio` feature the wait is a `tokio::time::sleep`, so under paused time it ends as soon as the runtime
+// auto-advances the clock: timeouts and races get tested without actually waiting.
+#[wrap(PriceFeed, fake)]
+trait AbxPriceFeed {
+    async fn abx_quote(&self, symbol: &str) -> f64;
+}
+
+pub fn demo() {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .unwrap();
+    println!("{}", runtime.block_on(run(&PriceFeed {}, "ACME")));
+}
+
+// Falls back to the last known price if the feed takes longer than 100ms
+async fn run<F: AbxPriceFeed>(feed: &F, symbol: &str) -> f64 {
+    let last_known = 10.0;
+    tokio::time::timeout(Duration::from_millis(100), feed.abx_quote(symbol))
+        .await
+        .unwrap_or(last_known)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, FakePriceFeed};
+    use std::time::Duration;
+
+    #[tokio::test(start_paused = true)]
+    async fn it_falls_back_when_the_feed_is_slow() {
+        let feed = FakePriceFeed::new().with_latency(Duration::from_millis(250));
+        feed.returns_for_quote([12.5]);
+
+        assert_eq!(run(&feed, "ACME").await, 10.0);
+    }
+}
@@ -0,0 +1,68 @@
+// Examples of the test doubles `#[wrap]` can generate alongside the delegating impl, so tests don't have to hand-roll
+// fakes like the one in the sample.
+//
+// Each module's `demo` wires up its real dependency the way `main` does in the sample, and its tests run with
+// `cargo test`.
+mod arbitrary_fakes;
+mod argument_capture;
+mod associated_type_fakes;
+mod async_fakes;
+mod call_counts;
+mod call_order;
+mod conditional_returns;
+mod debug_output;
+mod default_fakes;
+mod expectations;
+mod failure_injection;
+mod generic_method_fakes;
+mod in_memory_stores;
+mod inline_fakes;
+mod latency;
+mod matchers;
+mod nice_stubs;
+mod null_objects;
+mod panic_messages;
+mod partial_fakes;
+mod queued_returns;
+mod recording_fake;
+mod registries;
+mod resettable_fakes;
+mod shared_fakes;
+mod spies;
+mod strict_stubs;
+mod stub_builder;
+mod sync_fakes;
+mod test_only_fakes;
+
+fn main() {
+    recording_fake::demo();
+    stub_builder::demo();
+    queued_returns::demo();
+    strict_stubs::demo();
+    nice_stubs::demo();
+    call_counts::demo();
+    matchers::demo();
+    call_order::demo();
+    sync_fakes::demo();
+    async_fakes::demo();
+    spies::demo();
+    partial_fakes::demo();
+    resettable_fakes::demo();
+    expectations::demo();
+    generic_method_fakes::demo();
+    default_fakes::demo();
+    associated_type_fakes::demo();
+    inline_fakes::demo();
+    failure_injection::demo();
+    latency::demo();
+    in_memory_stores::demo();
+    shared_fakes::demo();
+    argument_capture::demo();
+    panic_messages::demo();
+    debug_output::demo();
+    conditional_returns::demo();
+    null_objects::demo();
+    test_only_fakes::demo();
+    arbitrary_fakes::demo();
+    registries::demo();
+}
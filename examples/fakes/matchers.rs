@@ -0,0 +1,54 @@
+use depabx::wrap;
+use sms::SmsGateway;
+
+// An SMS gateway. Sending can fail for some numbers.
+mod sms {
+    pub struct SmsGateway;
+
+    impl SmsGateway {
+        pub fn send(&self, number: &str, text: &str) -> bool {
+            println!("SMS to {number}: {text}");
+            !number.is_empty()
+        }
+    }
+}
+
+// Matchers from `depabx::matchers` describe the arguments a call should have: `eq(value)`, `any()`, or
+// `predicate(|arg| ...)`, with one matcher per argument (a tuple of them for several arguments). They narrow down the
+// recorded calls with `.matching(..)`, and `returns_for_send_when(..)` only answers calls that match.
+#[wrap(SmsGateway, fake)]
+trait AbxSmsGateway {
+    fn abx_send(&self, number: &str, text: &str) -> bool;
+}
+
+pub fn demo() {
+    println!("{:?}", run(&SmsGateway {}, &["+4712345678", "+4787654321"]));
+}
+
+// Alerts every on-call number, and returns the ones that couldn't be reached
+fn run<G: AbxSmsGateway>(gateway: &G, on_call: &[&str]) -> Vec<String> {
+    on_call
+        .iter()
+        .filter(|number| !gateway.abx_send(number, "Database is down"))
+        .map(|number| number.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, FakeSmsGateway};
+    use depabx::matchers::{any, eq, predicate};
+
+    #[test]
+    fn it_reports_numbers_that_failed() {
+        let gateway = FakeSmsGateway::new();
+        gateway.returns_for_send_when((eq("+1"), any()), [true]);
+        gateway.returns_for_send_when((eq("+2"), any()), [false]);
+
+        assert_eq!(run(&gateway, &["+1", "+2"]), vec!["+2"]);
+        gateway
+            .send_calls()
+            .matching((any(), predicate(|text: &str| text.contains("down"))))
+            .assert_called_times(2);
+    }
+}
@@ -0,0 +1,72 @@
+use depabx::wrap;
+use profile::{Profile, ProfileService};
+
+// A profile service with several lookups, most of which a given test doesn't care about.
+mod profile {
+    pub struct Profile {
+        pub display_name: String,
+    }
+
+    pub struct ProfileService;
+
+    impl ProfileService {
+        pub fn display_name(&self, id: u32) -> Option<String> {
+            Some(format!("user {id}"))
+        }
+
+        pub fn follower_count(&self, _id: u32) -> u64 {
+            1_200
+        }
+
+        pub fn badges(&self, _id: u32) -> Vec<String> {
+            vec!["early adopter".to_string()]
+        }
+
+        pub fn load(&self, id: u32) -> Profile {
+            Profile {
+                display_name: format!("user {id}"),
+            }
+        }
+    }
+}
+
+// `stub(nice)` is the opposite of `strict`: an unconfigured method returns `Default::default()`, so tests only set up
+// what they check. Since the macro can't tell which return types implement `Default`, a method whose type doesn't
+// is marked `#[abx(no_default)]` and keeps panicking when unconfigured.
+#[wrap(ProfileService, stub(nice))]
+trait AbxProfileService {
+    fn abx_display_name(&self, id: u32) -> Option<String>;
+    fn abx_follower_count(&self, id: u32) -> u64;
+    fn abx_badges(&self, id: u32) -> Vec<String>;
+    #[abx(no_default)]
+    fn abx_load(&self, id: u32) -> Profile;
+}
+
+pub fn demo() {
+    println!("{}", run(&ProfileService {}, 7));
+    println!("{}", ProfileService {}.abx_load(7).display_name);
+}
+
+fn run<P: AbxProfileService>(profiles: &P, id: u32) -> String {
+    let name = profiles
+        .abx_display_name(id)
+        .unwrap_or_else(|| "anonymous".to_string());
+    let badges = profiles.abx_badges(id).len();
+    format!(
+        "{name} ({} followers, {badges} badges)",
+        profiles.abx_follower_count(id)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, StubProfileService};
+
+    #[test]
+    fn it_falls_back_to_anonymous() {
+        // Followers and badges come back as 0 and an empty Vec without being configured
+        let profiles = StubProfileService::new().on_display_name(|_id| None);
+
+        assert_eq!(run(&profiles, 3), "anonymous (0 followers, 0 badges)");
+    }
+}
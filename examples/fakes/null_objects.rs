@@ -0,0 +1,50 @@
+use depabx::wrap;
+use tracing::Tracer;
+
+// A tracing exporter. Not every deployment runs a collector to send spans to.
+mod tracing {
+    pub struct Tracer;
+
+    impl Tracer {
+        pub fn start_span(&self, name: &str) -> u64 {
+            println!("span started: {name}");
+            1
+        }
+
+        pub fn end_span(&self, id: u64) {
+            println!("span ended: {id}");
+        }
+    }
+}
+
+// `noop` generates `NoopTracer`, a null object whose methods do nothing and return `Default::default()`. It isn't
+// limited to tests: it's also the dependency to wire in where a feature is switched off, or in benchmarks that
+// shouldn't measure it. It's a unit struct, so it costs nothing to create.
+#[wrap(Tracer, noop)]
+trait AbxTracer {
+    fn abx_start_span(&self, name: &str) -> u64;
+    fn abx_end_span(&self, id: u64);
+}
+
+pub fn demo() {
+    println!("{}", run(&Tracer {}, &[3, 4]));
+    // Without a collector, the same code runs untraced
+    println!("{}", run(&NoopTracer, &[3, 4]));
+}
+
+fn run<T: AbxTracer>(tracer: &T, values: &[u32]) -> u32 {
+    let span = tracer.abx_start_span("sum_of_squares");
+    let sum = values.iter().map(|v| v * v).sum();
+    tracer.abx_end_span(span);
+    sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, NoopTracer};
+
+    #[test]
+    fn it_sums_squares() {
+        assert_eq!(run(&NoopTracer, &[1, 2, 3]), 14);
+    }
+}
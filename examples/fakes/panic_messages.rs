@@ -0,0 +1,57 @@
+use depabx::wrap;
+use payments::Payments;
+
+// A payments client.
+mod payments {
+    pub struct Payments;
+
+    impl Payments {
+        pub fn authorize(&self, card: &str, cents: u64) -> bool {
+            !card.is_empty() && cents < 100_000
+        }
+
+        pub fn capture(&self, card: &str, cents: u64) {
+            println!("captured {cents} from {card}");
+        }
+    }
+}
+
+// When a strict double panics on an unexpected call, the message gives the full picture:
+//
+//     unexpected call to AbxPayments::abx_capture("4242", 500) at examples/fakes/panic_messages.rs:40:9
+//     configured: abx_authorize
+//
+// The generated methods are `#[track_caller]`, so the location is the call in the code under test rather than a line
+// inside the generated impl.
+#[wrap(Payments, stub(strict))]
+trait AbxPayments {
+    fn abx_authorize(&self, card: &str, cents: u64) -> bool;
+    fn abx_capture(&self, card: &str, cents: u64);
+}
+
+pub fn demo() {
+    println!("{}", run(&Payments {}, "4242", 500));
+}
+
+// Charges the card if the payment is authorized
+fn run<P: AbxPayments>(payments: &P, card: &str, cents: u64) -> bool {
+    let authorized = payments.abx_authorize(card, cents);
+    if authorized {
+        payments.abx_capture(card, cents);
+    }
+    authorized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, StubPayments};
+
+    // Leaving `on_capture` out turns the capture into a panic the test can expect
+    #[test]
+    #[should_panic(expected = "unexpected call to AbxPayments::abx_capture(\"4242\", 500)")]
+    fn it_captures_authorized_payments() {
+        let payments = StubPayments::new().on_authorize(|_, _| true);
+
+        run(&payments, "4242", 500);
+    }
+}
@@ -0,0 +1,52 @@
+use depabx::wrap;
+use system::System;
+
+// A dependency bundling a few platform services. Most are fine to use in tests; the clock isn't.
+mod system {
+    pub struct System;
+
+    impl System {
+        pub fn now_unix(&self) -> u64 {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs()
+        }
+
+        pub fn hostname(&self) -> String {
+            "localhost".to_string()
+        }
+    }
+}
+
+// A fake built with `FakeSystem::with_real(system)` falls through to the real instance for any call it has no
+// configured answer for. Queue a value for one method and everything else keeps its real behavior. The calls are
+// recorded either way.
+#[wrap(System, fake)]
+trait AbxSystem {
+    fn abx_now_unix(&self) -> u64;
+    fn abx_hostname(&self) -> String;
+}
+
+pub fn demo() {
+    println!("{}", run(&System {}));
+}
+
+fn run<S: AbxSystem>(system: &S) -> String {
+    format!("{}-{}", system.abx_hostname(), system.abx_now_unix())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, FakeSystem, System};
+
+    #[test]
+    fn it_names_the_snapshot_after_the_host_and_time() {
+        // Only the clock is faked; the hostname comes from the real `System`
+        let system = FakeSystem::with_real(System {});
+        system.returns_for_now_unix([1_700_000_000]);
+
+        assert_eq!(run(&system), "localhost-1700000000");
+        system.hostname_calls().assert_called_once();
+    }
+}
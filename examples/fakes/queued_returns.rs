@@ -0,0 +1,64 @@
+use depabx::wrap;
+use inventory::{InventoryApi, InventoryError};
+
+// An inventory service that is sometimes briefly unavailable.
+mod inventory {
+    #[derive(Debug, PartialEq)]
+    pub enum InventoryError {
+        Unavailable,
+        UnknownSku,
+    }
+
+    pub struct InventoryApi;
+
+    impl InventoryApi {
+        pub fn get(&self, sku: &str) -> Result<u32, InventoryError> {
+            match sku {
+                "" => Err(InventoryError::UnknownSku),
+                _ => Ok(12),
+            }
+        }
+    }
+}
+
+// Calls made to a generated fake's value-returning methods are answered from a queue. `returns_for_get` appends to
+// the queue for `abx_get`, and each call takes the next value in line. A call with nothing queued panics naming the
+// method, so a test never silently runs on an invented value.
+#[wrap(InventoryApi, fake)]
+trait AbxInventoryApi {
+    fn abx_get(&self, sku: &str) -> Result<u32, InventoryError>;
+}
+
+pub fn demo() {
+    println!("{:?}", run(&InventoryApi {}, "sku-1"));
+}
+
+// Retries while the service is unavailable, up to three attempts
+fn run<I: AbxInventoryApi>(inventory: &I, sku: &str) -> Result<u32, InventoryError> {
+    let mut result = inventory.abx_get(sku);
+    for _ in 1..3 {
+        if result != Err(InventoryError::Unavailable) {
+            break;
+        }
+        result = inventory.abx_get(sku);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, FakeInventoryApi, InventoryError};
+
+    #[test]
+    fn it_retries_until_the_service_answers() {
+        let inventory = FakeInventoryApi::new();
+        inventory.returns_for_get([
+            Err(InventoryError::Unavailable),
+            Err(InventoryError::Unavailable),
+            Ok(4),
+        ]);
+
+        assert_eq!(run(&inventory, "sku-9"), Ok(4));
+        assert_eq!(inventory.calls().len(), 3);
+    }
+}
@@ -0,0 +1,54 @@
+use depabx::wrap;
+use logger::Logger;
+
+// The same kind of logger as the sample, with plain `&str` arguments.
+mod logger {
+    pub struct Logger;
+
+    impl Logger {
+        pub fn log_event(&self, description: &str) {
+            println!("Event: {description}");
+        }
+
+        pub fn log_error(&self, description: &str, code: u32) {
+            println!("Error {code}: {description}");
+        }
+    }
+}
+
+// `fake` generates `FakeLogger` next to the impl: the trait name with `Abx` swapped for `Fake`. It implements the
+// trait by recording every call, in order, as a `depabx::Call` holding the method name and each argument formatted
+// with `Debug`. That's the fake from the sample test, minus the boilerplate.
+#[wrap(Logger, fake)]
+trait AbxLogger {
+    fn abx_log_event(&self, description: &str);
+    fn abx_log_error(&self, description: &str, code: u32);
+}
+
+pub fn demo() {
+    run(&Logger {});
+}
+
+fn run<L: AbxLogger>(logger: &L) {
+    logger.abx_log_event("Some event description");
+    logger.abx_log_error("Some error description", 500);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, FakeLogger};
+
+    #[test]
+    fn it_prints_two_logs() {
+        let logger = FakeLogger::new();
+
+        run(&logger);
+
+        let calls = logger.calls();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].method, "abx_log_event");
+        assert_eq!(calls[0].args, vec!["\"Some event description\""]);
+        assert_eq!(calls[1].method, "abx_log_error");
+        assert_eq!(calls[1].args, vec!["\"Some error description\"", "500"]);
+    }
+}
@@ -0,0 +1,78 @@
+use depabx::wrap;
+use deps::{Cache, HttpClient};
+
+// A cache in front of a slow HTTP API.
+mod deps {
+    pub struct Cache;
+    pub struct HttpClient;
+
+    impl Cache {
+        pub fn get(&self, url: &str) -> Option<String> {
+            url.ends_with("/cached").then(|| "cached body".to_string())
+        }
+
+        pub fn set(&self, url: &str, body: &str) {
+            println!("cached {} bytes for {url}", body.len());
+        }
+    }
+
+    impl HttpClient {
+        pub fn fetch(&self, url: &str) -> String {
+            format!("body of {url}")
+        }
+    }
+}
+
+// A `depabx::Registry` is a log that several fakes report into, opted into with `with_registry(&registry)`. It keeps
+// one interleaved record of the calls on all of them, rendered like `AbxCache::abx_get("/users")`, and isn't shared
+// with other tests. `assert_order` checks that calls to the listed methods happened in that order, ignoring calls to
+// others; `interactions()` returns the whole record for anything more specific.
+#[wrap(Cache, fake)]
+trait AbxCache {
+    fn abx_get(&self, url: &str) -> Option<String>;
+    fn abx_set(&self, url: &str, body: &str);
+}
+
+#[wrap(HttpClient, fake)]
+trait AbxHttpClient {
+    fn abx_fetch(&self, url: &str) -> String;
+}
+
+pub fn demo() {
+    println!("{}", run(&Cache {}, &HttpClient {}, "/users"));
+}
+
+fn run<C: AbxCache, H: AbxHttpClient>(cache: &C, http: &H, url: &str) -> String {
+    if let Some(body) = cache.abx_get(url) {
+        return body;
+    }
+    let body = http.abx_fetch(url);
+    cache.abx_set(url, &body);
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, FakeCache, FakeHttpClient};
+    use depabx::Registry;
+
+    #[test]
+    fn it_checks_the_cache_before_calling_the_api() {
+        let registry = Registry::new();
+        let cache = FakeCache::with_registry(&registry);
+        let http = FakeHttpClient::with_registry(&registry);
+        cache.returns_for_get([None]);
+        http.returns_for_fetch(["[]".to_string()]);
+
+        assert_eq!(run(&cache, &http, "/users"), "[]");
+        registry.assert_order([
+            "AbxCache::abx_get",
+            "AbxHttpClient::abx_fetch",
+            "AbxCache::abx_set",
+        ]);
+        assert_eq!(
+            registry.interactions()[2],
+            "AbxCache::abx_set(\"/users\", \"[]\")"
+        );
+    }
+}
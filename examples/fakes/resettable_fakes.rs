@@ -0,0 +1,51 @@
+use depabx::wrap;
+use flags::FeatureFlags;
+
+// A feature flag service.
+mod flags {
+    pub struct FeatureFlags;
+
+    impl FeatureFlags {
+        pub fn is_enabled(&self, flag: &str) -> bool {
+            flag == "new-checkout"
+        }
+    }
+}
+
+// `reset()` puts a generated fake back the way `new()` left it: recorded calls, queued returns, and any other
+// configuration are all cleared. Useful when one fake is reused across the cases of a table-driven test.
+#[wrap(FeatureFlags, fake)]
+trait AbxFeatureFlags {
+    fn abx_is_enabled(&self, flag: &str) -> bool;
+}
+
+pub fn demo() {
+    println!("{}", run(&FeatureFlags {}));
+}
+
+fn run<F: AbxFeatureFlags>(flags: &F) -> &'static str {
+    if flags.abx_is_enabled("new-checkout") {
+        "checkout v2"
+    } else {
+        "checkout v1"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, FakeFeatureFlags};
+
+    #[test]
+    fn it_picks_the_checkout_by_flag() {
+        let flags = FakeFeatureFlags::new();
+
+        for (enabled, expected) in [(true, "checkout v2"), (false, "checkout v1")] {
+            flags.reset();
+            flags.returns_for_is_enabled([enabled]);
+
+            assert_eq!(run(&flags), expected);
+            // Thanks to the reset, each case only sees its own call
+            flags.is_enabled_calls().assert_called_once();
+        }
+    }
+}
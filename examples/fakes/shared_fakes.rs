@@ -0,0 +1,59 @@
+use depabx::wrap;
+use metrics::Metrics;
+
+// A metrics client, cheap to clone and usually handed to every component.
+mod metrics {
+    #[derive(Clone)]
+    pub struct Metrics;
+
+    impl Metrics {
+        pub fn increment(&self, counter: &str) {
+            println!("{counter} += 1");
+        }
+    }
+}
+
+// `fake(clone)` makes the generated fake `Clone`. Its clones share one interaction log behind an `Arc`, so a double
+// can be given to several components that each want their own copy, and the test still inspects every call in one
+// place, in the order they happened.
+#[wrap(Metrics, fake(clone))]
+trait AbxMetrics {
+    fn abx_increment(&self, counter: &str);
+}
+
+struct Api<M: AbxMetrics> {
+    metrics: M,
+}
+
+struct Db<M: AbxMetrics> {
+    metrics: M,
+}
+
+pub fn demo() {
+    run(Metrics {});
+}
+
+fn run<M: AbxMetrics + Clone>(metrics: M) {
+    let api = Api {
+        metrics: metrics.clone(),
+    };
+    let db = Db { metrics };
+    api.metrics.abx_increment("api.requests");
+    db.metrics.abx_increment("db.queries");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, FakeMetrics};
+
+    #[test]
+    fn it_counts_requests_and_queries() {
+        let metrics = FakeMetrics::new();
+
+        run(metrics.clone());
+
+        let calls = metrics.calls();
+        assert_eq!(calls[0].args, vec!["\"api.requests\""]);
+        assert_eq!(calls[1].args, vec!["\"db.queries\""]);
+    }
+}
@@ -0,0 +1,65 @@
+use depabx::wrap;
+use kv::KvStore;
+
+// A real, in-process key-value store, cheap enough to use in integration tests.
+mod kv {
+    use std::{cell::RefCell, collections::HashMap};
+
+    pub struct KvStore {
+        pub entries: RefCell<HashMap<String, String>>,
+    }
+
+    impl KvStore {
+        pub fn get(&self, key: &str) -> Option<String> {
+            self.entries.borrow().get(key).cloned()
+        }
+
+        pub fn set(&self, key: &str, value: &str) {
+            self.entries
+                .borrow_mut()
+                .insert(key.to_string(), value.to_string());
+        }
+    }
+}
+
+// `spy` generates `SpyKvStore`, which owns a real `KvStore`, forwards every call to it, and records each call (with
+// the same `calls()` and `*_calls()` API as the fakes) before returning the real result.
+#[wrap(KvStore, spy)]
+trait AbxKvStore {
+    fn abx_get(&self, key: &str) -> Option<String>;
+    fn abx_set(&self, key: &str, value: &str);
+}
+
+pub fn demo() {
+    let store = KvStore {
+        entries: Default::default(),
+    };
+    println!("{}", run(&store, "theme"));
+}
+
+// Reads a setting, storing the default the first time it's missing
+fn run<S: AbxKvStore>(store: &S, key: &str) -> String {
+    store.abx_get(key).unwrap_or_else(|| {
+        store.abx_set(key, "light");
+        "light".to_string()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, KvStore, SpyKvStore};
+
+    #[test]
+    fn it_only_writes_the_default_once() {
+        let store = SpyKvStore::new(KvStore {
+            entries: Default::default(),
+        });
+
+        assert_eq!(run(&store, "theme"), "light");
+        assert_eq!(run(&store, "theme"), "light");
+
+        // The real store did the work; the spy shows how it was used
+        store.set_calls().assert_called_once();
+        store.get_calls().assert_called_times(2);
+    }
+}
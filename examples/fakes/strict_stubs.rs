@@ -0,0 +1,56 @@
+use depabx::wrap;
+use storage::Storage;
+
+// A storage client with a dangerous method the code under test must never call.
+mod storage {
+    pub struct Storage;
+
+    impl Storage {
+        pub fn list(&self, prefix: &str) -> Vec<String> {
+            vec![format!("{prefix}/a"), format!("{prefix}/b")]
+        }
+
+        pub fn delete(&self, key: &str) {
+            println!("deleted {key}");
+        }
+    }
+}
+
+// `stub(strict)` changes what unconfigured methods do: instead of falling back to a default, every call to one panics
+// with the method name and its arguments, including methods returning `()`.
+#[wrap(Storage, stub(strict))]
+trait AbxStorage {
+    fn abx_list(&self, prefix: &str) -> Vec<String>;
+    fn abx_delete(&self, key: &str);
+}
+
+pub fn demo() {
+    println!("{}", run(&Storage {}, "tmp"));
+}
+
+// Cleans up the prefix, unless it holds anything marked as kept
+fn run<S: AbxStorage>(storage: &S, prefix: &str) -> usize {
+    let keys = storage.abx_list(prefix);
+    if keys.iter().any(|key| key.ends_with(".keep")) {
+        return 0;
+    }
+    for key in &keys {
+        storage.abx_delete(key);
+    }
+    keys.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, StubStorage};
+
+    #[test]
+    fn it_keeps_prefixes_with_a_keep_marker() {
+        // `on_delete` isn't configured, so deleting anything fails with
+        // "unexpected call to AbxStorage::abx_delete(..)"
+        let storage = StubStorage::new()
+            .on_list(|prefix| vec![format!("{prefix}/a"), format!("{prefix}/.keep")]);
+
+        assert_eq!(run(&storage, "logs"), 0);
+    }
+}
@@ -0,0 +1,59 @@
+use depabx::wrap;
+use weather::WeatherApi;
+
+// A weather API client.
+mod weather {
+    pub struct WeatherApi;
+
+    impl WeatherApi {
+        pub fn temperature(&self, city: &str) -> f64 {
+            if city == "Oslo" {
+                -3.0
+            } else {
+                18.0
+            }
+        }
+
+        pub fn report_usage(&self, calls: u32) {
+            println!("used {calls} calls");
+        }
+    }
+}
+
+// `stub` generates `StubWeatherApi`, configured per method with closures: `on_temperature(|city| ...)` is named after
+// the concrete method and takes the trait method's arguments. A method that was never configured does nothing if it
+// returns `()`, and panics naming the method otherwise, since there's no value it could make up.
+#[wrap(WeatherApi, stub)]
+trait AbxWeatherApi {
+    fn abx_temperature(&self, city: &str) -> f64;
+    fn abx_report_usage(&self, calls: u32);
+}
+
+pub fn demo() {
+    println!("{}", run(&WeatherApi {}, "Oslo"));
+}
+
+fn run<W: AbxWeatherApi>(weather: &W, city: &str) -> String {
+    let celsius = weather.abx_temperature(city);
+    weather.abx_report_usage(1);
+    if celsius < 0.0 {
+        format!("{city}: freezing")
+    } else {
+        format!("{city}: {celsius}°C")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, StubWeatherApi};
+
+    #[test]
+    fn it_says_when_its_freezing() {
+        // Only the method the test cares about is configured; `abx_report_usage` falls back to doing nothing
+        let weather =
+            StubWeatherApi::new().on_temperature(|city| if city == "Nuuk" { -20.0 } else { 0.0 });
+
+        assert_eq!(run(&weather, "Nuuk"), "Nuuk: freezing");
+        assert_eq!(run(&weather, "Rome"), "Rome: 0°C");
+    }
+}
@@ -0,0 +1,57 @@
+use depabx::wrap;
+use std::thread;
+use thumbnails::Resizer;
+
+// An image resizer that's called from worker threads.
+mod thumbnails {
+    pub struct Resizer;
+
+    impl Resizer {
+        pub fn resize(&self, image: &str, width: u32) -> String {
+            format!("{image}@{width}w")
+        }
+    }
+}
+
+// Generated fakes use `RefCell` by default, which keeps them simple but not `Sync`. `fake(sync)` swaps every piece of
+// internal state for a `Mutex`, so the fake is `Send + Sync` and can be shared with threads and spawned tasks. Calls
+// from different threads are recorded in the order they happened to arrive.
+#[wrap(Resizer, fake(sync))]
+trait AbxResizer: Sync {
+    fn abx_resize(&self, image: &str, width: u32) -> String;
+}
+
+pub fn demo() {
+    println!("{:?}", run(&Resizer {}, &["a.png", "b.png"]));
+}
+
+// Resizes every image on its own thread
+fn run<R: AbxResizer>(resizer: &R, images: &[&str]) -> Vec<String> {
+    thread::scope(|scope| {
+        let jobs: Vec<_> = images
+            .iter()
+            .map(|image| scope.spawn(move || resizer.abx_resize(image, 320)))
+            .collect();
+        jobs.into_iter().map(|job| job.join().unwrap()).collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, FakeResizer};
+
+    #[test]
+    fn it_resizes_every_image_concurrently() {
+        let resizer = FakeResizer::new();
+        resizer.returns_for_resize([
+            "small".to_string(),
+            "small".to_string(),
+            "small".to_string(),
+        ]);
+
+        let resized = run(&resizer, &["1.png", "2.png", "3.png"]);
+
+        assert_eq!(resized, vec!["small"; 3]);
+        resizer.resize_calls().assert_called_times(3);
+    }
+}
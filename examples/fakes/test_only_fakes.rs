@@ -0,0 +1,45 @@
+use depabx::wrap;
+use queue::JobQueue;
+
+// A job queue client.
+mod queue {
+    pub struct JobQueue;
+
+    impl JobQueue {
+        pub fn enqueue(&self, job: &str) -> u64 {
+            job.len() as u64
+        }
+    }
+}
+
+// `fake(test_only)` puts `#[cfg(any(test, feature = "test-doubles"))]` on the generated fake, so it's compiled for this
+// crate's own tests, and other builds don't carry it. The cfg is evaluated in the crate declaring the trait, which
+// defines the feature. When that's a library, the crates depending on it turn the feature on in their
+// `[dev-dependencies]` to get its fakes in their tests, since `cfg(test)` doesn't hold for dependencies. The crate has
+// to declare the feature, `[features] test-doubles = []` as this one's `Cargo.toml` does, or rustc warns about an
+// unexpected cfg. A crate with its own feature for test doubles names it instead, as in `fake(test_only = "fakes")`.
+#[wrap(JobQueue, fake(test_only))]
+trait AbxJobQueue {
+    fn abx_enqueue(&self, job: &str) -> u64;
+}
+
+pub fn demo() {
+    println!("{:?}", run(&JobQueue {}, &["resize", "thumbnail"]));
+}
+
+fn run<Q: AbxJobQueue>(queue: &Q, jobs: &[&str]) -> Vec<u64> {
+    jobs.iter().map(|job| queue.abx_enqueue(job)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, FakeJobQueue};
+
+    #[test]
+    fn it_returns_the_ids_of_enqueued_jobs() {
+        let queue = FakeJobQueue::new();
+        queue.returns_for_enqueue([7, 8]);
+
+        assert_eq!(run(&queue, &["resize", "thumbnail"]), vec![7, 8]);
+    }
+}
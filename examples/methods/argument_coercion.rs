@@ -0,0 +1,56 @@
+use analytics::Analytics;
+use depabx::wrap;
+
+// A vendor API with parameter types that are awkward to call: owned strings where borrowed would do, and the reverse.
+mod analytics {
+    pub struct Analytics;
+
+    impl Analytics {
+        pub fn track(&self, event: String) -> String {
+            format!("tracked {event}")
+        }
+
+        pub fn tag(&self, tag: &str) -> usize {
+            tag.len()
+        }
+    }
+}
+
+// Mark an argument with `#[abx(into)]` or `#[abx(as_ref)]` to have the generated impl convert it before delegating:
+// `Analytics::track(self, event.into())` and `Analytics::tag(self, tag.as_ref())`. The trait gets to use whichever
+// type reads best.
+#[wrap(Analytics)]
+trait AbxAnalytics {
+    fn abx_track(&self, #[abx(into)] event: &str) -> String;
+    fn abx_tag(&self, #[abx(as_ref)] tag: String) -> usize;
+}
+
+pub fn demo() {
+    println!("{:?}", run(&Analytics {}));
+}
+
+fn run<A: AbxAnalytics>(analytics: &A) -> (String, usize) {
+    let tag = format!("build-{}", 42);
+    (analytics.abx_track("signup"), analytics.abx_tag(tag))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxAnalytics};
+
+    #[test]
+    fn it_tracks_signups_and_tags_the_build() {
+        // The conversion lives in the generated impl only, so the fake sees exactly the trait's types
+        struct FakeAnalytics;
+        impl AbxAnalytics for FakeAnalytics {
+            fn abx_track(&self, event: &str) -> String {
+                event.to_uppercase()
+            }
+            fn abx_tag(&self, tag: String) -> usize {
+                tag.len() * 10
+            }
+        }
+
+        assert_eq!(run(&FakeAnalytics {}), ("SIGNUP".to_string(), 80));
+    }
+}
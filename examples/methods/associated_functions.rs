@@ -0,0 +1,68 @@
+use depabx::wrap;
+use mailer::Mailer;
+
+// A dependency that's built from configuration. The constructor is an associated function with no receiver.
+mod mailer {
+    pub struct Mailer {
+        host: String,
+    }
+
+    impl Mailer {
+        pub fn connect(host: String) -> Self {
+            Mailer { host }
+        }
+
+        pub fn send(&self, to: &str) {
+            println!("Mailing {to} via {}", self.host);
+        }
+    }
+}
+
+// Methods without a receiver are wrapped as associated functions, and the generated impl calls the concrete one
+// directly: `fn abx_connect(host: String) -> Self { Mailer::connect(host) }`.
+#[wrap(Mailer)]
+trait AbxMailer {
+    fn abx_connect(host: String) -> Self;
+    fn abx_send(&self, to: &str);
+}
+
+pub fn demo() {
+    run::<Mailer>("smtp.prod.example.com");
+}
+
+// The code under test builds its own dependency through the trait, so tests can see what it was built with.
+fn run<M: AbxMailer>(host: &str) -> M {
+    let mailer = M::abx_connect(host.to_string());
+    mailer.abx_send("ops@example.com");
+    mailer
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxMailer};
+    use std::cell::RefCell;
+
+    #[test]
+    fn it_connects_to_the_given_host() {
+        struct FakeMailer {
+            host: String,
+            sent_to: RefCell<Vec<String>>,
+        }
+        impl AbxMailer for FakeMailer {
+            fn abx_connect(host: String) -> Self {
+                FakeMailer {
+                    host,
+                    sent_to: RefCell::new(Vec::new()),
+                }
+            }
+            fn abx_send(&self, to: &str) {
+                self.sent_to.borrow_mut().push(to.to_string());
+            }
+        }
+
+        let mailer = run::<FakeMailer>("smtp.test");
+
+        assert_eq!(mailer.host, "smtp.test");
+        assert_eq!(*mailer.sent_to.borrow(), vec!["ops@example.com"]);
+    }
+}
@@ -0,0 +1,89 @@
+use depabx::wrap;
+use http::{Client, Response};
+
+// An async SDK client. Every method is an `async fn`, so callers have to await it.
+mod http {
+    pub struct Client;
+
+    pub struct Response {
+        pub status: u16,
+    }
+
+    impl Client {
+        pub fn new() -> Self {
+            Client {}
+        }
+
+        pub async fn send(&self, body: String) -> Response {
+            println!("Sending: {body}");
+            Response { status: 200 }
+        }
+
+        pub async fn ping(&self) -> bool {
+            true
+        }
+    }
+}
+
+// Async methods are declared with `async fn`. By default they stay that way in the trait, and the generated impl
+// simply awaits the concrete method: `async fn abx_send(&self, body: String) -> Response { self.send(body).await }`.
+#[wrap(Client)]
+trait AbxClient {
+    async fn abx_send(&self, body: String) -> Response;
+}
+
+// `async fn` in a trait isn't object safe. If you need `dyn AbxPinger`, ask for boxed futures instead: the macro
+// rewrites each method to return `Pin<Box<dyn Future<Output = T> + '_>>`, and that's the signature mocks implement.
+#[wrap(Client, futures = "boxed")]
+trait AbxPinger {
+    async fn abx_ping(&self) -> bool;
+}
+
+pub fn demo() {
+    let client = Client::new();
+    futures::executor::block_on(run(&client, &client));
+}
+
+async fn run<C: AbxClient>(client: &C, pinger: &dyn AbxPinger) -> u16 {
+    if !pinger.abx_ping().await {
+        return 0;
+    }
+    client
+        .abx_send("Some request body".to_string())
+        .await
+        .status
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxClient, AbxPinger, Response};
+    use std::{cell::RefCell, future::Future, pin::Pin};
+
+    #[test]
+    fn it_sends_once_the_server_responds_to_a_ping() {
+        struct FakeClient {
+            sent: RefCell<Vec<String>>,
+        }
+        impl AbxClient for FakeClient {
+            async fn abx_send(&self, body: String) -> Response {
+                self.sent.borrow_mut().push(body);
+                Response { status: 201 }
+            }
+        }
+        // The boxed trait is implemented with the desugared signature
+        impl AbxPinger for FakeClient {
+            fn abx_ping(&self) -> Pin<Box<dyn Future<Output = bool> + '_>> {
+                Box::pin(async { true })
+            }
+        }
+
+        let client = FakeClient {
+            sent: RefCell::new(Vec::new()),
+        };
+
+        let status = futures::executor::block_on(run(&client, &client));
+
+        assert_eq!(status, 201);
+        assert_eq!(*client.sent.borrow(), vec!["Some request body".to_string()]);
+    }
+}
@@ -0,0 +1,75 @@
+use config::Profile;
+use depabx::wrap;
+
+// A dependency that hands out views into data it owns.
+mod config {
+    pub struct Profile {
+        name: String,
+        tags: Vec<String>,
+    }
+
+    impl Profile {
+        pub fn load() -> Self {
+            Profile {
+                name: "production".to_string(),
+                tags: vec!["eu-west".to_string(), "primary".to_string()],
+            }
+        }
+
+        pub fn name(&self) -> &str {
+            &self.name
+        }
+
+        pub fn tag(&self, index: usize) -> Option<&str> {
+            self.tags.get(index).map(|t| t.as_str())
+        }
+    }
+}
+
+// Returned references borrow from the receiver. Elided lifetimes are kept elided in the generated impl, so they're
+// tied to `&self` exactly as on the concrete type. Spelling the lifetime out works too.
+#[wrap(Profile)]
+trait AbxProfile {
+    fn abx_name(&self) -> &str;
+    #[allow(clippy::needless_lifetimes)]
+    fn abx_tag<'a>(&'a self, index: usize) -> Option<&'a str>;
+}
+
+pub fn demo() {
+    println!("{}", run(&Profile::load()));
+}
+
+fn run<P: AbxProfile>(profile: &P) -> String {
+    match profile.abx_tag(0) {
+        Some(tag) => format!("{} ({tag})", profile.abx_name()),
+        None => profile.abx_name().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxProfile};
+
+    #[test]
+    fn it_describes_the_profile_with_its_first_tag() {
+        struct FakeProfile {
+            name: String,
+            tags: Vec<String>,
+        }
+        impl AbxProfile for FakeProfile {
+            fn abx_name(&self) -> &str {
+                &self.name
+            }
+            fn abx_tag(&self, index: usize) -> Option<&str> {
+                self.tags.get(index).map(|t| t.as_str())
+            }
+        }
+
+        let profile = FakeProfile {
+            name: "staging".to_string(),
+            tags: vec!["canary".to_string()],
+        };
+
+        assert_eq!(run(&profile), "staging (canary)");
+    }
+}
@@ -0,0 +1,65 @@
+use bucket::Bucket;
+use depabx::wrap;
+use std::{error::Error, io::Read};
+
+// An SDK that returns trait objects for both its data and its errors.
+mod bucket {
+    use std::{error::Error, io::Read};
+
+    pub struct Bucket;
+
+    impl Bucket {
+        pub fn open(&self, key: &str) -> Box<dyn Read + Send + 'static> {
+            Box::new(std::io::Cursor::new(
+                format!("contents of {key}").into_bytes(),
+            ))
+        }
+
+        pub fn size(&self, key: &str) -> Result<u64, Box<dyn Error + Send + Sync>> {
+            if key.is_empty() {
+                return Err("empty key".into());
+            }
+            Ok(key.len() as u64)
+        }
+    }
+}
+
+// `Box<dyn Trait>` types are reproduced token for token, including any `+ Send + 'static` bounds, so the generated
+// signatures match the concrete ones exactly.
+#[wrap(Bucket)]
+trait AbxBucket {
+    fn abx_open(&self, key: &str) -> Box<dyn Read + Send + 'static>;
+    fn abx_size(&self, key: &str) -> Result<u64, Box<dyn Error + Send + Sync>>;
+}
+
+pub fn demo() {
+    println!("{:?}", run(&Bucket {}, "report.csv"));
+}
+
+fn run<B: AbxBucket>(bucket: &B, key: &str) -> Result<String, Box<dyn Error + Send + Sync>> {
+    let size = bucket.abx_size(key)?;
+    let mut contents = String::new();
+    bucket.abx_open(key).read_to_string(&mut contents)?;
+    Ok(format!("{size} bytes: {contents}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxBucket};
+    use std::{error::Error, io::Read};
+
+    #[test]
+    fn it_reads_the_object_after_checking_its_size() {
+        struct FakeBucket;
+        impl AbxBucket for FakeBucket {
+            fn abx_open(&self, _key: &str) -> Box<dyn Read + Send + 'static> {
+                Box::new("hello".as_bytes())
+            }
+            fn abx_size(&self, _key: &str) -> Result<u64, Box<dyn Error + Send + Sync>> {
+                Ok(5)
+            }
+        }
+
+        assert_eq!(run(&FakeBucket {}, "greeting").unwrap(), "5 bytes: hello");
+    }
+}
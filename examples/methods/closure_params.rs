@@ -0,0 +1,84 @@
+use depabx::wrap;
+use events::{Event, EventLog};
+
+// A dependency that takes callbacks of every flavor.
+mod events {
+    pub struct Event {
+        pub kind: String,
+    }
+
+    pub struct EventLog;
+
+    impl EventLog {
+        pub fn for_each(&self, mut f: impl FnMut(&Event)) {
+            for kind in ["start", "stop"] {
+                f(&Event {
+                    kind: kind.to_string(),
+                });
+            }
+        }
+
+        pub fn retry<F: Fn() -> bool>(&self, attempts: u32, f: F) -> bool {
+            (0..attempts).any(|_| f())
+        }
+
+        pub fn finish<F>(&self, f: F) -> String
+        where
+            F: FnOnce(u32) -> String,
+        {
+            f(2)
+        }
+    }
+}
+
+// Closure parameters are ordinary arguments to the macro, whether they're written as `impl Fn...` or as a generic
+// `F: Fn...` bound, so `Fn`, `FnMut` and `FnOnce` all forward to the concrete method unchanged.
+#[wrap(EventLog)]
+trait AbxEventLog {
+    fn abx_for_each(&self, f: impl FnMut(&Event));
+    fn abx_retry<F: Fn() -> bool>(&self, attempts: u32, f: F) -> bool;
+    fn abx_finish<F>(&self, f: F) -> String
+    where
+        F: FnOnce(u32) -> String;
+}
+
+pub fn demo() {
+    println!("{}", run(&EventLog {}));
+}
+
+fn run<L: AbxEventLog>(log: &L) -> String {
+    let mut kinds = Vec::new();
+    log.abx_for_each(|event| kinds.push(event.kind.clone()));
+    if !log.abx_retry(3, || !kinds.is_empty()) {
+        return "no events".to_string();
+    }
+    log.abx_finish(move |count| format!("{count} events: {}", kinds.join(", ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxEventLog, Event};
+
+    #[test]
+    fn it_summarizes_the_events() {
+        struct FakeEventLog;
+        impl AbxEventLog for FakeEventLog {
+            fn abx_for_each(&self, mut f: impl FnMut(&Event)) {
+                f(&Event {
+                    kind: "deploy".to_string(),
+                });
+            }
+            fn abx_retry<F: Fn() -> bool>(&self, _attempts: u32, f: F) -> bool {
+                f()
+            }
+            fn abx_finish<F>(&self, f: F) -> String
+            where
+                F: FnOnce(u32) -> String,
+            {
+                f(1)
+            }
+        }
+
+        assert_eq!(run(&FakeEventLog {}), "1 events: deploy");
+    }
+}
@@ -0,0 +1,58 @@
+use depabx::wrap;
+use stream::ByteReader;
+
+// A reader that returns fixed-size chunks, with the size chosen by a const generic parameter.
+mod stream {
+    pub struct ByteReader {
+        pub data: Vec<u8>,
+    }
+
+    impl ByteReader {
+        pub fn read_chunk<const N: usize>(&self) -> [u8; N] {
+            let mut chunk = [0; N];
+            for (slot, byte) in chunk.iter_mut().zip(&self.data) {
+                *slot = *byte;
+            }
+            chunk
+        }
+    }
+}
+
+// Const generic parameters are declared on the trait method like any other generic. The generated impl forwards them
+// with a turbofish (`ByteReader::read_chunk::<N>(self)`), since a const parameter can't always be inferred from the
+// arguments.
+#[wrap(ByteReader)]
+trait AbxByteReader {
+    fn abx_read_chunk<const N: usize>(&self) -> [u8; N];
+}
+
+pub fn demo() {
+    let reader = ByteReader {
+        data: vec![0xCA, 0xFE, 0xBA, 0xBE, 0x00, 0x01],
+    };
+    println!("{:#x}", run(&reader));
+}
+
+// Reads a big-endian u32 header
+fn run<R: AbxByteReader>(reader: &R) -> u32 {
+    u32::from_be_bytes(reader.abx_read_chunk::<4>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxByteReader};
+
+    #[test]
+    fn it_reads_a_four_byte_header() {
+        struct FakeByteReader;
+        impl AbxByteReader for FakeByteReader {
+            fn abx_read_chunk<const N: usize>(&self) -> [u8; N] {
+                let mut chunk = [0; N];
+                chunk[N - 1] = 7;
+                chunk
+            }
+        }
+
+        assert_eq!(run(&FakeByteReader {}), 7);
+    }
+}
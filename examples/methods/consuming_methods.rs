@@ -0,0 +1,68 @@
+use depabx::wrap;
+use report::ReportWriter;
+
+// A writer that buffers output until it's consumed. `into_inner` takes `self`, so the writer can't be used afterwards.
+mod report {
+    pub struct ReportWriter {
+        buffer: String,
+    }
+
+    impl ReportWriter {
+        pub fn new() -> Self {
+            ReportWriter {
+                buffer: String::new(),
+            }
+        }
+
+        pub fn write_line(&mut self, line: &str) {
+            self.buffer.push_str(line);
+            self.buffer.push('\n');
+        }
+
+        pub fn into_inner(self) -> String {
+            self.buffer
+        }
+    }
+}
+
+// A `self` receiver generates a consuming delegation, moving the concrete value into `ReportWriter::into_inner`.
+#[wrap(ReportWriter)]
+trait AbxReportWriter {
+    fn abx_write_line(&mut self, line: &str);
+    fn abx_into_inner(self) -> String;
+}
+
+pub fn demo() {
+    println!("{}", run(ReportWriter::new()));
+}
+
+// Taking the writer by value lets the code under test consume it, just like it would the concrete type.
+fn run<W: AbxReportWriter>(mut writer: W) -> String {
+    writer.abx_write_line("Totals");
+    writer.abx_write_line("------");
+    writer.abx_into_inner()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxReportWriter};
+
+    #[test]
+    fn it_returns_everything_written() {
+        struct FakeWriter {
+            lines: Vec<String>,
+        }
+        impl AbxReportWriter for FakeWriter {
+            fn abx_write_line(&mut self, line: &str) {
+                self.lines.push(line.to_string());
+            }
+            fn abx_into_inner(self) -> String {
+                self.lines.join("|")
+            }
+        }
+
+        let writer = FakeWriter { lines: Vec::new() };
+
+        assert_eq!(run(writer), "Totals|------");
+    }
+}
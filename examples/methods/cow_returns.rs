@@ -0,0 +1,70 @@
+use depabx::wrap;
+use std::borrow::Cow;
+use text::Normalizer;
+
+// A dependency that only allocates when it has to change its input.
+mod text {
+    use std::borrow::Cow;
+
+    pub struct Normalizer {
+        pub label: String,
+    }
+
+    impl Normalizer {
+        pub fn label(&self) -> Cow<'_, str> {
+            if self.label.contains(' ') {
+                Cow::Owned(self.label.replace(' ', "_"))
+            } else {
+                Cow::Borrowed(&self.label)
+            }
+        }
+
+        pub fn trim<'a>(&self, input: &'a str) -> Cow<'a, str> {
+            Cow::Borrowed(input.trim())
+        }
+    }
+}
+
+// The `'_` in `Cow<'_, str>` is left elided in the generated impl, so it still borrows from `&self`. Named lifetimes
+// that tie the result to another argument are kept too.
+#[wrap(Normalizer)]
+trait AbxNormalizer {
+    fn abx_label(&self) -> Cow<'_, str>;
+    fn abx_trim<'a>(&self, input: &'a str) -> Cow<'a, str>;
+}
+
+pub fn demo() {
+    let normalizer = Normalizer {
+        label: "nightly build".to_string(),
+    };
+    println!("{}", run(&normalizer, "  v1.2  "));
+}
+
+fn run<N: AbxNormalizer>(normalizer: &N, version: &str) -> String {
+    format!(
+        "{}@{}",
+        normalizer.abx_label(),
+        normalizer.abx_trim(version)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxNormalizer};
+    use std::borrow::Cow;
+
+    #[test]
+    fn it_joins_the_label_and_version() {
+        struct FakeNormalizer;
+        impl AbxNormalizer for FakeNormalizer {
+            fn abx_label(&self) -> Cow<'_, str> {
+                Cow::Borrowed("fake")
+            }
+            fn abx_trim<'a>(&self, input: &'a str) -> Cow<'a, str> {
+                Cow::Owned(input.replace(' ', ""))
+            }
+        }
+
+        assert_eq!(run(&FakeNormalizer {}, " 2.0 "), "fake@2.0");
+    }
+}
@@ -0,0 +1,65 @@
+use archive::Archive;
+use depabx::wrap;
+
+// A dependency with an optional cargo feature. `compress` only exists when the feature is enabled.
+mod archive {
+    pub struct Archive;
+
+    impl Archive {
+        pub fn add(&self, name: &str) -> String {
+            format!("added {name}")
+        }
+
+        #[cfg(feature = "compression")]
+        pub fn compress(&self, level: u8) -> String {
+            format!("compressed at level {level}")
+        }
+    }
+}
+
+// Gate the trait method with the same `#[cfg(feature = ...)]` as the concrete one. The macro copies it onto the
+// generated method, so builds without the feature neither declare nor delegate `abx_compress`.
+#[wrap(Archive)]
+trait AbxArchive {
+    fn abx_add(&self, name: &str) -> String;
+    #[cfg(feature = "compression")]
+    fn abx_compress(&self, level: u8) -> String;
+}
+
+pub fn demo() {
+    println!("{:?}", run(&Archive {}));
+}
+
+fn run<A: AbxArchive>(archive: &A) -> Vec<String> {
+    #[allow(unused_mut)]
+    let mut steps = vec![archive.abx_add("notes.txt")];
+    #[cfg(feature = "compression")]
+    steps.push(archive.abx_compress(9));
+    steps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxArchive};
+
+    #[test]
+    fn it_adds_then_compresses_when_enabled() {
+        struct FakeArchive;
+        impl AbxArchive for FakeArchive {
+            fn abx_add(&self, name: &str) -> String {
+                name.to_string()
+            }
+            #[cfg(feature = "compression")]
+            fn abx_compress(&self, level: u8) -> String {
+                level.to_string()
+            }
+        }
+
+        let steps = run(&FakeArchive {});
+
+        #[cfg(feature = "compression")]
+        assert_eq!(steps, vec!["notes.txt", "9"]);
+        #[cfg(not(feature = "compression"))]
+        assert_eq!(steps, vec!["notes.txt"]);
+    }
+}
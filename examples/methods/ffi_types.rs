@@ -0,0 +1,87 @@
+use depabx::wrap;
+use ffi::Binding;
+use std::ffi::{c_char, c_int, CStr};
+
+// A bindgen-style shim. Its signatures are written in C types and raw pointers.
+mod ffi {
+    use std::{
+        cell::RefCell,
+        ffi::{c_char, c_int, CStr, CString},
+    };
+
+    pub struct Binding {
+        pub last_name: RefCell<CString>,
+    }
+
+    impl Binding {
+        pub fn version(&self) -> *const c_char {
+            c"libfoo 2.1".as_ptr()
+        }
+
+        // Safety: `name` must point to a valid, nul-terminated string
+        pub unsafe fn set_name(&self, name: *const c_char) -> c_int {
+            let name = unsafe { CStr::from_ptr(name) };
+            *self.last_name.borrow_mut() = name.to_owned();
+            0
+        }
+    }
+}
+
+// Raw pointers and `std::ffi` types are just types to the macro, so they're passed through untouched in both
+// directions. Anything that dereferences them stays `unsafe`, exactly as in the binding.
+#[wrap(Binding)]
+trait AbxBinding {
+    fn abx_version(&self) -> *const c_char;
+    // Safety: `name` must point to a valid, nul-terminated string
+    unsafe fn abx_set_name(&self, name: *const c_char) -> c_int;
+}
+
+pub fn demo() {
+    let binding = Binding {
+        last_name: std::cell::RefCell::new(Default::default()),
+    };
+    println!("{:?}", run(&binding));
+}
+
+fn run<B: AbxBinding>(binding: &B) -> (String, c_int) {
+    // Safety: the binding always returns a static, nul-terminated version string
+    let version = unsafe { CStr::from_ptr(binding.abx_version()) };
+    // Safety: the literal is nul-terminated and outlives the call
+    let status = unsafe { binding.abx_set_name(c"depabx".as_ptr()) };
+    (version.to_string_lossy().into_owned(), status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxBinding};
+    use std::{
+        cell::RefCell,
+        ffi::{c_char, c_int, CStr},
+    };
+
+    #[test]
+    fn it_reads_the_version_and_sets_the_name() {
+        struct FakeBinding {
+            names: RefCell<Vec<String>>,
+        }
+        impl AbxBinding for FakeBinding {
+            fn abx_version(&self) -> *const c_char {
+                c"fake 0.0".as_ptr()
+            }
+            unsafe fn abx_set_name(&self, name: *const c_char) -> c_int {
+                let name = unsafe { CStr::from_ptr(name) };
+                self.names
+                    .borrow_mut()
+                    .push(name.to_string_lossy().into_owned());
+                7
+            }
+        }
+
+        let binding = FakeBinding {
+            names: RefCell::new(Vec::new()),
+        };
+
+        assert_eq!(run(&binding), ("fake 0.0".to_string(), 7));
+        assert_eq!(*binding.names.borrow(), vec!["depabx"]);
+    }
+}
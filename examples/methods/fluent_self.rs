@@ -0,0 +1,83 @@
+use depabx::wrap;
+use request::RequestBuilder;
+
+// A builder-style dependency. Each call returns the builder so calls can be chained.
+mod request {
+    pub struct RequestBuilder {
+        pub parts: Vec<String>,
+    }
+
+    impl RequestBuilder {
+        pub fn new(url: &str) -> Self {
+            RequestBuilder {
+                parts: vec![url.to_string()],
+            }
+        }
+
+        pub fn header(mut self, name: &str, value: &str) -> Self {
+            self.parts.push(format!("{name}: {value}"));
+            self
+        }
+
+        pub fn retries(&mut self, count: u8) -> &mut Self {
+            self.parts.push(format!("retries={count}"));
+            self
+        }
+
+        pub fn build(self) -> String {
+            self.parts.join("\n")
+        }
+    }
+}
+
+// `Self` in the trait means the implementor, so for the generated impl it's the concrete builder, and the value the
+// concrete method returns is handed straight back. This works for by-value (`self -> Self`) and by-reference
+// (`&mut self -> &mut Self`) chaining alike.
+#[wrap(RequestBuilder)]
+trait AbxRequestBuilder {
+    fn abx_header(self, name: &str, value: &str) -> Self;
+    fn abx_retries(&mut self, count: u8) -> &mut Self;
+    fn abx_build(self) -> String;
+}
+
+pub fn demo() {
+    println!("{}", run(RequestBuilder::new("https://example.com")));
+}
+
+fn run<B: AbxRequestBuilder>(builder: B) -> String {
+    let mut builder = builder
+        .abx_header("Accept", "application/json")
+        .abx_header("User-Agent", "depabx");
+    builder.abx_retries(3).abx_retries(5);
+    builder.abx_build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxRequestBuilder};
+
+    #[test]
+    fn it_chains_headers_and_retries() {
+        // The fake chains just like the builder, by returning itself
+        struct FakeBuilder {
+            calls: Vec<String>,
+        }
+        impl AbxRequestBuilder for FakeBuilder {
+            fn abx_header(mut self, name: &str, _value: &str) -> Self {
+                self.calls.push(name.to_string());
+                self
+            }
+            fn abx_retries(&mut self, count: u8) -> &mut Self {
+                self.calls.push(count.to_string());
+                self
+            }
+            fn abx_build(self) -> String {
+                self.calls.join(",")
+            }
+        }
+
+        let built = run(FakeBuilder { calls: Vec::new() });
+
+        assert_eq!(built, "Accept,User-Agent,3,5");
+    }
+}
@@ -0,0 +1,65 @@
+use api::{ApiClient, Reply};
+use depabx::wrap;
+use std::future::Future;
+
+// A client that builds its futures by hand instead of using `async fn`.
+mod api {
+    use std::future::Future;
+
+    pub struct Reply {
+        pub body: String,
+    }
+
+    pub struct ApiClient {
+        pub base_url: String,
+    }
+
+    impl ApiClient {
+        pub fn get(&self, path: &str) -> impl Future<Output = Reply> {
+            let url = format!("{}{path}", self.base_url);
+            async move { Reply { body: url } }
+        }
+    }
+}
+
+// A method returning `impl Future` isn't treated as async. The generated impl returns the concrete future untouched
+// (no extra `async` block or `.await`), so it's polled exactly as the vendor built it.
+#[wrap(ApiClient)]
+trait AbxApiClient {
+    fn abx_get(&self, path: &str) -> impl Future<Output = Reply>;
+}
+
+pub fn demo() {
+    let client = ApiClient {
+        base_url: "https://api.example.com".to_string(),
+    };
+    println!("{}", futures::executor::block_on(run(&client)));
+}
+
+async fn run<C: AbxApiClient>(client: &C) -> String {
+    client.abx_get("/health").await.body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxApiClient, Reply};
+    use std::future::{ready, Future};
+
+    #[test]
+    fn it_returns_the_health_body() {
+        struct FakeApiClient;
+        impl AbxApiClient for FakeApiClient {
+            // A fake can hand back an already completed future
+            fn abx_get(&self, path: &str) -> impl Future<Output = Reply> {
+                ready(Reply {
+                    body: format!("ok {path}"),
+                })
+            }
+        }
+
+        assert_eq!(
+            futures::executor::block_on(run(&FakeApiClient {})),
+            "ok /health"
+        );
+    }
+}
@@ -0,0 +1,80 @@
+use depabx::wrap;
+use index::Index;
+use std::fmt::Display;
+
+// A dependency with heavily generic methods.
+mod index {
+    use std::fmt::Display;
+
+    pub struct Index;
+
+    impl Index {
+        pub fn put<K, V>(&self, key: K, value: V) -> String
+        where
+            K: AsRef<str>,
+            V: Display,
+        {
+            format!("{}={value}", key.as_ref())
+        }
+
+        pub fn first_match<'a, T>(&self, items: &'a [T], wanted: &T) -> Option<&'a T>
+        where
+            T: PartialEq,
+        {
+            items.iter().find(|item| *item == wanted)
+        }
+    }
+}
+
+// Generic parameters, lifetime parameters, and `where` clauses are copied into the generated impl exactly as written on
+// the trait methods, and the delegation forwards every argument without naming the type parameters.
+#[wrap(Index)]
+trait AbxIndex {
+    fn abx_put<K, V>(&self, key: K, value: V) -> String
+    where
+        K: AsRef<str>,
+        V: Display;
+    fn abx_first_match<'a, T>(&self, items: &'a [T], wanted: &T) -> Option<&'a T>
+    where
+        T: PartialEq;
+}
+
+pub fn demo() {
+    println!("{}", run(&Index {}));
+}
+
+fn run<I: AbxIndex>(index: &I) -> String {
+    let sizes = [10, 20, 30];
+    match index.abx_first_match(&sizes, &20) {
+        Some(size) => index.abx_put("size", size),
+        None => index.abx_put("size", "none"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxIndex};
+    use std::fmt::Display;
+
+    #[test]
+    fn it_stores_the_matching_size() {
+        struct FakeIndex;
+        impl AbxIndex for FakeIndex {
+            fn abx_put<K, V>(&self, key: K, value: V) -> String
+            where
+                K: AsRef<str>,
+                V: Display,
+            {
+                format!("fake {}:{value}", key.as_ref())
+            }
+            fn abx_first_match<'a, T>(&self, items: &'a [T], _wanted: &T) -> Option<&'a T>
+            where
+                T: PartialEq,
+            {
+                items.last()
+            }
+        }
+
+        assert_eq!(run(&FakeIndex {}), "fake size:30");
+    }
+}
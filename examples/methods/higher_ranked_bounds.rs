@@ -0,0 +1,77 @@
+use depabx::wrap;
+use lines::Document;
+
+// A dependency that lends out borrowed lines to callbacks, so its bounds have to hold for any lifetime.
+mod lines {
+    pub struct Document {
+        pub text: String,
+    }
+
+    impl Document {
+        pub fn count_matching<F>(&self, f: F) -> usize
+        where
+            for<'a> F: Fn(&'a str) -> bool,
+        {
+            self.text.lines().filter(|line| f(line)).count()
+        }
+
+        pub fn map_first<F>(&self, f: F) -> Option<String>
+        where
+            F: for<'a> Fn(&'a str) -> &'a str,
+        {
+            self.text.lines().next().map(|line| f(line).to_string())
+        }
+    }
+}
+
+// Higher-ranked bounds are accepted in either position, `for<'a> F: ...` or `F: for<'a> ...`, and are copied into
+// the generated impl's `where` clause unchanged.
+#[wrap(Document)]
+trait AbxDocument {
+    fn abx_count_matching<F>(&self, f: F) -> usize
+    where
+        for<'a> F: Fn(&'a str) -> bool;
+    fn abx_map_first<F>(&self, f: F) -> Option<String>
+    where
+        F: for<'a> Fn(&'a str) -> &'a str;
+}
+
+pub fn demo() {
+    let doc = Document {
+        text: "# Title\nTODO: one\ndone\nTODO: two".to_string(),
+    };
+    println!("{:?}", run(&doc));
+}
+
+fn run<D: AbxDocument>(doc: &D) -> (usize, Option<String>) {
+    let todos = doc.abx_count_matching(|line| line.starts_with("TODO"));
+    let title = doc.abx_map_first(|line| line.trim_start_matches("# "));
+    (todos, title)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxDocument};
+
+    #[test]
+    fn it_counts_todos_and_reads_the_title() {
+        struct FakeDocument;
+        impl AbxDocument for FakeDocument {
+            fn abx_count_matching<F>(&self, f: F) -> usize
+            where
+                for<'a> F: Fn(&'a str) -> bool,
+            {
+                ["TODO: a", "b"].iter().filter(|line| f(line)).count()
+            }
+            fn abx_map_first<F>(&self, f: F) -> Option<String>
+            where
+                F: for<'a> Fn(&'a str) -> &'a str,
+            {
+                let line = String::from("# Fake");
+                Some(f(&line).to_string())
+            }
+        }
+
+        assert_eq!(run(&FakeDocument {}), (1, Some("Fake".to_string())));
+    }
+}
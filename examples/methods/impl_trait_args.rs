@@ -0,0 +1,59 @@
+use depabx::wrap;
+use std::fmt::Display;
+use tracer::Tracer;
+
+// A dependency that takes `impl Trait` arguments instead of named type parameters.
+mod tracer {
+    use std::fmt::Display;
+
+    pub struct Tracer;
+
+    impl Tracer {
+        pub fn span(&self, name: impl Into<String>, detail: impl Display) -> String {
+            let name = name.into();
+            println!("[{name}] {detail}");
+            name
+        }
+    }
+}
+
+// `impl Trait` in argument position is kept as-is in the generated impl, and each argument is passed straight through.
+#[wrap(Tracer)]
+trait AbxTracer {
+    fn abx_span(&self, name: impl Into<String>, detail: impl Display) -> String;
+}
+
+pub fn demo() {
+    run(&Tracer {});
+}
+
+fn run<T: AbxTracer>(tracer: &T) -> String {
+    tracer.abx_span("checkout", 3)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxTracer};
+    use std::{cell::RefCell, fmt::Display};
+
+    #[test]
+    fn it_opens_a_checkout_span() {
+        struct FakeTracer {
+            spans: RefCell<Vec<String>>,
+        }
+        impl AbxTracer for FakeTracer {
+            fn abx_span(&self, name: impl Into<String>, detail: impl Display) -> String {
+                let name = name.into();
+                self.spans.borrow_mut().push(format!("{name}: {detail}"));
+                name
+            }
+        }
+
+        let tracer = FakeTracer {
+            spans: RefCell::new(Vec::new()),
+        };
+
+        assert_eq!(run(&tracer), "checkout");
+        assert_eq!(*tracer.spans.borrow(), vec!["checkout: 3"]);
+    }
+}
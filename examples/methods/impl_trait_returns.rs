@@ -0,0 +1,68 @@
+use depabx::wrap;
+use table::{Row, Table};
+
+// A dependency that hides its iterator type behind `impl Iterator`.
+mod table {
+    pub struct Row {
+        pub id: u32,
+        pub active: bool,
+    }
+
+    pub struct Table;
+
+    impl Table {
+        pub fn rows(&self) -> impl Iterator<Item = Row> {
+            (1..=3).map(|id| Row {
+                id,
+                active: id != 2,
+            })
+        }
+    }
+}
+
+// The opaque return type is written the same way in the trait. The generated impl declares the same `impl Trait`
+// return and returns whatever the concrete method produced, so each implementor keeps its own hidden type.
+#[wrap(Table)]
+trait AbxTable {
+    fn abx_rows(&self) -> impl Iterator<Item = Row>;
+}
+
+pub fn demo() {
+    println!("{:?}", run(&Table {}));
+}
+
+fn run<T: AbxTable>(table: &T) -> Vec<u32> {
+    table
+        .abx_rows()
+        .filter(|row| row.active)
+        .map(|row| row.id)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxTable, Row};
+
+    #[test]
+    fn it_keeps_only_active_rows() {
+        // The fake can return any iterator, here a Vec's
+        struct FakeTable;
+        impl AbxTable for FakeTable {
+            fn abx_rows(&self) -> impl Iterator<Item = Row> {
+                vec![
+                    Row {
+                        id: 7,
+                        active: false,
+                    },
+                    Row {
+                        id: 8,
+                        active: true,
+                    },
+                ]
+                .into_iter()
+            }
+        }
+
+        assert_eq!(run(&FakeTable {}), vec![8]);
+    }
+}
@@ -0,0 +1,76 @@
+use depabx::wrap;
+use scanner::{Record, Scanner};
+
+// A dependency that returns concrete, nameable iterator types.
+mod scanner {
+    pub struct Record {
+        pub key: String,
+    }
+
+    impl Record {
+        fn into_key(self) -> String {
+            self.key
+        }
+    }
+
+    pub struct Scanner;
+
+    impl Scanner {
+        pub fn scan(&self) -> std::vec::IntoIter<Record> {
+            vec![
+                Record {
+                    key: "a".to_string(),
+                },
+                Record {
+                    key: "b".to_string(),
+                },
+            ]
+            .into_iter()
+        }
+
+        pub fn keys(&self) -> std::iter::Map<std::vec::IntoIter<Record>, fn(Record) -> String> {
+            self.scan().map(Record::into_key as fn(Record) -> String)
+        }
+    }
+}
+
+// A concrete iterator type can be kept in the trait as-is, however long its path is. Fakes then have to produce that
+// exact type too. When that's too restrictive, declare an erased `Box<dyn Iterator>` in the trait and mark the method
+// with `#[abx(boxed)]`; the generated impl boxes the concrete iterator.
+#[wrap(Scanner)]
+trait AbxScanner {
+    fn abx_scan(&self) -> std::vec::IntoIter<Record>;
+    #[abx(boxed)]
+    fn abx_keys(&self) -> Box<dyn Iterator<Item = String> + '_>;
+}
+
+pub fn demo() {
+    println!("{:?}", run(&Scanner {}));
+}
+
+fn run<S: AbxScanner>(scanner: &S) -> (usize, String) {
+    let count = scanner.abx_scan().count();
+    let keys = scanner.abx_keys().collect::<Vec<_>>().join("");
+    (count, keys)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxScanner, Record};
+
+    #[test]
+    fn it_counts_records_and_joins_keys() {
+        struct FakeScanner;
+        impl AbxScanner for FakeScanner {
+            fn abx_scan(&self) -> std::vec::IntoIter<Record> {
+                Vec::new().into_iter()
+            }
+            // With the erased signature, any iterator will do
+            fn abx_keys(&self) -> Box<dyn Iterator<Item = String> + '_> {
+                Box::new(["x", "y", "z"].into_iter().map(String::from))
+            }
+        }
+
+        assert_eq!(run(&FakeScanner {}), (0, "xyz".to_string()));
+    }
+}
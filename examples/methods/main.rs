@@ -0,0 +1,61 @@
+// Examples of wrapping dependencies whose methods have shapes beyond the plain `&self` methods in the sample.
+//
+// Each module's `demo` wires up its real dependency the way `main` does in the sample, and its tests run with
+// `cargo test`.
+mod argument_coercion;
+mod associated_functions;
+mod async_methods;
+mod borrowed_returns;
+mod boxed_returns;
+mod closure_params;
+mod const_generics;
+mod consuming_methods;
+mod cow_returns;
+mod feature_gated_methods;
+mod ffi_types;
+mod fluent_self;
+mod future_returns;
+mod generic_methods;
+mod higher_ranked_bounds;
+mod impl_trait_args;
+mod impl_trait_returns;
+mod iterator_returns;
+mod method_attributes;
+mod mut_receivers;
+mod nested_abstractions;
+mod result_errors;
+mod send_futures;
+mod slice_params;
+mod smart_pointer_receivers;
+mod stream_returns;
+mod unsafe_methods;
+
+fn main() {
+    async_methods::demo();
+    mut_receivers::demo();
+    consuming_methods::demo();
+    associated_functions::demo();
+    result_errors::demo();
+    borrowed_returns::demo();
+    generic_methods::demo();
+    impl_trait_args::demo();
+    impl_trait_returns::demo();
+    boxed_returns::demo();
+    closure_params::demo();
+    iterator_returns::demo();
+    future_returns::demo();
+    stream_returns::demo();
+    unsafe_methods::demo();
+    smart_pointer_receivers::demo();
+    const_generics::demo();
+    higher_ranked_bounds::demo();
+    method_attributes::demo();
+    argument_coercion::demo();
+    slice_params::demo();
+    cow_returns::demo();
+    fluent_self::demo();
+    nested_abstractions::demo();
+    ffi_types::demo();
+    send_futures::demo();
+    feature_gated_methods::demo();
+}
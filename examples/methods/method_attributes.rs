@@ -0,0 +1,94 @@
+use depabx::wrap;
+use lock::FileLock;
+
+// A lock file dependency with an API that has grown over time.
+mod lock {
+    use std::cell::Cell;
+
+    pub struct FileLock {
+        pub held: Cell<bool>,
+    }
+
+    impl FileLock {
+        pub fn try_acquire(&self) -> bool {
+            !self.held.replace(true)
+        }
+
+        pub fn release(&self) {
+            self.held.set(false);
+        }
+
+        pub fn unlock(&self) {
+            self.release();
+        }
+
+        #[cfg(unix)]
+        pub fn owner_pid(&self) -> u32 {
+            std::process::id()
+        }
+    }
+}
+
+// Attributes on trait methods are honored. Lints like `#[must_use]` and `#[deprecated]` apply to calls, which resolve
+// to the trait declaration, so they stay there. `#[cfg(...)]` is copied onto the generated impl method as well, so
+// the delegation is compiled in exactly the configurations the trait method exists in.
+#[wrap(FileLock)]
+trait AbxFileLock {
+    #[must_use]
+    fn abx_try_acquire(&self) -> bool;
+    fn abx_release(&self);
+    #[deprecated(note = "use abx_release")]
+    fn abx_unlock(&self);
+    #[cfg(unix)]
+    fn abx_owner_pid(&self) -> u32;
+}
+
+pub fn demo() {
+    let lock = FileLock {
+        held: std::cell::Cell::new(false),
+    };
+    println!("{}", run(&lock));
+    // Deprecated methods still delegate, but calling one warns unless it's allowed
+    #[allow(deprecated)]
+    lock.abx_unlock();
+}
+
+fn run<L: AbxFileLock>(lock: &L) -> String {
+    if !lock.abx_try_acquire() {
+        return "busy".to_string();
+    }
+    #[cfg(unix)]
+    let status = format!("held by {}", lock.abx_owner_pid());
+    #[cfg(not(unix))]
+    let status = "held".to_string();
+    lock.abx_release();
+    status
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxFileLock};
+
+    #[test]
+    fn it_reports_busy_when_already_locked() {
+        // The fake has to mirror the cfg, since the method only exists on unix
+        struct FakeFileLock;
+        impl AbxFileLock for FakeFileLock {
+            fn abx_try_acquire(&self) -> bool {
+                false
+            }
+            fn abx_release(&self) {
+                panic!("released a lock that was never acquired");
+            }
+            fn abx_unlock(&self) {
+                panic!("called a deprecated method");
+            }
+            #[cfg(unix)]
+            fn abx_owner_pid(&self) -> u32 {
+                1
+            }
+        }
+
+        assert_eq!(run(&FakeFileLock {}), "busy");
+    }
+}
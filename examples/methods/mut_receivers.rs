@@ -0,0 +1,71 @@
+use db::Connection;
+use depabx::wrap;
+
+// A database connection. Writing needs exclusive access, so `insert` takes `&mut self`.
+mod db {
+    pub struct Connection {
+        rows: Vec<String>,
+    }
+
+    impl Connection {
+        pub fn open(_url: &str) -> Self {
+            Connection { rows: Vec::new() }
+        }
+
+        pub fn insert(&mut self, row: String) -> usize {
+            self.rows.push(row);
+            self.rows.len()
+        }
+
+        pub fn count(&self) -> usize {
+            self.rows.len()
+        }
+    }
+}
+
+// Receivers are carried over as written: `&mut self` in the trait delegates to the concrete `&mut self` method, and
+// `&self` methods can sit right next to them.
+#[wrap(Connection)]
+trait AbxConnection {
+    fn abx_insert(&mut self, row: String) -> usize;
+    fn abx_count(&self) -> usize;
+}
+
+pub fn demo() {
+    run(&mut Connection::open("postgres://prod"));
+}
+
+fn run<C: AbxConnection>(conn: &mut C) -> usize {
+    conn.abx_insert("first row".to_string());
+    conn.abx_insert("second row".to_string());
+    conn.abx_count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxConnection};
+
+    #[test]
+    fn it_inserts_two_rows() {
+        // Since the trait hands out `&mut self`, the fake can track calls in a plain Vec; no RefCell needed
+        struct FakeConnection {
+            inserted: Vec<String>,
+        }
+        impl AbxConnection for FakeConnection {
+            fn abx_insert(&mut self, row: String) -> usize {
+                self.inserted.push(row);
+                self.inserted.len()
+            }
+            fn abx_count(&self) -> usize {
+                self.inserted.len()
+            }
+        }
+
+        let mut conn = FakeConnection {
+            inserted: Vec::new(),
+        };
+
+        assert_eq!(run(&mut conn), 2);
+        assert_eq!(conn.inserted, vec!["first row", "second row"]);
+    }
+}
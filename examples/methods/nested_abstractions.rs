@@ -0,0 +1,70 @@
+use db::{Database, Session};
+use depabx::wrap;
+
+// A vendor client whose methods hand out other vendor types.
+mod db {
+    pub struct Database;
+
+    pub struct Session {
+        pub id: u32,
+    }
+
+    impl Database {
+        pub fn session(&self) -> Session {
+            Session { id: 1 }
+        }
+    }
+
+    impl Session {
+        pub fn query(&self, sql: &str) -> String {
+            format!("session {} ran {sql}", self.id)
+        }
+    }
+}
+
+// Wrap the returned type on its own...
+#[wrap(Session)]
+trait AbxSession {
+    fn abx_query(&self, sql: &str) -> String;
+}
+
+// ...then return `impl AbxSession` instead of `Session`. The generated impl still returns the concrete `Session`, which
+// satisfies the opaque type through the impl above, so the vendor type never shows up in the abstraction. If `Session`
+// isn't wrapped, the build fails at this return type with "`Session` doesn't implement `AbxSession`" and a note
+// suggesting `#[wrap(Session)]` on `AbxSession`.
+#[wrap(Database)]
+trait AbxDatabase {
+    fn abx_session(&self) -> impl AbxSession;
+}
+
+pub fn demo() {
+    println!("{}", run(&Database {}));
+}
+
+fn run<D: AbxDatabase>(db: &D) -> String {
+    db.abx_session().abx_query("SELECT 1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxDatabase, AbxSession};
+
+    #[test]
+    fn it_queries_through_a_new_session() {
+        // Both layers are faked, and neither fake knows about the vendor types
+        struct FakeSession;
+        impl AbxSession for FakeSession {
+            fn abx_query(&self, sql: &str) -> String {
+                format!("fake: {sql}")
+            }
+        }
+        struct FakeDatabase;
+        impl AbxDatabase for FakeDatabase {
+            fn abx_session(&self) -> impl AbxSession {
+                FakeSession {}
+            }
+        }
+
+        assert_eq!(run(&FakeDatabase {}), "fake: SELECT 1");
+    }
+}
@@ -0,0 +1,86 @@
+use depabx::wrap;
+use std::fmt;
+use vendor::{Store, VendorError};
+
+// A vendor client with its own error type. We don't want `VendorError` leaking into the rest of our code.
+mod vendor {
+    #[derive(Debug)]
+    pub enum VendorError {
+        NotFound,
+        Throttled,
+    }
+
+    pub struct Store;
+
+    impl Store {
+        pub fn fetch(&self, id: u32) -> Result<String, VendorError> {
+            match id {
+                0 => Err(VendorError::NotFound),
+                1..=100 => Ok(format!("record {id}")),
+                _ => Err(VendorError::Throttled),
+            }
+        }
+    }
+}
+
+// Our own error type. All it needs is a `From` impl for the vendor error.
+#[derive(Debug)]
+pub enum StoreError {
+    Missing,
+    Unavailable,
+}
+
+impl From<VendorError> for StoreError {
+    fn from(e: VendorError) -> Self {
+        match e {
+            VendorError::NotFound => StoreError::Missing,
+            VendorError::Throttled => StoreError::Unavailable,
+        }
+    }
+}
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StoreError::Missing => write!(f, "record is missing"),
+            StoreError::Unavailable => write!(f, "store is unavailable"),
+        }
+    }
+}
+
+// Declare the error type you want in the trait. When a method returns a `Result`, the generated impl ends the
+// delegation with `.map_err(Into::into)`, so the vendor error is converted through the `From` impl above. When the
+// error types are identical the conversion is a no-op.
+#[wrap(Store)]
+trait AbxStore {
+    fn abx_fetch(&self, id: u32) -> Result<String, StoreError>;
+}
+
+pub fn demo() {
+    println!("{}", run(&Store {}, 7));
+}
+
+fn run<S: AbxStore>(store: &S, id: u32) -> String {
+    match store.abx_fetch(id) {
+        Ok(record) => record,
+        Err(e) => format!("Couldn't load {id}: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxStore, StoreError};
+
+    #[test]
+    fn it_reports_missing_records() {
+        // The fake only ever deals in our own error type
+        struct FakeStore;
+        impl AbxStore for FakeStore {
+            fn abx_fetch(&self, _id: u32) -> Result<String, StoreError> {
+                Err(StoreError::Missing)
+            }
+        }
+
+        assert_eq!(run(&FakeStore {}, 3), "Couldn't load 3: record is missing");
+    }
+}
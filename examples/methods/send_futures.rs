@@ -0,0 +1,55 @@
+use depabx::wrap;
+use feed::FeedClient;
+use std::{sync::Arc, thread};
+
+// An async client that's used from background tasks.
+mod feed {
+    pub struct FeedClient;
+
+    impl FeedClient {
+        pub async fn fetch(&self, topic: &str) -> Vec<String> {
+            vec![format!("{topic}: headline")]
+        }
+    }
+}
+
+// A plain `async fn` in a trait says nothing about whether its future is `Send`, so generic callers can't move it to
+// another thread. `#[abx(send)]` desugars the method to `fn ... -> impl Future<Output = T> + Send`; put it on the
+// trait instead to apply it to every async method.
+#[wrap(FeedClient)]
+trait AbxFeedClient {
+    #[abx(send)]
+    async fn abx_fetch(&self, topic: &str) -> Vec<String>;
+}
+
+pub fn demo() {
+    let handle = run(Arc::new(FeedClient {}));
+    println!("{}", handle.join().unwrap());
+}
+
+// Fetches on a background thread. Without the `Send` bound on the future, this wouldn't compile.
+fn run<C: AbxFeedClient + Send + Sync + 'static>(client: Arc<C>) -> thread::JoinHandle<usize> {
+    let task = async move { client.abx_fetch("news").await.len() };
+    thread::spawn(move || futures::executor::block_on(task))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxFeedClient};
+    use std::sync::Arc;
+
+    #[test]
+    fn it_counts_fetched_items_in_the_background() {
+        // An `async fn` fulfils the desugared signature as long as its future is `Send`
+        struct FakeFeedClient;
+        impl AbxFeedClient for FakeFeedClient {
+            async fn abx_fetch(&self, topic: &str) -> Vec<String> {
+                vec![topic.to_string(); 4]
+            }
+        }
+
+        let handle = run(Arc::new(FakeFeedClient {}));
+
+        assert_eq!(handle.join().unwrap(), 4);
+    }
+}
@@ -0,0 +1,58 @@
+use cipher::Cipher;
+use depabx::wrap;
+
+// A crypto-style dependency that works on byte slices and fixed-size keys.
+mod cipher {
+    pub struct Cipher;
+
+    impl Cipher {
+        pub fn checksum(&self, data: &[u8]) -> u32 {
+            data.iter().map(|b| *b as u32).sum()
+        }
+
+        pub fn xor_in_place(&self, key: [u8; 4], buf: &mut [u8]) {
+            for (i, byte) in buf.iter_mut().enumerate() {
+                *byte ^= key[i % key.len()];
+            }
+        }
+    }
+}
+
+// Slice and array parameters, shared or `&mut`, are ordinary typed arguments and are forwarded by name.
+#[wrap(Cipher)]
+trait AbxCipher {
+    fn abx_checksum(&self, data: &[u8]) -> u32;
+    fn abx_xor_in_place(&self, key: [u8; 4], buf: &mut [u8]);
+}
+
+pub fn demo() {
+    println!("{:?}", run(&Cipher {}, b"hello"));
+}
+
+// Encrypts the message and returns it along with the checksum of the original
+fn run<C: AbxCipher>(cipher: &C, message: &[u8]) -> (u32, Vec<u8>) {
+    let checksum = cipher.abx_checksum(message);
+    let mut buf = message.to_vec();
+    cipher.abx_xor_in_place([1, 2, 3, 4], &mut buf);
+    (checksum, buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxCipher};
+
+    #[test]
+    fn it_checksums_before_encrypting() {
+        struct FakeCipher;
+        impl AbxCipher for FakeCipher {
+            fn abx_checksum(&self, data: &[u8]) -> u32 {
+                data.len() as u32
+            }
+            fn abx_xor_in_place(&self, key: [u8; 4], buf: &mut [u8]) {
+                buf.fill(key[0]);
+            }
+        }
+
+        assert_eq!(run(&FakeCipher {}, b"abc"), (3, vec![1, 1, 1]));
+    }
+}
@@ -0,0 +1,85 @@
+use actor::Actor;
+use depabx::wrap;
+use std::{pin::Pin, rc::Rc, sync::Arc};
+
+// An actor-style dependency whose methods take smart-pointer receivers.
+mod actor {
+    use std::{pin::Pin, rc::Rc, sync::Arc};
+
+    pub struct Actor {
+        pub name: String,
+        pub ticks: u32,
+    }
+
+    impl Actor {
+        pub fn handle(self: Arc<Self>, msg: &str) -> String {
+            format!("{} got {msg}", self.name)
+        }
+
+        pub fn owners(self: Rc<Self>) -> usize {
+            Rc::strong_count(&self)
+        }
+
+        pub fn tick(mut self: Pin<&mut Self>) -> u32 {
+            self.ticks += 1;
+            self.ticks
+        }
+    }
+}
+
+// Typed `self` receivers are kept on the trait methods and the generated impl passes `self` along as is, which works
+// for `Arc<Self>`, `Rc<Self>`, `Pin<&mut Self>` and the other receiver types Rust accepts.
+#[wrap(Actor)]
+trait AbxActor {
+    fn abx_handle(self: Arc<Self>, msg: &str) -> String;
+    fn abx_owners(self: Rc<Self>) -> usize;
+    fn abx_tick(self: Pin<&mut Self>) -> u32;
+}
+
+pub fn demo() {
+    let actor = |name: &str| Actor {
+        name: name.to_string(),
+        ticks: 0,
+    };
+    println!("{:?}", run(actor("billing"), Rc::new(actor("audit"))));
+}
+
+// Each receiver form hands out the actor differently: pinned in place, shared across threads, or shared locally.
+fn run<A: AbxActor + Unpin>(mut worker: A, auditor: Rc<A>) -> (u32, String, usize) {
+    Pin::new(&mut worker).abx_tick();
+    let ticks = Pin::new(&mut worker).abx_tick();
+    let reply = Arc::new(worker).abx_handle("invoice");
+    let _registered = Rc::clone(&auditor);
+    (ticks, reply, auditor.abx_owners())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxActor};
+    use std::{pin::Pin, rc::Rc, sync::Arc};
+
+    #[test]
+    fn it_ticks_handles_and_counts_owners() {
+        struct FakeActor {
+            ticks: u32,
+        }
+        impl AbxActor for FakeActor {
+            fn abx_handle(self: Arc<Self>, msg: &str) -> String {
+                format!("handled {msg} after {} ticks", self.ticks)
+            }
+            fn abx_owners(self: Rc<Self>) -> usize {
+                Rc::strong_count(&self)
+            }
+            fn abx_tick(mut self: Pin<&mut Self>) -> u32 {
+                self.ticks += 1;
+                self.ticks
+            }
+        }
+
+        let (ticks, reply, owners) = run(FakeActor { ticks: 0 }, Rc::new(FakeActor { ticks: 0 }));
+
+        assert_eq!(ticks, 2);
+        assert_eq!(reply, "handled invoice after 2 ticks");
+        assert_eq!(owners, 2);
+    }
+}
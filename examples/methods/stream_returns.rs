@@ -0,0 +1,82 @@
+use broker::{Broker, Msg};
+use depabx::wrap;
+use futures::{Stream, StreamExt};
+use std::pin::Pin;
+
+// An event-driven dependency that pushes messages through streams.
+mod broker {
+    use futures::{stream, Stream};
+    use std::pin::Pin;
+
+    pub struct Msg {
+        pub text: String,
+    }
+
+    pub struct Broker;
+
+    impl Broker {
+        pub fn subscribe(&self, topic: &str) -> impl Stream<Item = Msg> {
+            let text = format!("welcome to {topic}");
+            stream::iter(vec![Msg { text }])
+        }
+
+        pub fn firehose(&self) -> Pin<Box<dyn Stream<Item = Msg> + Send>> {
+            Box::pin(stream::empty())
+        }
+    }
+}
+
+// Streams need nothing special: an `impl Stream` return is forwarded like any other opaque return, and a pinned boxed
+// stream is just a type. The code under test drives both with `StreamExt`.
+#[wrap(Broker)]
+trait AbxBroker {
+    fn abx_subscribe(&self, topic: &str) -> impl Stream<Item = Msg>;
+    fn abx_firehose(&self) -> Pin<Box<dyn Stream<Item = Msg> + Send>>;
+}
+
+pub fn demo() {
+    println!("{:?}", futures::executor::block_on(run(&Broker {})));
+}
+
+async fn run<B: AbxBroker>(broker: &B) -> Vec<String> {
+    let mut texts: Vec<String> = broker
+        .abx_subscribe("alerts")
+        .map(|msg| msg.text)
+        .collect()
+        .await;
+    let mut firehose = broker.abx_firehose();
+    while let Some(msg) = firehose.next().await {
+        texts.push(msg.text);
+    }
+    texts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxBroker, Msg};
+    use futures::{stream, Stream};
+    use std::pin::Pin;
+
+    #[test]
+    fn it_collects_messages_from_both_streams() {
+        struct FakeBroker;
+        impl AbxBroker for FakeBroker {
+            fn abx_subscribe(&self, topic: &str) -> impl Stream<Item = Msg> {
+                stream::once(async move {
+                    Msg {
+                        text: format!("{topic} 1"),
+                    }
+                })
+            }
+            fn abx_firehose(&self) -> Pin<Box<dyn Stream<Item = Msg> + Send>> {
+                Box::pin(stream::iter(vec![Msg {
+                    text: "firehose 1".to_string(),
+                }]))
+            }
+        }
+
+        let texts = futures::executor::block_on(run(&FakeBroker {}));
+
+        assert_eq!(texts, vec!["alerts 1", "firehose 1"]);
+    }
+}
@@ -0,0 +1,67 @@
+use depabx::wrap;
+use device::Device;
+
+// An FFI-backed type. Its methods are `unsafe` because the caller has to uphold the device's contract.
+mod device {
+    pub struct Device {
+        registers: Vec<u8>,
+    }
+
+    impl Device {
+        pub fn open() -> Self {
+            Device {
+                registers: vec![0x10, 0x20, 0x30],
+            }
+        }
+
+        pub fn len(&self) -> usize {
+            self.registers.len()
+        }
+
+        // Safety: `index` must be less than `len()`
+        pub unsafe fn register(&self, index: usize) -> u8 {
+            unsafe { *self.registers.get_unchecked(index) }
+        }
+    }
+}
+
+// An `unsafe fn` stays `unsafe` in the trait, so callers (including callers through a mock) still take on the same
+// safety contract. The generated impl makes the concrete call inside an `unsafe` block.
+#[wrap(Device)]
+trait AbxDevice {
+    fn abx_len(&self) -> usize;
+    // Safety: `index` must be less than `abx_len()`
+    unsafe fn abx_register(&self, index: usize) -> u8;
+}
+
+pub fn demo() {
+    println!("{}", run(&Device::open()));
+}
+
+fn run<D: AbxDevice>(device: &D) -> u32 {
+    (0..device.abx_len())
+        // Safety: every index is below `abx_len()`
+        .map(|i| unsafe { device.abx_register(i) } as u32)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxDevice};
+
+    #[test]
+    fn it_sums_every_register() {
+        struct FakeDevice;
+        impl AbxDevice for FakeDevice {
+            fn abx_len(&self) -> usize {
+                2
+            }
+            unsafe fn abx_register(&self, index: usize) -> u8 {
+                assert!(index < 2, "read past the last register");
+                5
+            }
+        }
+
+        assert_eq!(run(&FakeDevice {}), 10);
+    }
+}
@@ -0,0 +1,52 @@
+use depabx::wrap;
+use hardware::Sensor;
+
+// A hardware sensor. There's no sensor on CI, so tests can't use the real readings.
+mod hardware {
+    pub struct Sensor;
+
+    impl Sensor {
+        // Only called from the generated impl, which test builds leave out
+        #[cfg_attr(test, allow(dead_code))]
+        pub fn read_celsius(&self) -> f64 {
+            21.5
+        }
+    }
+}
+
+// `cfg(...)` puts the generated impl behind that predicate. Here it's only compiled outside of tests, which frees test
+// builds to implement the trait for `Sensor` themselves without conflicting with the generated impl.
+#[wrap(Sensor, cfg(not(test)))]
+trait AbxSensor {
+    fn abx_read_celsius(&self) -> f64;
+}
+
+pub fn demo() {
+    println!("{}", run(&Sensor {}));
+}
+
+fn run<S: AbxSensor>(sensor: &S) -> String {
+    let celsius = sensor.abx_read_celsius();
+    if celsius > 30.0 {
+        format!("too hot: {celsius}")
+    } else {
+        format!("ok: {celsius}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxSensor, Sensor};
+
+    // Test builds provide their own impl for the real type, which also keeps `demo` compiling under test
+    impl AbxSensor for Sensor {
+        fn abx_read_celsius(&self) -> f64 {
+            35.0
+        }
+    }
+
+    #[test]
+    fn it_warns_when_too_hot() {
+        assert_eq!(run(&Sensor {}), "too hot: 35");
+    }
+}
@@ -0,0 +1,51 @@
+use depabx::wrap;
+use std::{rc::Rc, sync::Arc};
+use translate::Translator;
+
+// A translation client that's usually shared behind a smart pointer.
+mod translate {
+    pub struct Translator;
+
+    impl Translator {
+        pub fn translate(&self, text: &str) -> String {
+            format!("¡{text}!")
+        }
+    }
+}
+
+// `deref` implements `AbxTranslator` for `&Translator`, `&mut Translator`, `Box<Translator>`, `Rc<Translator>` and
+// `Arc<Translator>` next to the impl for `Translator` itself, forwarding through `Deref`, so shared clients satisfy
+// the trait without unwrapping them first. Custom smart pointers aren't covered, since a blanket impl over `Deref`
+// would overlap the impl for another crate's type.
+#[wrap(Translator, deref)]
+trait AbxTranslator {
+    fn abx_translate(&self, text: &str) -> String;
+}
+
+pub fn demo() {
+    let shared = Arc::new(Translator {});
+    println!("{}", run(&shared));
+    println!("{}", run(&Rc::new(Translator {})));
+    println!("{}", run(&Box::new(Translator {})));
+}
+
+fn run<T: AbxTranslator>(translator: &T) -> String {
+    translator.abx_translate("hola")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxTranslator};
+
+    #[test]
+    fn it_translates_the_greeting() {
+        struct FakeTranslator;
+        impl AbxTranslator for FakeTranslator {
+            fn abx_translate(&self, text: &str) -> String {
+                text.chars().rev().collect()
+            }
+        }
+
+        assert_eq!(run(&FakeTranslator {}), "aloh");
+    }
+}
@@ -0,0 +1,55 @@
+use chat::SlackClient;
+use depabx::wrap;
+
+// A chat client.
+mod chat {
+    pub struct SlackClient;
+
+    impl SlackClient {
+        pub fn post_message(&self, channel: &str, text: &str) -> String {
+            format!("#{channel}: {text}")
+        }
+    }
+}
+
+// `dump` writes the code generated for this trait to a file, relative to the crate root, every time the macro runs.
+// It's plain formatted Rust, handy for reviewing what the macro does or checking it into a snapshot. Setting
+// `DEPABX_DUMP_DIR` does the same for every `#[wrap]` in the build, one file per trait.
+#[wrap(SlackClient, dump = "target/depabx/slack.rs")]
+trait AbxSlackClient {
+    fn abx_post_message(&self, channel: &str, text: &str) -> String;
+}
+
+pub fn demo() {
+    println!("{}", run(&SlackClient {}));
+}
+
+fn run<S: AbxSlackClient>(slack: &S) -> String {
+    slack.abx_post_message("deploys", "v2 is live")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxSlackClient};
+
+    #[test]
+    fn it_announces_the_deploy() {
+        struct FakeSlackClient;
+        impl AbxSlackClient for FakeSlackClient {
+            fn abx_post_message(&self, channel: &str, _text: &str) -> String {
+                channel.to_string()
+            }
+        }
+
+        assert_eq!(run(&FakeSlackClient {}), "deploys");
+    }
+
+    #[test]
+    fn it_dumps_the_generated_impl() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/target/depabx/slack.rs");
+        let dumped = std::fs::read_to_string(path).unwrap();
+
+        assert!(dumped.contains("impl AbxSlackClient for SlackClient"));
+        assert!(dumped.contains("<SlackClient>::post_message(self, channel, text)"));
+    }
+}
@@ -0,0 +1,58 @@
+use depabx::wrap;
+use std::fmt::Display;
+use tracing_sink::EventSink;
+
+// A low-level event sink.
+mod tracing_sink {
+    pub struct EventSink;
+
+    impl EventSink {
+        pub fn emit(&self, level: u8, message: &str) -> String {
+            format!("<{level}> {message}")
+        }
+    }
+}
+
+// `ext` also generates `trait AbxEventSinkExt: AbxEventSink` with a blanket impl for every `T: AbxEventSink + ?Sized`.
+// Methods marked `#[abx(ext)]` are moved there with their bodies, so the core trait (the one fakes implement) is left
+// with just the wrapped methods, while callers still get the helpers once the extension trait is imported.
+#[wrap(EventSink, ext)]
+trait AbxEventSink {
+    fn abx_emit(&self, level: u8, message: &str) -> String;
+
+    #[abx(ext)]
+    fn abx_info<T: Display>(&self, message: T) -> String {
+        self.abx_emit(6, &message.to_string())
+    }
+
+    #[abx(ext)]
+    fn abx_error<T: Display>(&self, message: T) -> String {
+        self.abx_emit(3, &message.to_string())
+    }
+}
+
+pub fn demo() {
+    println!("{:?}", run(&EventSink {}));
+}
+
+fn run<S: AbxEventSink>(sink: &S) -> Vec<String> {
+    vec![sink.abx_info("job started"), sink.abx_error(404)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxEventSink};
+
+    #[test]
+    fn it_emits_info_then_error() {
+        // Only the core method needs faking; the helpers come from the extension trait
+        struct FakeEventSink;
+        impl AbxEventSink for FakeEventSink {
+            fn abx_emit(&self, level: u8, message: &str) -> String {
+                format!("{level}:{message}")
+            }
+        }
+
+        assert_eq!(run(&FakeEventSink {}), vec!["6:job started", "3:404"]);
+    }
+}
@@ -0,0 +1,48 @@
+use depabx::wrap;
+
+// An optional dependency. In a real crate this would be something like `aws-sdk-s3`, pulled in by the `s3` feature.
+#[cfg(feature = "s3")]
+mod s3 {
+    pub struct Client;
+
+    impl Client {
+        pub fn put_object(&self, key: &str, body: &[u8]) -> String {
+            format!("s3://bucket/{key} ({} bytes)", body.len())
+        }
+    }
+}
+
+// `feature` gates only the generated impl, as if it were written under `#[cfg(feature = "s3")]`; the target path is
+// never resolved without the feature. The trait is always available, so code written against it (and its tests)
+// builds with or without the vendor crate.
+#[wrap(s3::Client, feature = "s3")]
+trait AbxObjectStore {
+    fn abx_put_object(&self, key: &str, body: &[u8]) -> String;
+}
+
+pub fn demo() {
+    #[cfg(feature = "s3")]
+    println!("{}", run(&s3::Client {}));
+}
+
+#[cfg_attr(not(feature = "s3"), allow(dead_code))]
+fn run<S: AbxObjectStore>(store: &S) -> String {
+    store.abx_put_object("reports/daily.csv", b"id,total\n1,20\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxObjectStore};
+
+    #[test]
+    fn it_uploads_the_daily_report() {
+        struct FakeObjectStore;
+        impl AbxObjectStore for FakeObjectStore {
+            fn abx_put_object(&self, key: &str, body: &[u8]) -> String {
+                format!("{key}:{}", body.len())
+            }
+        }
+
+        assert_eq!(run(&FakeObjectStore {}), "reports/daily.csv:14");
+    }
+}
@@ -0,0 +1,65 @@
+use depabx::wrap;
+use smtp::SmtpClient;
+
+// A vendor SMTP client.
+mod smtp {
+    pub struct SmtpClient;
+
+    impl SmtpClient {
+        pub fn send(&self, to: &str, body: &str) -> bool {
+            println!("To {to}: {body}");
+            true
+        }
+    }
+}
+
+// Our own service type, which holds the vendor client alongside its other state.
+pub struct Notifier {
+    pub client: SmtpClient,
+    pub signature: String,
+}
+
+// `via` names a field to delegate through, and the impl is generated on the outer type:
+// `impl AbxMailer for Notifier { fn abx_send(&self, ..) -> bool { self.client.send(..) } }`.
+#[wrap(Notifier, via = client)]
+trait AbxMailer {
+    fn abx_send(&self, to: &str, body: &str) -> bool;
+}
+
+pub fn demo() {
+    let notifier = Notifier {
+        client: SmtpClient {},
+        signature: "-- The team".to_string(),
+    };
+    println!("{}", run(&notifier, &notifier.signature));
+}
+
+fn run<M: AbxMailer>(mailer: &M, signature: &str) -> bool {
+    mailer.abx_send("ops@example.com", &format!("Deploy finished\n{signature}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxMailer};
+    use std::cell::RefCell;
+
+    #[test]
+    fn it_signs_the_message() {
+        struct FakeMailer {
+            bodies: RefCell<Vec<String>>,
+        }
+        impl AbxMailer for FakeMailer {
+            fn abx_send(&self, _to: &str, body: &str) -> bool {
+                self.bodies.borrow_mut().push(body.to_string());
+                false
+            }
+        }
+
+        let mailer = FakeMailer {
+            bodies: RefCell::new(Vec::new()),
+        };
+
+        assert!(!run(&mailer, "-- Tests"));
+        assert_eq!(*mailer.bodies.borrow(), vec!["Deploy finished\n-- Tests"]);
+    }
+}
@@ -0,0 +1,54 @@
+use depabx::wrap_impl;
+
+// One of our own types. We write its methods once, in an ordinary inherent impl.
+pub struct RateLimiter {
+    pub limit: u32,
+}
+
+// `#[wrap_impl]` is the sibling of `#[wrap]` for types we own. It leaves the impl block as it is, and also generates
+// `trait AbxRateLimiter` from its public methods (with the usual `abx_` prefix) plus the delegating impl.
+// Private helpers like `remaining` aren't part of the trait. It takes the same options as `#[wrap]`.
+#[wrap_impl]
+impl RateLimiter {
+    pub fn allow(&self, used: u32) -> bool {
+        self.remaining(used) > 0
+    }
+
+    pub fn limit(&self) -> u32 {
+        self.limit
+    }
+
+    fn remaining(&self, used: u32) -> u32 {
+        self.limit.saturating_sub(used)
+    }
+}
+
+pub fn demo() {
+    println!("{:?}", run(&RateLimiter { limit: 2 }));
+}
+
+fn run<R: AbxRateLimiter>(limiter: &R) -> Vec<bool> {
+    (0..=limiter.abx_limit())
+        .map(|used| limiter.abx_allow(used))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxRateLimiter};
+
+    #[test]
+    fn it_checks_every_usage_level_up_to_the_limit() {
+        struct FakeRateLimiter;
+        impl AbxRateLimiter for FakeRateLimiter {
+            fn abx_allow(&self, used: u32) -> bool {
+                used.is_multiple_of(2)
+            }
+            fn abx_limit(&self) -> u32 {
+                3
+            }
+        }
+
+        assert_eq!(run(&FakeRateLimiter {}), vec![true, false, true, false]);
+    }
+}
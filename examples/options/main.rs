@@ -0,0 +1,58 @@
+// Examples of the options `#[wrap]` accepts after the target type, and of the per-method `#[abx(...)]` options that
+// change how a single method is delegated.
+//
+// Each module's `demo` wires up its real dependency the way `main` does in the sample, and its tests run with
+// `cargo test`.
+mod cfg_impl;
+mod deref;
+mod dump;
+mod extension_traits;
+mod feature_gated_impls;
+mod field_delegation;
+mod inherent_impls;
+mod map_arg;
+mod map_return;
+mod method_docs;
+mod method_list;
+mod module;
+mod multiple_targets;
+mod newtype;
+mod no_prefix;
+mod object_safety;
+mod prefix;
+mod rename_all;
+mod role_traits;
+mod send_sync;
+mod skip;
+mod strict;
+mod target_paths;
+mod target_rename;
+mod visibility;
+
+fn main() {
+    prefix::demo();
+    no_prefix::demo();
+    target_rename::demo();
+    skip::demo();
+    target_paths::demo();
+    multiple_targets::demo();
+    cfg_impl::demo();
+    visibility::demo();
+    field_delegation::demo();
+    newtype::demo();
+    map_return::demo();
+    map_arg::demo();
+    module::demo();
+    inherent_impls::demo();
+    strict::demo();
+    deref::demo();
+    rename_all::demo();
+    object_safety::demo();
+    extension_traits::demo();
+    role_traits::demo();
+    send_sync::demo();
+    method_docs::demo();
+    feature_gated_impls::demo();
+    dump::demo();
+    method_list::demo();
+}
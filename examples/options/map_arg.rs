@@ -0,0 +1,67 @@
+use depabx::wrap;
+use search::{SearchApi, SearchRequest};
+
+// A vendor API that wants a request builder where we'd rather pass a plain string.
+mod search {
+    pub struct SearchRequest {
+        pub query: String,
+        pub limit: usize,
+    }
+
+    impl SearchRequest {
+        pub fn new(query: &str) -> Self {
+            SearchRequest {
+                query: query.to_string(),
+                limit: 100,
+            }
+        }
+
+        pub fn limit(mut self, limit: usize) -> Self {
+            self.limit = limit;
+            self
+        }
+    }
+
+    pub struct SearchApi;
+
+    impl SearchApi {
+        pub fn search(&self, request: SearchRequest) -> Vec<String> {
+            (0..request.limit.min(2))
+                .map(|i| format!("{} #{i}", request.query))
+                .collect()
+        }
+    }
+}
+
+// `map_arg` replaces an argument with an expression before it's passed on. The expression can use any of the trait
+// method's arguments by name, so this generates `SearchApi::search(self, SearchRequest::new(query).limit(10))`.
+#[wrap(SearchApi)]
+trait AbxSearchApi {
+    #[abx(map_arg(query = "SearchRequest::new(query).limit(10)"))]
+    fn abx_search(&self, query: &str) -> Vec<String>;
+}
+
+pub fn demo() {
+    println!("{:?}", run(&SearchApi {}));
+}
+
+fn run<S: AbxSearchApi>(api: &S) -> Option<String> {
+    api.abx_search("depabx").into_iter().next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxSearchApi};
+
+    #[test]
+    fn it_returns_the_first_result() {
+        struct FakeSearchApi;
+        impl AbxSearchApi for FakeSearchApi {
+            fn abx_search(&self, query: &str) -> Vec<String> {
+                vec![format!("{query}!"), "second".to_string()]
+            }
+        }
+
+        assert_eq!(run(&FakeSearchApi {}), Some("depabx!".to_string()));
+    }
+}
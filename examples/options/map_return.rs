@@ -0,0 +1,77 @@
+use depabx::wrap;
+use users::{UserRecord, UsersApi};
+
+// A vendor API that returns its own wire-format struct.
+mod users {
+    pub struct UserRecord {
+        pub user_name: String,
+        pub is_admin: u8,
+    }
+
+    pub struct UsersApi;
+
+    impl UsersApi {
+        pub fn fetch_user(&self, id: u32) -> UserRecord {
+            UserRecord {
+                user_name: format!("user{id}"),
+                is_admin: (id == 1) as u8,
+            }
+        }
+    }
+}
+
+// The type the rest of our code works with.
+pub struct User {
+    pub name: String,
+    pub admin: bool,
+}
+
+fn to_user(record: UserRecord) -> User {
+    User {
+        name: record.user_name,
+        admin: record.is_admin != 0,
+    }
+}
+
+// `map_return` names a function the concrete return value is passed through before it's returned from the trait
+// method, so the generated body is `to_user(UsersApi::fetch_user(self, id))`. The path is resolved where the trait is
+// defined.
+#[wrap(UsersApi)]
+trait AbxUsersApi {
+    #[abx(target = "fetch_user", map_return = "to_user")]
+    fn abx_user(&self, id: u32) -> User;
+}
+
+pub fn demo() {
+    println!("{}", run(&UsersApi {}, 1));
+}
+
+fn run<U: AbxUsersApi>(api: &U, id: u32) -> String {
+    let user = api.abx_user(id);
+    if user.admin {
+        format!("{} (admin)", user.name)
+    } else {
+        user.name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxUsersApi, User};
+
+    #[test]
+    fn it_marks_admins() {
+        // The fake deals only in our domain type
+        struct FakeUsersApi;
+        impl AbxUsersApi for FakeUsersApi {
+            fn abx_user(&self, id: u32) -> User {
+                User {
+                    name: format!("test{id}"),
+                    admin: true,
+                }
+            }
+        }
+
+        assert_eq!(run(&FakeUsersApi {}, 5), "test5 (admin)");
+    }
+}
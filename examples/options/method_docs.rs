@@ -0,0 +1,54 @@
+use depabx::wrap;
+use payments::PaymentGateway;
+
+// A payment gateway SDK.
+mod payments {
+    pub struct PaymentGateway;
+
+    impl PaymentGateway {
+        pub fn charge_card(&self, cents: u64) -> Result<String, String> {
+            if cents == 0 {
+                Err("nothing to charge".to_string())
+            } else {
+                Ok(format!("ch_{cents}"))
+            }
+        }
+    }
+}
+
+// Doc comments written on a trait method are copied onto the generated impl method, followed by a line linking what
+// it forwards to, e.g. "Delegates to [`PaymentGateway::charge_card`].", so hovering either one explains both.
+#[wrap(PaymentGateway)]
+trait AbxPaymentGateway {
+    /// Charges the customer's default card and returns the charge id.
+    #[abx(target = "charge_card")]
+    fn abx_charge(&self, cents: u64) -> Result<String, String>;
+}
+
+pub fn demo() {
+    println!("{:?}", run(&PaymentGateway {}, 1999));
+}
+
+fn run<G: AbxPaymentGateway>(gateway: &G, cents: u64) -> String {
+    match gateway.abx_charge(cents) {
+        Ok(id) => format!("charged {id}"),
+        Err(e) => format!("declined: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxPaymentGateway};
+
+    #[test]
+    fn it_reports_declines() {
+        struct FakePaymentGateway;
+        impl AbxPaymentGateway for FakePaymentGateway {
+            fn abx_charge(&self, _cents: u64) -> Result<String, String> {
+                Err("card expired".to_string())
+            }
+        }
+
+        assert_eq!(run(&FakePaymentGateway {}, 500), "declined: card expired");
+    }
+}
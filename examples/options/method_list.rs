@@ -0,0 +1,65 @@
+use alerts::Alerter;
+use depabx::wrap;
+
+// An alerting client with a handful of simple methods.
+mod alerts {
+    pub struct Alerter;
+
+    impl Alerter {
+        pub fn warn(&self, message: &str) {
+            println!("WARN {message}");
+        }
+
+        pub fn critical(&self, message: &str) -> bool {
+            println!("CRIT {message}");
+            true
+        }
+    }
+}
+
+// For straightforward wrappers, list the concrete methods instead of restating them. Each entry is the concrete
+// method's name with its signature as a hint, and the macro fills the empty trait with the prefixed declarations
+// (`fn abx_warn(&self, message: &str);` and so on) before generating the impl as usual.
+#[wrap(Alerter, methods(warn(&self, message: &str), critical(&self, message: &str) -> bool))]
+trait AbxAlerter {}
+
+pub fn demo() {
+    println!("{}", run(&Alerter {}, 97));
+}
+
+fn run<A: AbxAlerter>(alerter: &A, disk_percent: u8) -> bool {
+    if disk_percent > 95 {
+        return alerter.abx_critical("disk almost full");
+    }
+    alerter.abx_warn("disk filling up");
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxAlerter};
+    use std::cell::RefCell;
+
+    #[test]
+    fn it_only_warns_below_the_critical_level() {
+        // Fakes implement the generated declarations like any hand-written ones
+        struct FakeAlerter {
+            warnings: RefCell<Vec<String>>,
+        }
+        impl AbxAlerter for FakeAlerter {
+            fn abx_warn(&self, message: &str) {
+                self.warnings.borrow_mut().push(message.to_string());
+            }
+            fn abx_critical(&self, _message: &str) -> bool {
+                panic!("shouldn't be critical");
+            }
+        }
+
+        let alerter = FakeAlerter {
+            warnings: RefCell::new(Vec::new()),
+        };
+
+        assert!(!run(&alerter, 80));
+        assert_eq!(*alerter.warnings.borrow(), vec!["disk filling up"]);
+    }
+}
@@ -0,0 +1,47 @@
+use depabx::wrap;
+use geo::Geocoder;
+
+// A geocoding SDK.
+mod geo {
+    pub struct Geocoder;
+
+    impl Geocoder {
+        pub fn lookup(&self, address: &str) -> (f64, f64) {
+            (address.len() as f64, 0.0)
+        }
+    }
+}
+
+// `module` puts everything the macro generates for this trait in a new inline module of that name, next to the trait.
+// The module imports its parent's items, so paths in the trait keep working, and helper items like the newtype are
+// reached through it.
+#[wrap(Geocoder, newtype = ProdGeocoder, module = abstractions)]
+trait AbxGeocoder {
+    fn abx_lookup(&self, address: &str) -> (f64, f64);
+}
+
+pub fn demo() {
+    println!("{}", run(&abstractions::ProdGeocoder::from(Geocoder {})));
+}
+
+fn run<G: AbxGeocoder>(geocoder: &G) -> String {
+    let (lat, lon) = geocoder.abx_lookup("1 Main St");
+    format!("{lat:.1},{lon:.1}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxGeocoder};
+
+    #[test]
+    fn it_formats_the_coordinates() {
+        struct FakeGeocoder;
+        impl AbxGeocoder for FakeGeocoder {
+            fn abx_lookup(&self, _address: &str) -> (f64, f64) {
+                (51.5, -0.125)
+            }
+        }
+
+        assert_eq!(run(&FakeGeocoder {}), "51.5,-0.1");
+    }
+}
@@ -0,0 +1,67 @@
+use backends::{ConsoleSink, FileSink, SyslogSink};
+use depabx::wrap;
+
+// Several logging backends from different crates. They share method names but no trait.
+mod backends {
+    pub struct ConsoleSink;
+    pub struct FileSink {
+        pub path: String,
+    }
+    pub struct SyslogSink;
+
+    impl ConsoleSink {
+        pub fn write(&self, line: &str) -> String {
+            format!("console: {line}")
+        }
+    }
+
+    impl FileSink {
+        pub fn write(&self, line: &str) -> String {
+            format!("{}: {line}", self.path)
+        }
+    }
+
+    impl SyslogSink {
+        pub fn write(&self, line: &str) -> String {
+            format!("syslog: {line}")
+        }
+    }
+}
+
+// List every type that should implement the trait. Each one gets its own delegating impl, all from a single trait
+// definition. Options, if any, go after the last target.
+#[wrap(ConsoleSink, FileSink, SyslogSink)]
+trait AbxSink {
+    fn abx_write(&self, line: &str) -> String;
+}
+
+pub fn demo() {
+    let file = FileSink {
+        path: "/var/log/app.log".to_string(),
+    };
+    let sinks: [&dyn AbxSink; 3] = [&ConsoleSink {}, &file, &SyslogSink {}];
+    println!("{:?}", run(&sinks));
+}
+
+fn run(sinks: &[&dyn AbxSink]) -> Vec<String> {
+    sinks.iter().map(|sink| sink.abx_write("started")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxSink};
+
+    #[test]
+    fn it_writes_to_every_sink() {
+        struct FakeSink(&'static str);
+        impl AbxSink for FakeSink {
+            fn abx_write(&self, line: &str) -> String {
+                format!("{}<{line}>", self.0)
+            }
+        }
+
+        let lines = run(&[&FakeSink("a"), &FakeSink("b")]);
+
+        assert_eq!(lines, vec!["a<started>", "b<started>"]);
+    }
+}
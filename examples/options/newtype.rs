@@ -0,0 +1,45 @@
+use depabx::wrap;
+use pager::Pager;
+
+// A vendor paging client.
+mod pager {
+    pub struct Pager;
+
+    impl Pager {
+        pub fn page(&self, team: &str, summary: &str) -> String {
+            format!("paged {team}: {summary}")
+        }
+    }
+}
+
+// `newtype` emits `struct ProdPager(Pager);`, as visible as the trait unless `vis` says otherwise, with a
+// `From<Pager>` impl. The trait is implemented on the newtype instead of on the vendor type, which stays untouched.
+#[wrap(Pager, newtype = ProdPager)]
+trait AbxPager {
+    fn abx_page(&self, team: &str, summary: &str) -> String;
+}
+
+pub fn demo() {
+    println!("{}", run(&ProdPager::from(Pager {})));
+}
+
+fn run<P: AbxPager>(pager: &P) -> String {
+    pager.abx_page("payments", "error rate above 5%")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxPager};
+
+    #[test]
+    fn it_pages_the_payments_team() {
+        struct FakePager;
+        impl AbxPager for FakePager {
+            fn abx_page(&self, team: &str, _summary: &str) -> String {
+                team.to_uppercase()
+            }
+        }
+
+        assert_eq!(run(&FakePager {}), "PAYMENTS");
+    }
+}
@@ -0,0 +1,48 @@
+use cache::Cache;
+use depabx::wrap;
+
+// A cache client.
+mod cache {
+    pub struct Cache;
+
+    impl Cache {
+        pub fn lookup(&self, key: &str) -> Option<String> {
+            (key == "greeting").then(|| "hello".to_string())
+        }
+    }
+}
+
+// With an empty prefix the trait mirrors the concrete type's method names exactly, so code reads the same whether it
+// holds a `Cache` or an `impl AbxCache`. The generated impl calls `Cache::lookup(self, key)`, and inherent methods
+// take precedence over trait methods, so it can't recurse into itself.
+#[wrap(Cache, prefix = "")]
+trait AbxCache {
+    fn lookup(&self, key: &str) -> Option<String>;
+}
+
+pub fn demo() {
+    println!("{}", run(&Cache {}));
+}
+
+fn run<C: AbxCache>(cache: &C) -> String {
+    cache
+        .lookup("greeting")
+        .unwrap_or_else(|| "default".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxCache};
+
+    #[test]
+    fn it_falls_back_on_a_cache_miss() {
+        struct FakeCache;
+        impl AbxCache for FakeCache {
+            fn lookup(&self, _key: &str) -> Option<String> {
+                None
+            }
+        }
+
+        assert_eq!(run(&FakeCache {}), "default");
+    }
+}
@@ -0,0 +1,73 @@
+use depabx::wrap;
+use std::fmt::Display;
+use webhook::Webhook;
+
+// A webhook client that's stored as a trait object so the target can be chosen at runtime.
+mod webhook {
+    use std::fmt::Display;
+
+    pub struct Webhook {
+        pub url: String,
+    }
+
+    impl Webhook {
+        pub fn post(&self, payload: &str) -> String {
+            format!("POST {} {payload}", self.url)
+        }
+
+        pub fn post_display<T: Display>(&self, payload: T) -> String {
+            self.post(&payload.to_string())
+        }
+    }
+}
+
+// `dyn` makes the macro check that the trait stays object safe. A generic method, a `Self` return, or anything else
+// that would rule out `dyn AbxWebhook` is reported at that method ("`abx_post_display` makes `AbxWebhook` unusable as
+// `dyn AbxWebhook` because it has generic parameters"), unless it opts out with `where Self: Sized` as below.
+#[wrap(Webhook, dyn)]
+trait AbxWebhook {
+    fn abx_post(&self, payload: &str) -> String;
+    fn abx_post_display<T: Display>(&self, payload: T) -> String
+    where
+        Self: Sized;
+}
+
+pub fn demo() {
+    let hook: Box<dyn AbxWebhook> = Box::new(Webhook {
+        url: "https://hooks.example.com/deploys".to_string(),
+    });
+    println!("{}", run(hook.as_ref()));
+
+    // Methods bound by `Self: Sized` are still there when the type is known
+    let errors = Webhook {
+        url: "https://hooks.example.com/errors".to_string(),
+    };
+    println!("{}", errors.abx_post_display(500));
+}
+
+fn run(hook: &dyn AbxWebhook) -> String {
+    hook.abx_post("{\"status\":\"deployed\"}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxWebhook};
+    use std::fmt::Display;
+
+    #[test]
+    fn it_posts_the_deploy_status() {
+        struct FakeWebhook;
+        impl AbxWebhook for FakeWebhook {
+            fn abx_post(&self, payload: &str) -> String {
+                payload.len().to_string()
+            }
+            fn abx_post_display<T: Display>(&self, payload: T) -> String {
+                payload.to_string()
+            }
+        }
+
+        let hook: Box<dyn AbxWebhook> = Box::new(FakeWebhook {});
+
+        assert_eq!(run(hook.as_ref()), "21");
+    }
+}
@@ -0,0 +1,46 @@
+use depabx::wrap;
+use queue::Queue;
+
+// A message queue client.
+mod queue {
+    pub struct Queue;
+
+    impl Queue {
+        pub fn publish(&self, msg: &str) -> u64 {
+            msg.len() as u64
+        }
+    }
+}
+
+// `abx_` is only the default prefix. Pass `prefix` to use your own; it's stripped from each trait method name to find
+// the concrete method, so `dep_publish` delegates to `Queue::publish`.
+#[wrap(Queue, prefix = "dep_")]
+trait DepQueue {
+    fn dep_publish(&self, msg: &str) -> u64;
+}
+
+pub fn demo() {
+    println!("{}", run(&Queue {}));
+}
+
+fn run<Q: DepQueue>(queue: &Q) -> u64 {
+    queue.dep_publish("order created")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, DepQueue};
+
+    #[test]
+    fn it_publishes_the_order_event() {
+        struct FakeQueue;
+        impl DepQueue for FakeQueue {
+            fn dep_publish(&self, msg: &str) -> u64 {
+                assert_eq!(msg, "order created");
+                99
+            }
+        }
+
+        assert_eq!(run(&FakeQueue {}), 99);
+    }
+}
@@ -0,0 +1,69 @@
+use depabx::wrap;
+use sys::CLogger;
+
+// A C binding that kept the library's naming: every method is PascalCase and starts with the type name.
+mod sys {
+    pub struct CLogger;
+
+    #[allow(non_snake_case)]
+    impl CLogger {
+        pub fn LoggerLogEvent(&self, message: &str) -> i32 {
+            println!("event: {message}");
+            0
+        }
+
+        pub fn LoggerFlush(&self) -> i32 {
+            0
+        }
+
+        pub fn LoggerGetLastErrorCode(&self) -> i32 {
+            -1
+        }
+    }
+}
+
+// `rename_all` converts each trait method name (after the prefix is stripped) before looking up the concrete method.
+// It accepts "PascalCase", "camelCase" and "SCREAMING_SNAKE_CASE", and `target_prefix` is prepended afterwards, so
+// `abx_log_event` resolves to `LoggerLogEvent`. `#[abx(target = "...")]` still overrides the result, for names that
+// don't follow the pattern.
+#[wrap(CLogger, rename_all = "PascalCase", target_prefix = "Logger")]
+trait AbxCLogger {
+    fn abx_log_event(&self, message: &str) -> i32;
+    fn abx_flush(&self) -> i32;
+    #[abx(target = "LoggerGetLastErrorCode")]
+    fn abx_last_error(&self) -> i32;
+}
+
+pub fn demo() {
+    println!("{:?}", run(&CLogger {}));
+}
+
+fn run<L: AbxCLogger>(logger: &L) -> Result<(), i32> {
+    if logger.abx_log_event("started") != 0 || logger.abx_flush() != 0 {
+        return Err(logger.abx_last_error());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxCLogger};
+
+    #[test]
+    fn it_reports_the_last_error_when_flushing_fails() {
+        struct FakeCLogger;
+        impl AbxCLogger for FakeCLogger {
+            fn abx_log_event(&self, _message: &str) -> i32 {
+                0
+            }
+            fn abx_flush(&self) -> i32 {
+                5
+            }
+            fn abx_last_error(&self) -> i32 {
+                42
+            }
+        }
+
+        assert_eq!(run(&FakeCLogger {}), Err(42));
+    }
+}
@@ -0,0 +1,88 @@
+use blob::BlobClient;
+use depabx::wrap;
+
+// A big vendor client that does a bit of everything.
+mod blob {
+    use std::cell::RefCell;
+
+    pub struct BlobClient {
+        pub blobs: RefCell<Vec<String>>,
+    }
+
+    impl BlobClient {
+        pub fn read(&self, index: usize) -> Option<String> {
+            self.blobs.borrow().get(index).cloned()
+        }
+
+        pub fn write(&self, blob: &str) -> usize {
+            self.blobs.borrow_mut().push(blob.to_string());
+            self.blobs.borrow().len() - 1
+        }
+
+        pub fn delete_all(&self) {
+            self.blobs.borrow_mut().clear();
+        }
+    }
+}
+
+// Each `role` trait covers one slice of the client, so code can ask for exactly the capability it uses. Every role
+// registers the methods it declares for its target, so two roles in the same module declaring the same method name
+// (`abx_write`, say) fail the build at both traits, instead of leaving callers with ambiguous calls.
+#[wrap(BlobClient, role)]
+trait AbxBlobReader {
+    fn abx_read(&self, index: usize) -> Option<String>;
+}
+
+#[wrap(BlobClient, role)]
+trait AbxBlobWriter {
+    fn abx_write(&self, blob: &str) -> usize;
+}
+
+#[wrap(BlobClient, role)]
+trait AbxBlobAdmin {
+    fn abx_delete_all(&self);
+}
+
+pub fn demo() {
+    let client = BlobClient {
+        blobs: std::cell::RefCell::new(Vec::new()),
+    };
+    println!("{:?}", run(&client, &client));
+    client.abx_delete_all();
+}
+
+// Only needs reading and writing, so a test never has to fake `abx_delete_all`
+fn run<R: AbxBlobReader, W: AbxBlobWriter>(reader: &R, writer: &W) -> Option<String> {
+    let index = writer.abx_write("hello");
+    reader.abx_read(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxBlobReader, AbxBlobWriter};
+    use std::cell::RefCell;
+
+    #[test]
+    fn it_reads_back_what_it_wrote() {
+        struct FakeBlobs {
+            written: RefCell<Vec<String>>,
+        }
+        impl AbxBlobReader for FakeBlobs {
+            fn abx_read(&self, index: usize) -> Option<String> {
+                self.written.borrow().get(index).cloned()
+            }
+        }
+        impl AbxBlobWriter for FakeBlobs {
+            fn abx_write(&self, blob: &str) -> usize {
+                self.written.borrow_mut().push(blob.to_uppercase());
+                0
+            }
+        }
+
+        let blobs = FakeBlobs {
+            written: RefCell::new(Vec::new()),
+        };
+
+        assert_eq!(run(&blobs, &blobs), Some("HELLO".to_string()));
+    }
+}
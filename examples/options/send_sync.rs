@@ -0,0 +1,68 @@
+use depabx::wrap;
+use geoip::GeoIp;
+use std::thread;
+
+// A lookup client with no interior mutability, so it's safe to use from many threads at once.
+mod geoip {
+    pub struct GeoIp;
+
+    impl GeoIp {
+        pub fn country(&self, ip: &str) -> &'static str {
+            if ip.starts_with("10.") {
+                "internal"
+            } else {
+                "unknown"
+            }
+        }
+    }
+}
+
+// `send_sync` adds `Send + Sync` to the trait's supertraits and asserts that the wrapped type implements both, so
+// multithreaded callers don't have to repeat the bounds everywhere. A type that isn't thread safe fails the build at
+// this attribute with the offending auto trait named.
+#[wrap(GeoIp, send_sync)]
+trait AbxGeoIp {
+    fn abx_country(&self, ip: &str) -> &'static str;
+}
+
+pub fn demo() {
+    println!("{:?}", run(&GeoIp {}, &["10.0.0.1", "8.8.8.8"]));
+}
+
+// Looks up every address on its own thread. Only `AbxGeoIp` is needed as a bound.
+fn run<G: AbxGeoIp>(geoip: &G, ips: &[&str]) -> Vec<&'static str> {
+    thread::scope(|scope| {
+        let lookups: Vec<_> = ips
+            .iter()
+            .map(|ip| scope.spawn(move || geoip.abx_country(ip)))
+            .collect();
+        lookups.into_iter().map(|l| l.join().unwrap()).collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxGeoIp};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn it_looks_up_every_address() {
+        // The fake has to be thread safe too, so it counts with an atomic
+        struct FakeGeoIp {
+            lookups: AtomicUsize,
+        }
+        impl AbxGeoIp for FakeGeoIp {
+            fn abx_country(&self, _ip: &str) -> &'static str {
+                self.lookups.fetch_add(1, Ordering::SeqCst);
+                "test"
+            }
+        }
+
+        let geoip = FakeGeoIp {
+            lookups: AtomicUsize::new(0),
+        };
+
+        assert_eq!(run(&geoip, &["a", "b", "c"]), vec!["test"; 3]);
+        assert_eq!(geoip.lookups.load(Ordering::SeqCst), 3);
+    }
+}
@@ -0,0 +1,55 @@
+use depabx::wrap;
+use std::time::Duration;
+use timer::Stopwatch;
+
+// A stopwatch that only knows how to report raw milliseconds.
+mod timer {
+    pub struct Stopwatch {
+        pub started_ms: u64,
+    }
+
+    impl Stopwatch {
+        pub fn elapsed_ms(&self, now_ms: u64) -> u64 {
+            now_ms - self.started_ms
+        }
+    }
+}
+
+// `#[abx(skip)]` tells the macro a method isn't part of the dependency. It's never delegated, and it's left out of
+// anything else the macro generates for the trait, so helpers that don't exist on `Stopwatch` can live here. A
+// skipped method needs a default body, since the generated impl won't provide one.
+#[wrap(Stopwatch)]
+trait AbxStopwatch {
+    fn abx_elapsed_ms(&self, now_ms: u64) -> u64;
+
+    #[abx(skip)]
+    fn abx_elapsed(&self, now_ms: u64) -> Duration {
+        Duration::from_millis(self.abx_elapsed_ms(now_ms))
+    }
+}
+
+pub fn demo() {
+    println!("{}", run(&Stopwatch { started_ms: 1_000 }, 4_500));
+}
+
+fn run<S: AbxStopwatch>(stopwatch: &S, now_ms: u64) -> String {
+    format!("took {:?}", stopwatch.abx_elapsed(now_ms))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxStopwatch};
+
+    #[test]
+    fn it_formats_the_elapsed_duration() {
+        // The skipped helper comes along for free
+        struct FakeStopwatch;
+        impl AbxStopwatch for FakeStopwatch {
+            fn abx_elapsed_ms(&self, _now_ms: u64) -> u64 {
+                1_500
+            }
+        }
+
+        assert_eq!(run(&FakeStopwatch {}, 0), "took 1.5s");
+    }
+}
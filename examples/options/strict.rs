@@ -0,0 +1,62 @@
+use depabx::wrap;
+use ledger::Ledger;
+
+// A ledger API. Amounts are in cents, and labels are owned strings.
+mod ledger {
+    pub struct Ledger;
+
+    impl Ledger {
+        pub fn credit(&self, cents: u64) -> u64 {
+            cents
+        }
+
+        pub fn label(&self, text: String) -> String {
+            format!("[{text}]")
+        }
+    }
+}
+
+// In `strict` mode every trait signature has to match its concrete method exactly. The macro checks this with a
+// function pointer coercion per method, and rejects adapters like `#[abx(into)]`, so the trait stays a faithful
+// mirror of the vendor API.
+#[wrap(Ledger, strict)]
+trait AbxLedger {
+    fn abx_credit(&self, cents: u64) -> u64;
+}
+
+// Without `strict` (the default), signatures only need to line up after the adapters are applied.
+#[wrap(Ledger)]
+trait AbxLabels {
+    fn abx_label(&self, #[abx(into)] text: &str) -> String;
+}
+
+pub fn demo() {
+    println!("{}", run(&Ledger {}, &Ledger {}));
+}
+
+fn run<L: AbxLedger, T: AbxLabels>(ledger: &L, labels: &T) -> String {
+    let total = ledger.abx_credit(250) + ledger.abx_credit(100);
+    labels.abx_label(&format!("total {total}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxLabels, AbxLedger};
+
+    #[test]
+    fn it_labels_the_total_credit() {
+        struct FakeLedger;
+        impl AbxLedger for FakeLedger {
+            fn abx_credit(&self, cents: u64) -> u64 {
+                cents * 2
+            }
+        }
+        impl AbxLabels for FakeLedger {
+            fn abx_label(&self, text: &str) -> String {
+                text.to_string()
+            }
+        }
+
+        assert_eq!(run(&FakeLedger {}, &FakeLedger {}), "total 700");
+    }
+}
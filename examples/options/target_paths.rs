@@ -0,0 +1,92 @@
+use depabx::wrap;
+
+// A dependency nested a few modules deep, with a re-export and a type alias on top, like many SDK crates.
+mod sdk {
+    pub mod v2 {
+        pub mod client {
+            pub struct Client<R> {
+                pub region: R,
+            }
+
+            impl<R: std::fmt::Display> Client<R> {
+                pub fn endpoint(&self) -> String {
+                    format!("https://{}.example.com", self.region)
+                }
+            }
+        }
+    }
+
+    pub use v2::client::Client;
+
+    pub type DefaultClient = Client<&'static str>;
+}
+
+// The target can be any type path: fully qualified, through a re-export, with generic arguments, or a type alias.
+// It's used verbatim as the self type of the generated impl, so it resolves exactly like a path you'd write yourself.
+#[wrap(crate::target_paths::sdk::v2::client::Client<String>)]
+trait AbxClient {
+    fn abx_endpoint(&self) -> String;
+}
+
+#[wrap(sdk::DefaultClient)]
+trait AbxDefaultClient {
+    fn abx_endpoint(&self) -> String;
+}
+
+// Paths starting with `::` name another crate, which also covers crates renamed in Cargo.toml.
+#[wrap(::std::collections::HashMap<String, u32>)]
+trait AbxCounts {
+    fn abx_get(&self, key: &str) -> Option<&u32>;
+}
+
+pub fn demo() {
+    let client = sdk::Client {
+        region: "eu-west-1".to_string(),
+    };
+    let default_client = sdk::DefaultClient {
+        region: "us-east-1",
+    };
+    let counts = std::collections::HashMap::from([("retries".to_string(), 2)]);
+    println!("{:?}", run(&client, &default_client, &counts));
+}
+
+fn run<C: AbxClient, D: AbxDefaultClient, N: AbxCounts>(
+    client: &C,
+    fallback: &D,
+    counts: &N,
+) -> Vec<String> {
+    let retries = counts.abx_get("retries").copied().unwrap_or(0);
+    let mut endpoints = vec![client.abx_endpoint()];
+    endpoints.extend((0..retries).map(|_| fallback.abx_endpoint()));
+    endpoints
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxClient, AbxCounts, AbxDefaultClient};
+
+    #[test]
+    fn it_adds_a_fallback_endpoint_per_retry() {
+        struct FakeClient;
+        impl AbxClient for FakeClient {
+            fn abx_endpoint(&self) -> String {
+                "primary".to_string()
+            }
+        }
+        impl AbxDefaultClient for FakeClient {
+            fn abx_endpoint(&self) -> String {
+                "fallback".to_string()
+            }
+        }
+        struct FakeCounts;
+        impl AbxCounts for FakeCounts {
+            fn abx_get(&self, _key: &str) -> Option<&u32> {
+                Some(&1)
+            }
+        }
+
+        let endpoints = run(&FakeClient {}, &FakeClient {}, &FakeCounts {});
+
+        assert_eq!(endpoints, vec!["primary", "fallback"]);
+    }
+}
@@ -0,0 +1,57 @@
+use depabx::wrap;
+use s3::ObjectStore;
+
+// An object storage SDK with verbose method names.
+mod s3 {
+    pub struct ObjectStore;
+
+    impl ObjectStore {
+        pub fn upload_object(&self, key: &str, body: &[u8]) -> String {
+            format!("{key} ({} bytes)", body.len())
+        }
+
+        pub fn download_object(&self, key: &str) -> Vec<u8> {
+            key.as_bytes().to_vec()
+        }
+    }
+}
+
+// `#[abx(target = "...")]` names the concrete method to delegate to, overriding the name derived from the trait
+// method. The trait gets to use the vocabulary of the code that calls it.
+#[wrap(ObjectStore)]
+trait AbxBlobs {
+    #[abx(target = "upload_object")]
+    fn abx_put(&self, key: &str, body: &[u8]) -> String;
+    #[abx(target = "download_object")]
+    fn abx_get(&self, key: &str) -> Vec<u8>;
+}
+
+pub fn demo() {
+    println!("{}", run(&ObjectStore {}));
+}
+
+// Copies the backup to a dated key
+fn run<B: AbxBlobs>(blobs: &B) -> String {
+    let body = blobs.abx_get("backup/latest");
+    blobs.abx_put("backup/2026-10-14", &body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxBlobs};
+
+    #[test]
+    fn it_copies_the_latest_backup() {
+        struct FakeBlobs;
+        impl AbxBlobs for FakeBlobs {
+            fn abx_put(&self, key: &str, body: &[u8]) -> String {
+                format!("{key}={}", String::from_utf8_lossy(body))
+            }
+            fn abx_get(&self, _key: &str) -> Vec<u8> {
+                b"data".to_vec()
+            }
+        }
+
+        assert_eq!(run(&FakeBlobs {}), "backup/2026-10-14=data");
+    }
+}
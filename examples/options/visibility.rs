@@ -0,0 +1,46 @@
+use audit::AuditTrail;
+use depabx::wrap;
+
+// An audit trail client.
+mod audit {
+    pub struct AuditTrail;
+
+    impl AuditTrail {
+        pub fn record(&self, actor: &str, action: &str) -> String {
+            format!("{actor} {action}")
+        }
+    }
+}
+
+// By default, the named items the macro generates next to the impl, like newtypes or fakes when those are asked for,
+// have the same visibility as the trait. `vis` picks a different one, and `doc(hidden)` keeps the impls and
+// everything generated with them out of rustdoc, so a public trait doesn't drag its glue into the public API.
+#[wrap(AuditTrail, vis = "pub(crate)", doc(hidden))]
+pub trait AbxAuditTrail: Send + Sync {
+    fn abx_record(&self, actor: &str, action: &str) -> String;
+}
+
+pub fn demo() {
+    println!("{}", run(&AuditTrail {}, "alice"));
+}
+
+fn run<A: AbxAuditTrail>(audit: &A, user: &str) -> String {
+    audit.abx_record(user, "signed in")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxAuditTrail};
+
+    #[test]
+    fn it_records_the_sign_in() {
+        struct FakeAuditTrail;
+        impl AbxAuditTrail for FakeAuditTrail {
+            fn abx_record(&self, actor: &str, action: &str) -> String {
+                format!("[{actor}|{action}]")
+            }
+        }
+
+        assert_eq!(run(&FakeAuditTrail {}, "bob"), "[bob|signed in]");
+    }
+}
@@ -0,0 +1,62 @@
+use depabx::wrap;
+use upload::Uploader;
+
+// An uploader with a documented limit on how many items fit in one request.
+mod upload {
+    pub struct Uploader;
+
+    impl Uploader {
+        pub const MAX_BATCH: usize = 3;
+
+        pub fn upload(&self, items: &[String]) -> usize {
+            items.len()
+        }
+    }
+}
+
+// Associated consts are bound the same way as associated types. The value can be any const expression, including a
+// constant on the concrete type, so the limit below always matches the vendor's.
+#[wrap(Uploader)]
+#[abx(const MAX_BATCH = Uploader::MAX_BATCH)]
+#[abx(const NAME = "uploader")]
+trait AbxUploader {
+    const MAX_BATCH: usize;
+    const NAME: &'static str;
+    fn abx_upload(&self, items: &[String]) -> usize;
+}
+
+pub fn demo() {
+    let items: Vec<String> = (1..=7).map(|i| format!("item {i}")).collect();
+    println!("{:?}", run(&Uploader {}, &items));
+}
+
+// Uploads in batches no larger than the uploader allows, returning how many items went up in each
+fn run<U: AbxUploader>(uploader: &U, items: &[String]) -> Vec<usize> {
+    println!("Uploading with {}", U::NAME);
+    items
+        .chunks(U::MAX_BATCH)
+        .map(|batch| uploader.abx_upload(batch))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxUploader};
+
+    #[test]
+    fn it_respects_the_batch_limit() {
+        // Picking a tiny limit in the fake makes the batching easy to see
+        struct FakeUploader;
+        impl AbxUploader for FakeUploader {
+            const MAX_BATCH: usize = 2;
+            const NAME: &'static str = "fake";
+            fn abx_upload(&self, items: &[String]) -> usize {
+                items.len()
+            }
+        }
+
+        let items = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+
+        assert_eq!(run(&FakeUploader {}, &items), vec![2, 1]);
+    }
+}
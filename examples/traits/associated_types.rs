@@ -0,0 +1,64 @@
+use depabx::wrap;
+use search::{Hits, SearchEngine};
+
+// A search engine with its own result type. `Hits` is iterable, but otherwise we don't care what it is.
+mod search {
+    pub struct Hits {
+        pub titles: Vec<String>,
+    }
+
+    impl IntoIterator for Hits {
+        type Item = String;
+        type IntoIter = std::vec::IntoIter<String>;
+
+        fn into_iter(self) -> Self::IntoIter {
+            self.titles.into_iter()
+        }
+    }
+
+    pub struct SearchEngine;
+
+    impl SearchEngine {
+        pub fn search(&self, query: &str) -> Hits {
+            Hits {
+                titles: vec![format!("{query} guide"), format!("{query} FAQ")],
+            }
+        }
+    }
+}
+
+// Associated types let the trait talk about the dependency's types without naming them. `#[abx(type ... = ...)]`
+// binds each one for the wrapped type, generating `type Output = Hits;` in the impl.
+#[wrap(SearchEngine)]
+#[abx(type Output = Hits)]
+trait AbxSearchEngine {
+    type Output: IntoIterator<Item = String>;
+    fn abx_search(&self, query: &str) -> Self::Output;
+}
+
+pub fn demo() {
+    println!("{:?}", run(&SearchEngine {}));
+}
+
+fn run<S: AbxSearchEngine>(engine: &S) -> Option<String> {
+    engine.abx_search("rust").into_iter().next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxSearchEngine};
+
+    #[test]
+    fn it_returns_the_top_hit() {
+        // The fake picks the simplest type that fits the bound
+        struct FakeSearchEngine;
+        impl AbxSearchEngine for FakeSearchEngine {
+            type Output = Vec<String>;
+            fn abx_search(&self, query: &str) -> Vec<String> {
+                vec![format!("top {query}")]
+            }
+        }
+
+        assert_eq!(run(&FakeSearchEngine {}), Some("top rust".to_string()));
+    }
+}
@@ -0,0 +1,60 @@
+use depabx::wrap;
+use std::fmt::Display;
+use telemetry::Telemetry;
+
+// A telemetry client with a single low-level method.
+mod telemetry {
+    pub struct Telemetry;
+
+    impl Telemetry {
+        pub fn emit(&self, level: &str, message: String) {
+            println!("{level}: {message}");
+        }
+    }
+}
+
+// Methods with a default body are left alone: the macro only delegates the methods without one. That makes the body
+// a convenient place for helpers built on the wrapped methods, and fakes get them for free.
+#[wrap(Telemetry)]
+trait AbxTelemetry {
+    fn abx_emit(&self, level: &str, message: String);
+
+    fn abx_warn<T: Display>(&self, message: T) {
+        self.abx_emit("WARN", message.to_string());
+    }
+}
+
+pub fn demo() {
+    run(&Telemetry {});
+}
+
+fn run<T: AbxTelemetry>(telemetry: &T) {
+    telemetry.abx_warn("Disk is 90% full");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxTelemetry};
+    use std::cell::RefCell;
+
+    #[test]
+    fn it_warns_through_the_emit_method() {
+        // Only the method without a default body needs implementing
+        struct FakeTelemetry {
+            emitted: RefCell<Vec<String>>,
+        }
+        impl AbxTelemetry for FakeTelemetry {
+            fn abx_emit(&self, level: &str, message: String) {
+                self.emitted.borrow_mut().push(format!("{level} {message}"));
+            }
+        }
+
+        let telemetry = FakeTelemetry {
+            emitted: RefCell::new(Vec::new()),
+        };
+
+        run(&telemetry);
+
+        assert_eq!(*telemetry.emitted.borrow(), vec!["WARN Disk is 90% full"]);
+    }
+}
@@ -0,0 +1,88 @@
+use depabx::wrap;
+use hyperlike::{Client, HttpsConnector, Pool};
+use std::net::TcpStream;
+
+// A generic client parameterized by its transport, as HTTP and database crates often are.
+mod hyperlike {
+    pub struct HttpsConnector;
+
+    pub struct Client<C> {
+        connector: C,
+    }
+
+    impl<C> Client<C> {
+        pub fn new(connector: C) -> Self {
+            Client { connector }
+        }
+
+        pub fn get(&self, url: &str) -> String {
+            format!(
+                "GET {url} over {}",
+                std::any::type_name_of_val(&self.connector)
+            )
+        }
+    }
+
+    pub struct Pool<C, const N: usize> {
+        open: Vec<C>,
+    }
+
+    impl<C, const N: usize> Pool<C, N> {
+        pub fn new() -> Self {
+            Pool { open: Vec::new() }
+        }
+
+        pub fn idle(&self) -> usize {
+            N - self.open.len()
+        }
+    }
+}
+
+// Generic arguments on the wrap target pick the instantiation the impl is generated for:
+// `impl AbxClient for Client<HttpsConnector>`. Any number of arguments is accepted, including const arguments.
+#[wrap(Client<HttpsConnector>)]
+trait AbxClient {
+    fn abx_get(&self, url: &str) -> String;
+}
+
+// Turbofish paths work too, in case the target is copied from an expression.
+#[wrap(Pool::<TcpStream, 4>)]
+trait AbxPool {
+    fn abx_idle(&self) -> usize;
+}
+
+pub fn demo() {
+    let client = Client::new(HttpsConnector);
+    let pool = Pool::<TcpStream, 4>::new();
+    println!("{}", run(&client, &pool));
+}
+
+fn run<C: AbxClient, P: AbxPool>(client: &C, pool: &P) -> String {
+    if pool.abx_idle() == 0 {
+        return "no idle connections".to_string();
+    }
+    client.abx_get("https://example.com/status")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxClient, AbxPool};
+
+    #[test]
+    fn it_skips_the_request_without_idle_connections() {
+        struct FakeClient;
+        impl AbxClient for FakeClient {
+            fn abx_get(&self, _url: &str) -> String {
+                panic!("shouldn't make a request");
+            }
+        }
+        struct FakePool;
+        impl AbxPool for FakePool {
+            fn abx_idle(&self) -> usize {
+                0
+            }
+        }
+
+        assert_eq!(run(&FakeClient {}, &FakePool {}), "no idle connections");
+    }
+}
@@ -0,0 +1,75 @@
+use depabx::wrap;
+use kv::Store;
+
+// A typed key-value client. It's generic over its key and value types.
+mod kv {
+    use std::{cell::RefCell, collections::HashMap, hash::Hash};
+
+    pub struct Store<K, V> {
+        entries: RefCell<HashMap<K, V>>,
+    }
+
+    impl<K: Eq + Hash, V: Clone> Store<K, V> {
+        pub fn new() -> Self {
+            Store {
+                entries: RefCell::new(HashMap::new()),
+            }
+        }
+
+        pub fn get(&self, key: &K) -> Option<V> {
+            self.entries.borrow().get(key).cloned()
+        }
+
+        pub fn set(&self, key: K, value: V) {
+            self.entries.borrow_mut().insert(key, value);
+        }
+    }
+}
+
+// The trait can be generic too. The wrap target names the instantiation to implement it for, and the trait's
+// parameters are filled in from it: this generates `impl AbxStore<String, u64> for Store<String, u64>`.
+#[wrap(Store<String, u64>)]
+trait AbxStore<K, V> {
+    fn abx_get(&self, key: &K) -> Option<V>;
+    fn abx_set(&self, key: K, value: V);
+}
+
+pub fn demo() {
+    println!("{}", run(&Store::new()));
+}
+
+// Counts visits, so it only needs a store of that one shape
+fn run<S: AbxStore<String, u64>>(store: &S) -> u64 {
+    let key = "visits".to_string();
+    let visits = store.abx_get(&key).unwrap_or(0) + 1;
+    store.abx_set(key, visits);
+    visits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxStore};
+    use std::cell::RefCell;
+
+    #[test]
+    fn it_increments_the_stored_visits() {
+        struct FakeStore {
+            saved: RefCell<Option<(String, u64)>>,
+        }
+        impl AbxStore<String, u64> for FakeStore {
+            fn abx_get(&self, _key: &String) -> Option<u64> {
+                Some(41)
+            }
+            fn abx_set(&self, key: String, value: u64) {
+                *self.saved.borrow_mut() = Some((key, value));
+            }
+        }
+
+        let store = FakeStore {
+            saved: RefCell::new(None),
+        };
+
+        assert_eq!(run(&store), 42);
+        assert_eq!(*store.saved.borrow(), Some(("visits".to_string(), 42)));
+    }
+}
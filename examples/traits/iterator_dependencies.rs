@@ -0,0 +1,65 @@
+use depabx::wrap;
+use driver::{Cursor, Row};
+
+// A database cursor whose whole interface is `Iterator`; rows are pulled from it one at a time.
+mod driver {
+    pub struct Row {
+        pub amount: i64,
+    }
+
+    pub struct Cursor {
+        pub remaining: i64,
+    }
+
+    impl Iterator for Cursor {
+        type Item = Row;
+
+        fn next(&mut self) -> Option<Row> {
+            if self.remaining == 0 {
+                return None;
+            }
+            self.remaining -= 1;
+            Some(Row {
+                amount: self.remaining * 10,
+            })
+        }
+    }
+}
+
+// There's no inherent method to delegate to here, so mark the method `#[abx(iter)]`. The generated impl goes through
+// the concrete type's iterator impl instead, boxing `IntoIterator::into_iter(self)`. That works both for types that
+// are iterators (through `&mut self`) and for collections where `&T: IntoIterator` (through `&self`).
+#[wrap(Cursor)]
+trait AbxCursor {
+    #[abx(iter)]
+    fn abx_iter(&mut self) -> Box<dyn Iterator<Item = Row> + '_>;
+}
+
+pub fn demo() {
+    println!("{}", run(&mut Cursor { remaining: 3 }));
+}
+
+fn run<C: AbxCursor>(cursor: &mut C) -> i64 {
+    cursor.abx_iter().map(|row| row.amount).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxCursor, Row};
+
+    #[test]
+    fn it_sums_every_row() {
+        // Faking a cursor takes nothing more than a Vec of rows
+        struct FakeCursor(Vec<Row>);
+        impl AbxCursor for FakeCursor {
+            fn abx_iter(&mut self) -> Box<dyn Iterator<Item = Row> + '_> {
+                Box::new(self.0.drain(..))
+            }
+        }
+
+        let mut cursor = FakeCursor(vec![Row { amount: 5 }, Row { amount: -2 }]);
+
+        assert_eq!(run(&mut cursor), 3);
+        assert!(cursor.0.is_empty());
+    }
+}
@@ -0,0 +1,84 @@
+use db::{Connection, Transaction};
+use depabx::wrap;
+
+// A database driver whose transactions borrow the connection they were started on.
+mod db {
+    use std::cell::RefCell;
+
+    pub struct Connection {
+        pub log: RefCell<Vec<String>>,
+    }
+
+    pub struct Transaction<'conn> {
+        conn: &'conn Connection,
+    }
+
+    impl Connection {
+        pub fn begin(&self) -> Transaction<'_> {
+            Transaction { conn: self }
+        }
+    }
+
+    impl Transaction<'_> {
+        pub fn execute(&self, sql: &str) {
+            self.conn.log.borrow_mut().push(sql.to_string());
+        }
+
+        pub fn commit(self) -> usize {
+            self.conn.log.borrow().len()
+        }
+    }
+}
+
+// Lifetime parameters on the wrap target become parameters of the generated impl, so this expands to
+// `impl<'conn> AbxTransaction for Transaction<'conn>`.
+#[wrap(Transaction<'conn>)]
+trait AbxTransaction {
+    fn abx_execute(&self, sql: &str);
+    fn abx_commit(self) -> usize;
+}
+
+pub fn demo() {
+    let conn = Connection {
+        log: std::cell::RefCell::new(Vec::new()),
+    };
+    println!("{}", run(conn.begin()));
+}
+
+fn run<T: AbxTransaction>(tx: T) -> usize {
+    tx.abx_execute("UPDATE accounts SET balance = balance - 10 WHERE id = 1");
+    tx.abx_execute("UPDATE accounts SET balance = balance + 10 WHERE id = 2");
+    tx.abx_commit()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxTransaction};
+    use std::cell::RefCell;
+
+    #[test]
+    fn it_runs_both_updates_in_one_transaction() {
+        // Fakes can borrow their state too, just like the real transaction borrows its connection
+        struct FakeTransaction<'a> {
+            statements: &'a RefCell<Vec<String>>,
+        }
+        impl AbxTransaction for FakeTransaction<'_> {
+            fn abx_execute(&self, sql: &str) {
+                self.statements.borrow_mut().push(sql.to_string());
+            }
+            fn abx_commit(self) -> usize {
+                self.statements.borrow().len()
+            }
+        }
+
+        let statements = RefCell::new(Vec::new());
+
+        assert_eq!(
+            run(FakeTransaction {
+                statements: &statements
+            }),
+            2
+        );
+        assert!(statements.borrow()[0].contains("balance - 10"));
+    }
+}
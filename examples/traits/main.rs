@@ -0,0 +1,24 @@
+// Examples of Abx traits that are more than a list of methods: generic traits, associated items, supertraits, and
+// traits over generic concrete types.
+//
+// Each module's `demo` wires up its real dependency the way `main` does in the sample, and its tests run with
+// `cargo test`.
+mod associated_consts;
+mod associated_types;
+mod default_methods;
+mod generic_targets;
+mod generic_traits;
+mod iterator_dependencies;
+mod lifetime_targets;
+mod supertraits;
+
+fn main() {
+    generic_traits::demo();
+    associated_types::demo();
+    associated_consts::demo();
+    default_methods::demo();
+    generic_targets::demo();
+    lifetime_targets::demo();
+    supertraits::demo();
+    iterator_dependencies::demo();
+}
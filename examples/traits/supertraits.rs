@@ -0,0 +1,72 @@
+use depabx::wrap;
+use metrics::MetricsHandle;
+use std::thread;
+
+// A cheap, cloneable handle to a metrics backend that is safe to share across threads.
+mod metrics {
+    #[derive(Clone)]
+    pub struct MetricsHandle {
+        pub prefix: String,
+    }
+
+    impl MetricsHandle {
+        pub fn increment(&self, name: &str) {
+            println!("{}.{name} += 1", self.prefix);
+        }
+    }
+}
+
+// Supertraits are kept on the trait, and the macro also emits a compile-time assertion that the wrapped type
+// implements each of them. If `MetricsHandle` stopped being `Clone`, the build would fail at this attribute with
+// "`MetricsHandle` must implement `Clone` to be wrapped by `AbxMetrics`" rather than deep inside the generated impl.
+#[wrap(MetricsHandle)]
+trait AbxMetrics: Clone + Send + Sync + 'static {
+    fn abx_increment(&self, name: &str);
+}
+
+pub fn demo() {
+    run(&MetricsHandle {
+        prefix: "app".to_string(),
+    });
+}
+
+// Every worker thread gets its own clone of the handle
+fn run<M: AbxMetrics>(metrics: &M) {
+    let workers: Vec<_> = (0..3)
+        .map(|_| {
+            let metrics = metrics.clone();
+            thread::spawn(move || metrics.abx_increment("jobs_done"))
+        })
+        .collect();
+    for worker in workers {
+        worker.join().unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, AbxMetrics};
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn it_counts_a_job_per_worker() {
+        // To satisfy the supertraits, the fake shares its state through an Arc<Mutex<_>>
+        #[derive(Clone)]
+        struct FakeMetrics {
+            counted: Arc<Mutex<Vec<String>>>,
+        }
+        impl AbxMetrics for FakeMetrics {
+            fn abx_increment(&self, name: &str) {
+                self.counted.lock().unwrap().push(name.to_string());
+            }
+        }
+
+        let metrics = FakeMetrics {
+            counted: Arc::new(Mutex::new(Vec::new())),
+        };
+
+        run(&metrics);
+
+        assert_eq!(*metrics.counted.lock().unwrap(), vec!["jobs_done"; 3]);
+    }
+}
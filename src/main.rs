@@ -73,7 +73,7 @@ mod tests {
         }
         impl FakeLogger {
             fn get_logs(&self) -> Vec<String> {
-                self.logs.borrow().iter().map(|s| s.clone()).collect()
+                self.logs.borrow().iter().cloned().collect()
             }
         }
 